@@ -58,7 +58,7 @@
 use anchor_lang::prelude::*;
 
 /// Errors specific to the Pythagorean bonding curve
-#[error_code]
+#[error_code(offset = 7800)]
 pub enum AmmError {
     #[msg("Invalid reserves: must be positive")]
     InvalidReserves,
@@ -74,6 +74,8 @@ pub enum AmmError {
     InsufficientTokens,
     #[msg("No tokens to mint")]
     NoTokensToMint,
+    #[msg("Outcome token supply cap reached")]
+    SupplyCapReached,
 }
 
 /// Precision scale factor to prevent overflow while maintaining accuracy
@@ -86,6 +88,18 @@ const PRECISION_SCALE: u128 = 1_000;
 pub struct PythagoreanCurve;
 
 impl PythagoreanCurve {
+    /// Largest outcome supply the curve accepts
+    ///
+    /// At 2^63 per side, YES² + NO² ≤ 2^127 still fits in u128 without scaling.
+    pub const MAX_SUPPLY: u64 = 1 << 63;
+
+    /// Reject a trade that would push an outcome supply past `MAX_SUPPLY`
+    pub fn check_supply_cap(current_supply: u64, minted: u64) -> Result<()> {
+        let new_supply = current_supply.checked_add(minted).ok_or(AmmError::SupplyCapReached)?;
+        require!(new_supply <= Self::MAX_SUPPLY, AmmError::SupplyCapReached);
+        Ok(())
+    }
+
     /// Calculate tokens to mint when adding collateral (buying tokens)
     ///
     /// Formula: new_YES = √(new_R² - NO²), tokens = new_YES - old_YES
@@ -205,8 +219,107 @@ impl PythagoreanCurve {
         Ok(scaled_result as u64)
     }
 
+    /// Collateral released when burning `pairs` of both YES and NO (merge)
+    ///
+    /// Formula: new_R = √((A - n)² + (B - n)²), collateral = R - new_R
+    ///
+    /// Computed without `PRECISION_SCALE` and with `new_R` rounded up, so the
+    /// pool always keeps at least what the invariant requires.
+    pub fn get_pair_release(reserves: u64, yes_supply: u64, no_supply: u64, pairs: u64) -> Result<u64> {
+        require!(pairs > 0, AmmError::InvalidReserves);
+        require!(pairs <= yes_supply && pairs <= no_supply, AmmError::InsufficientTokens);
+
+        let new_r = invariant_reserves_ceil(yes_supply - pairs, no_supply - pairs)?;
+        Ok((reserves as u128).saturating_sub(new_r) as u64)
+    }
+
+    /// Collateral required to mint `pairs` of both YES and NO (split)
+    ///
+    /// Formula: new_R = √((A + n)² + (B + n)²), cost = new_R - R
+    pub fn get_pair_cost(reserves: u64, yes_supply: u64, no_supply: u64, pairs: u64) -> Result<u64> {
+        require!(pairs > 0, AmmError::InvalidReserves);
+        let new_a = yes_supply.checked_add(pairs).ok_or(AmmError::Overflow)?;
+        let new_b = no_supply.checked_add(pairs).ok_or(AmmError::Overflow)?;
+
+        let new_r = invariant_reserves_ceil(new_a, new_b)?;
+        let cost = new_r.saturating_sub(reserves as u128);
+        u64::try_from(cost).map_err(|_| error!(AmmError::Overflow))
+    }
+
+    /// Pairs of YES + NO that `collateral_in` buys (complete set)
+    ///
+    /// Largest n with (A + n)² + (B + n)² ≤ (R + L)², i.e.
+    /// n = (√(2(R + L)² - (A - B)²) - (A + B)) / 2, then re-checked against
+    /// the invariant so rounding can never over-mint.
+    pub fn get_pairs_for_collateral(reserves: u64, yes_supply: u64, no_supply: u64, collateral_in: u64) -> Result<u64> {
+        require!(collateral_in > 0, AmmError::InvalidReserves);
+        let new_r = (reserves as u128).checked_add(collateral_in as u128).ok_or(AmmError::Overflow)?;
+        let skew = (yes_supply as i128 - no_supply as i128).unsigned_abs();
+
+        let disc = new_r
+            .checked_mul(new_r)
+            .and_then(|r2| r2.checked_mul(2))
+            .and_then(|r2| r2.checked_sub(skew * skew))
+            .ok_or(AmmError::Overflow)?;
+        let total = yes_supply as u128 + no_supply as u128;
+        let mut pairs = (sqrt(disc).saturating_sub(total) / 2) as u64;
+
+        while pairs > 0 {
+            let yes = yes_supply.checked_add(pairs).ok_or(AmmError::Overflow)?;
+            let no = no_supply.checked_add(pairs).ok_or(AmmError::Overflow)?;
+            if invariant_reserves_ceil(yes, no)? <= new_r {
+                break;
+            }
+            pairs -= 1;
+        }
+        require!(pairs > 0, AmmError::NoTokensToMint);
+        Ok(pairs)
+    }
+
+    /// Tokens of the other side minted for burning `amount` of one side (swap)
+    ///
+    /// Formula: new_B = √(R² - (A - n)²), tokens_out = new_B - B
+    ///
+    /// `reserves` is what the pool keeps after the swap: pass R minus any
+    /// fee taken out. The curve is path independent, so this lands where a
+    /// sell of `n` followed by a buy with its proceeds would, without the
+    /// buy's `PRECISION_SCALE` truncation. `new_B` is rounded down, so the
+    /// pool never ends up below the invariant.
+    pub fn get_swap_output(reserves: u64, from_supply: u64, to_supply: u64, amount: u64) -> Result<u64> {
+        require!(amount > 0, AmmError::InvalidReserves);
+        require!(amount <= from_supply, AmmError::InsufficientTokens);
+
+        let new_from = (from_supply - amount) as u128;
+        let r = reserves as u128;
+        let new_to_squared = (r * r).checked_sub(new_from * new_from).ok_or(AmmError::InvalidSupplies)?;
+        let tokens_out = sqrt(new_to_squared).saturating_sub(to_supply as u128);
+        require!(tokens_out > 0, AmmError::NoTokensToMint);
+        u64::try_from(tokens_out).map_err(|_| error!(AmmError::Overflow))
+    }
+
+    /// Implied probability of a side in basis points (5000 at balance)
+    ///
+    /// Probability = A² / (A² + B²), i.e. the square of the marginal price.
+    /// Use this wherever "odds" are meant; `get_price` is the marginal cost
+    /// per token and reads ≈7071 at 50/50, which confuses integrators.
+    pub fn get_implied_probability_bps(target_supply: u64, other_supply: u64) -> Result<u64> {
+        let a_squared = target_supply as u128 * target_supply as u128;
+        let b_squared = other_supply as u128 * other_supply as u128;
+        let total = a_squared.checked_add(b_squared).ok_or(AmmError::Overflow)?;
+        if total == 0 {
+            return Ok(5000);
+        }
+
+        // Divide before scaling so A² * 10000 can't overflow near the supply cap
+        let bps = if a_squared > u128::MAX / 10_000 { a_squared / (total / 10_000) } else { a_squared * 10_000 / total };
+        Ok(bps.min(10_000) as u64)
+    }
+
     /// Get the current price of a token
     ///
+    /// This is the marginal price, not the implied probability: at 50/50 it is
+    /// ≈7071 bps. See `get_implied_probability_bps` for odds.
+    ///
     /// Price = A / R where R = √(A² + B²)
     ///
     /// This represents:
@@ -228,13 +341,10 @@ impl PythagoreanCurve {
             return Ok(5000); // Default 50% if no liquidity
         }
 
-        // Scale down
-        let r = (reserves as u128) / PRECISION_SCALE;
-        let a = (target_supply as u128) / PRECISION_SCALE;
-
-        if r == 0 {
-            return Ok(5000);
-        }
+        // No PRECISION_SCALE here: A * 10000 always fits in u128, and scaling
+        // down would collapse small reserves to 0 and misreport the price
+        let r = reserves as u128;
+        let a = target_supply as u128;
 
         // Price = A / R (scaled to basis points)
         // price_bps = (A * 10000) / R
@@ -262,6 +372,15 @@ impl PythagoreanCurve {
     }
 }
 
+/// ⌈√(A² + B²)⌉, the reserves a supply pair requires
+fn invariant_reserves_ceil(a: u64, b: u64) -> Result<u128> {
+    let r_squared = (a as u128 * a as u128)
+        .checked_add(b as u128 * b as u128)
+        .ok_or(AmmError::Overflow)?;
+    let r = sqrt(r_squared);
+    Ok(if r * r < r_squared { r + 1 } else { r })
+}
+
 /// Integer square root using Newton's method
 ///
 /// Computes floor(√x) efficiently for any non-negative integer
@@ -330,6 +449,25 @@ mod tests {
         assert!(yes_price >= 7000 && yes_price <= 7200);
     }
 
+    #[test]
+    fn test_price_at_low_reserves() {
+        // Below PRECISION_SCALE, reserves used to collapse to the 50% default
+        assert_eq!(PythagoreanCurve::get_price(500, 400, 300).unwrap(), 8000);
+        assert_eq!(PythagoreanCurve::get_price(500, 300, 400).unwrap(), 6000);
+        assert_eq!(PythagoreanCurve::get_price(0, 400, 300).unwrap(), 5000);
+    }
+
+    #[test]
+    fn test_implied_probability() {
+        assert_eq!(PythagoreanCurve::get_implied_probability_bps(707_000, 707_000).unwrap(), 5000);
+        assert_eq!(PythagoreanCurve::get_implied_probability_bps(800_000, 600_000).unwrap(), 6400);
+        assert_eq!(PythagoreanCurve::get_implied_probability_bps(600_000, 800_000).unwrap(), 3600);
+        assert_eq!(PythagoreanCurve::get_implied_probability_bps(0, 0).unwrap(), 5000);
+
+        let cap = PythagoreanCurve::MAX_SUPPLY;
+        assert_eq!(PythagoreanCurve::get_implied_probability_bps(cap, cap).unwrap(), 5000);
+    }
+
     #[test]
     fn test_buy_increases_supply() {
         let reserves = 1_000_000u64;
@@ -348,6 +486,44 @@ mod tests {
         // New YES supply should maintain invariant
     }
 
+    #[test]
+    fn test_pair_mint_then_burn_never_profits() {
+        let (reserves, yes_supply, no_supply) = (1_000_000u64, 800_000u64, 600_000u64);
+        let cost = PythagoreanCurve::get_pair_cost(reserves, yes_supply, no_supply, 10_000).unwrap();
+        let release = PythagoreanCurve::get_pair_release(reserves + cost, yes_supply + 10_000, no_supply + 10_000, 10_000).unwrap();
+
+        // A pair is worth (A + B) / R ≈ 1.4 collateral at these odds
+        assert!(cost > 13_000 && cost < 15_000);
+        assert!(release <= cost);
+        assert!(PythagoreanCurve::get_pair_release(reserves, yes_supply, no_supply, 600_001).is_err());
+    }
+
+    #[test]
+    fn test_complete_set_round_trip() {
+        let (reserves, yes_supply, no_supply) = (1_000_000u64, 800_000u64, 600_000u64);
+        let pairs = PythagoreanCurve::get_pairs_for_collateral(reserves, yes_supply, no_supply, 14_002).unwrap();
+        assert!(pairs > 9_990 && pairs <= 10_000);
+
+        // Minting never costs less than the invariant requires ...
+        let cost = PythagoreanCurve::get_pair_cost(reserves, yes_supply, no_supply, pairs).unwrap();
+        assert!(cost <= 14_002);
+
+        // ... and burning straight back never returns more than was paid
+        let release = PythagoreanCurve::get_pair_release(reserves + 14_002, yes_supply + pairs, no_supply + pairs, pairs).unwrap();
+        assert!(release <= 14_002);
+    }
+
+    #[test]
+    fn test_supply_cap() {
+        let cap = PythagoreanCurve::MAX_SUPPLY;
+        assert!(PythagoreanCurve::check_supply_cap(cap - 10, 10).is_ok());
+        assert!(PythagoreanCurve::check_supply_cap(cap - 10, 11).is_err());
+        assert!(PythagoreanCurve::check_supply_cap(u64::MAX, 1).is_err());
+
+        // Both sides at the cap still square and sum without overflow
+        assert!(invariant_reserves_ceil(cap, cap).is_ok());
+    }
+
     #[test]
     fn test_sell_returns_collateral() {
         let reserves = 1_000_000u64;
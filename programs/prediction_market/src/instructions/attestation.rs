@@ -0,0 +1,172 @@
+//! Oracle Attestations
+//!
+//! Lets an oracle sign a verdict off-chain and have anyone submit it.
+//! The Solana runtime cannot verify Ed25519 signatures from inside a
+//! program cheaply, so the relayer places an Ed25519 program instruction
+//! immediately before ours. If that instruction's signature is invalid the
+//! whole transaction fails; we only have to check that it covers the exact
+//! oracle key, signature and message we were handed.
+//!
+//! ## Message Layout (81 bytes)
+//!
+//! ```text
+//! market (32) || market_id (u64 LE) || yes_wins (u8) || deadline (i64 LE) || evidence_hash (32)
+//! ```
+//!
+//! The market address binds the attestation to one deployment and config,
+//! and the deadline bounds how long a signed verdict stays usable.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    ed25519_program,
+    instruction::Instruction,
+    sysvar::instructions::get_instruction_relative,
+};
+
+/// Length of an attestation message
+pub const ATTESTATION_MESSAGE_LEN: usize = 32 + 8 + 1 + 8 + 32;
+
+/// Size of the per-signature offsets record in an Ed25519 instruction
+const ED25519_OFFSETS_LEN: usize = 14;
+
+/// Offsets start after `num_signatures: u8` and one byte of padding
+const ED25519_OFFSETS_START: usize = 2;
+
+/// Instruction index meaning "this Ed25519 instruction's own data"
+const ED25519_SELF_INDEX: u16 = u16::MAX;
+
+/// A decoded resolution attestation
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Attestation {
+    pub market: Pubkey,
+    pub market_id: u64,
+    pub yes_wins: bool,
+    pub deadline: i64,
+    pub evidence_hash: [u8; 32],
+}
+
+impl Attestation {
+    /// Encode in the layout the oracle signs
+    pub fn to_message(&self) -> [u8; ATTESTATION_MESSAGE_LEN] {
+        let mut msg = [0u8; ATTESTATION_MESSAGE_LEN];
+        msg[..32].copy_from_slice(self.market.as_ref());
+        msg[32..40].copy_from_slice(&self.market_id.to_le_bytes());
+        msg[40] = self.yes_wins as u8;
+        msg[41..49].copy_from_slice(&self.deadline.to_le_bytes());
+        msg[49..].copy_from_slice(&self.evidence_hash);
+        msg
+    }
+
+    /// Decode a signed message, rejecting any other length or outcome byte
+    pub fn from_message(msg: &[u8]) -> Result<Self> {
+        require!(msg.len() == ATTESTATION_MESSAGE_LEN, AttestationError::MalformedMessage);
+        require!(msg[40] <= 1, AttestationError::MalformedMessage);
+        Ok(Self {
+            market: Pubkey::try_from(&msg[..32]).map_err(|_| AttestationError::MalformedMessage)?,
+            market_id: u64::from_le_bytes(msg[32..40].try_into().unwrap()),
+            yes_wins: msg[40] == 1,
+            deadline: i64::from_le_bytes(msg[41..49].try_into().unwrap()),
+            evidence_hash: msg[49..].try_into().unwrap(),
+        })
+    }
+}
+
+/// Require the instruction just before this one to verify `signature` by `signer` over `message`
+pub fn verify_preceding_ed25519(instructions_sysvar: &AccountInfo, signer: &Pubkey, signature: &[u8; 64], message: &[u8]) -> Result<()> {
+    let ix = get_instruction_relative(-1, instructions_sysvar).map_err(|_| AttestationError::MissingSignatureInstruction)?;
+    check_ed25519_instruction(&ix, signer, signature, message)
+}
+
+/// Check a single-signature Ed25519 program instruction against the expected data
+///
+/// Every offset must point into the instruction's own data, so a crafted
+/// instruction cannot borrow a valid signature from elsewhere in the transaction.
+pub fn check_ed25519_instruction(ix: &Instruction, signer: &Pubkey, signature: &[u8; 64], message: &[u8]) -> Result<()> {
+    require_keys_eq!(ix.program_id, ed25519_program::ID, AttestationError::MissingSignatureInstruction);
+    let data = &ix.data;
+    require!(data.len() >= ED25519_OFFSETS_START + ED25519_OFFSETS_LEN && data[0] == 1, AttestationError::InvalidSignatureInstruction);
+
+    let field = |i: usize| u16::from_le_bytes([data[ED25519_OFFSETS_START + 2 * i], data[ED25519_OFFSETS_START + 2 * i + 1]]);
+    let (sig_offset, sig_ix, key_offset, key_ix, msg_offset, msg_len, msg_ix) = (field(0), field(1), field(2), field(3), field(4), field(5), field(6));
+    require!(
+        sig_ix == ED25519_SELF_INDEX && key_ix == ED25519_SELF_INDEX && msg_ix == ED25519_SELF_INDEX,
+        AttestationError::InvalidSignatureInstruction
+    );
+
+    let slice = |offset: u16, len: usize| data.get(offset as usize..offset as usize + len).ok_or(AttestationError::InvalidSignatureInstruction);
+    require!(slice(key_offset, 32)? == signer.as_ref(), AttestationError::WrongSigner);
+    require!(slice(sig_offset, 64)? == signature.as_ref(), AttestationError::SignatureMismatch);
+    require!(slice(msg_offset, msg_len as usize)? == message, AttestationError::SignatureMismatch);
+    Ok(())
+}
+
+#[error_code(offset = 8700)]
+pub enum AttestationError {
+    #[msg("Attestation message is malformed")]
+    MalformedMessage,
+    #[msg("Attestation does not match this market")]
+    MarketMismatch,
+    #[msg("Attestation has expired")]
+    Expired,
+    #[msg("Expected an Ed25519 signature instruction before this one")]
+    MissingSignatureInstruction,
+    #[msg("Ed25519 instruction is not a single self-contained signature")]
+    InvalidSignatureInstruction,
+    #[msg("Attestation was not signed by the oracle")]
+    WrongSigner,
+    #[msg("Signature or message differs from the verified instruction")]
+    SignatureMismatch,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Lay out an Ed25519 instruction the way the web3.js helper does
+    fn ed25519_ix(signer: &Pubkey, signature: &[u8; 64], message: &[u8]) -> Instruction {
+        let key_offset = (ED25519_OFFSETS_START + ED25519_OFFSETS_LEN) as u16;
+        let sig_offset = key_offset + 32;
+        let msg_offset = sig_offset + 64;
+        let mut data = vec![1u8, 0];
+        for v in [sig_offset, ED25519_SELF_INDEX, key_offset, ED25519_SELF_INDEX, msg_offset, message.len() as u16, ED25519_SELF_INDEX] {
+            data.extend_from_slice(&v.to_le_bytes());
+        }
+        data.extend_from_slice(signer.as_ref());
+        data.extend_from_slice(signature);
+        data.extend_from_slice(message);
+        Instruction { program_id: ed25519_program::ID, accounts: vec![], data }
+    }
+
+    #[test]
+    fn test_message_round_trip() {
+        let att = Attestation { market: Pubkey::new_unique(), market_id: 7, yes_wins: true, deadline: 1_700_000_000, evidence_hash: [9u8; 32] };
+        assert_eq!(Attestation::from_message(&att.to_message()).unwrap(), att);
+
+        let mut bad = att.to_message();
+        bad[40] = 2;
+        assert!(Attestation::from_message(&bad).is_err());
+        assert!(Attestation::from_message(&bad[..80]).is_err());
+    }
+
+    #[test]
+    fn test_ed25519_instruction_checks() {
+        let oracle = Pubkey::new_unique();
+        let signature = [3u8; 64];
+        let message = [5u8; ATTESTATION_MESSAGE_LEN];
+        let ix = ed25519_ix(&oracle, &signature, &message);
+
+        assert!(check_ed25519_instruction(&ix, &oracle, &signature, &message).is_ok());
+        assert!(check_ed25519_instruction(&ix, &Pubkey::new_unique(), &signature, &message).is_err());
+        assert!(check_ed25519_instruction(&ix, &oracle, &[4u8; 64], &message).is_err());
+        assert!(check_ed25519_instruction(&ix, &oracle, &signature, &[6u8; ATTESTATION_MESSAGE_LEN]).is_err());
+
+        // Offsets pointing at another instruction are refused
+        let mut foreign = ix.clone();
+        foreign.data[4..6].copy_from_slice(&0u16.to_le_bytes());
+        assert!(check_ed25519_instruction(&foreign, &oracle, &signature, &message).is_err());
+
+        let mut wrong_program = ix;
+        wrong_program.program_id = Pubkey::new_unique();
+        assert!(check_ed25519_instruction(&wrong_program, &oracle, &signature, &message).is_err());
+    }
+}
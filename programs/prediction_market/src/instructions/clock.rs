@@ -0,0 +1,80 @@
+//! Clock Source
+//!
+//! Time-dependent handlers read the current time through `now!`. Normal
+//! builds always use the Clock sysvar.
+//!
+//! Builds with the `test-clock` feature also accept an optional `TestClock`
+//! account on those handlers. When one is passed, its timestamp is used
+//! instead, so integration tests can move past end times, claim locks and
+//! dispute windows by calling `set_test_clock` rather than warping slots.
+//!
+//! Production builds compile the override fields out of the handlers, and
+//! `set_test_clock` (which `#[program]` can't drop per feature) always fails.
+
+use anchor_lang::prelude::*;
+
+use crate::instructions::market::ConfigError;
+use crate::state::{Config, TestClock};
+
+/// Current unix timestamp for a handler's accounts (`$accounts.test_clock` in test-clock builds)
+macro_rules! now {
+    ($accounts:expr) => {{
+        #[cfg(feature = "test-clock")]
+        let now = $crate::instructions::clock::unix_timestamp($accounts.test_clock.as_deref());
+        #[cfg(not(feature = "test-clock"))]
+        let now = $crate::instructions::clock::unix_timestamp();
+        now
+    }};
+}
+pub(crate) use now;
+
+/// The Clock sysvar's unix timestamp
+#[cfg(not(feature = "test-clock"))]
+pub fn unix_timestamp() -> Result<i64> {
+    Ok(Clock::get()?.unix_timestamp)
+}
+
+/// The test clock's timestamp if one was passed, else the Clock sysvar's
+#[cfg(feature = "test-clock")]
+pub fn unix_timestamp(test_clock: Option<&Account<TestClock>>) -> Result<i64> {
+    match test_clock {
+        Some(clock) => Ok(clock.unix_timestamp),
+        None => Ok(Clock::get()?.unix_timestamp),
+    }
+}
+
+#[derive(Accounts)]
+pub struct SetTestClock<'info> {
+    #[account(mut, constraint = admin.key() == config.admin @ ConfigError::Unauthorized)]
+    pub admin: Signer<'info>,
+
+    #[account(seeds = [Config::SEED], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + TestClock::INIT_SPACE,
+        seeds = [TestClock::SEED],
+        bump,
+    )]
+    pub test_clock: Box<Account<'info, TestClock>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SetTestClock<'info> {
+    /// Pin the time reported to handlers that are passed the test clock
+    pub fn set_test_clock(&mut self, unix_timestamp: i64, bump: u8) -> Result<()> {
+        require!(cfg!(feature = "test-clock"), ClockError::TestClockDisabled);
+        self.test_clock.unix_timestamp = unix_timestamp;
+        self.test_clock.bump = bump;
+        Ok(())
+    }
+}
+
+#[error_code(offset = 9000)]
+pub enum ClockError {
+    #[msg("Program was built without the test-clock feature")]
+    TestClockDisabled,
+}
@@ -0,0 +1,59 @@
+//! Compliance Hook
+//!
+//! Optional recipient screening for regulated deployments. When
+//! `config.recipient_allowlist` is set, every payout path CPIs into that
+//! program with the recipient before releasing funds. The allowlist program
+//! rejects a recipient by failing the call, which aborts the payout.
+//!
+//! Unset (the default), no check runs and payouts stay permissionless.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    hash,
+    instruction::{AccountMeta, Instruction},
+    program::invoke,
+};
+
+use crate::state::Config;
+
+/// Preimage of the allowlist program's check instruction discriminator
+pub const CHECK_RECIPIENT_DISCRIMINATOR_SEED: &[u8] = b"global:check_recipient";
+
+/// Anchor-style discriminator for `check_recipient(recipient)`
+pub fn check_recipient_discriminator() -> [u8; 8] {
+    let mut disc = [0u8; 8];
+    disc.copy_from_slice(&hash::hash(CHECK_RECIPIENT_DISCRIMINATOR_SEED).to_bytes()[..8]);
+    disc
+}
+
+/// Screen `recipient` against the configured allowlist program, if any
+pub fn check_recipient<'info>(
+    config: &Config,
+    allowlist_program: Option<&UncheckedAccount<'info>>,
+    recipient: &AccountInfo<'info>,
+) -> Result<()> {
+    let Some(expected) = config.recipient_allowlist else {
+        return Ok(());
+    };
+
+    let program = allowlist_program.ok_or(ComplianceError::AllowlistProgramMissing)?;
+    require_keys_eq!(program.key(), expected, ComplianceError::InvalidAllowlistProgram);
+    require!(program.executable, ComplianceError::InvalidAllowlistProgram);
+
+    let ix = Instruction {
+        program_id: expected,
+        accounts: vec![AccountMeta::new_readonly(recipient.key(), false)],
+        data: check_recipient_discriminator().to_vec(),
+    };
+
+    invoke(&ix, &[recipient.clone(), program.to_account_info()])?;
+    Ok(())
+}
+
+#[error_code(offset = 8800)]
+pub enum ComplianceError {
+    #[msg("Config requires an allowlist program for payouts")]
+    AllowlistProgramMissing,
+    #[msg("Allowlist program does not match config")]
+    InvalidAllowlistProgram,
+}
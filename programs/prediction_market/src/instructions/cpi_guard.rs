@@ -0,0 +1,37 @@
+//! CPI Guard
+//!
+//! Optional protection for the privacy exits against hostile wrapper
+//! programs. When `config.cpi_guard_enabled` is set, these instructions
+//! must be invoked at the top level of a transaction, unless the
+//! transaction's top-level program is `config.allowed_cpi_caller`.
+//!
+//! Off by default so legitimate composability keeps working.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    instruction::{get_stack_height, TRANSACTION_LEVEL_STACK_HEIGHT},
+    sysvar::instructions::get_instruction_relative,
+};
+
+use crate::state::Config;
+
+/// Reject CPI invocations from programs other than the allowlisted caller
+pub fn check_cpi_caller(config: &Config, instructions_sysvar: &AccountInfo) -> Result<()> {
+    if !config.cpi_guard_enabled || get_stack_height() <= TRANSACTION_LEVEL_STACK_HEIGHT {
+        return Ok(());
+    }
+
+    // The outermost instruction belongs to whichever program started the CPI chain
+    let top_level = get_instruction_relative(0, instructions_sysvar)?;
+    require!(
+        config.allowed_cpi_caller == Some(top_level.program_id),
+        CpiGuardError::UnauthorizedCaller
+    );
+    Ok(())
+}
+
+#[error_code(offset = 8900)]
+pub enum CpiGuardError {
+    #[msg("Instruction cannot be invoked via CPI from this program")]
+    UnauthorizedCaller,
+}
@@ -6,8 +6,12 @@
 //!
 //! Step 1: CreateMarketState - Initializes the market account.
 //! Step 2: CreateMarketMints - Creates the YES and NO token mints.
-//! Step 3: CreateMarketVaults - Creates the market's collateral vault and creator accounts.
+//! Step 3: CreateMarketVaults - Creates the market's collateral vault and creator accounts,
+//!         plus the protocol fee vault for the collateral if it has none yet.
 //! Step 4: FundMarket - Transfers initial liquidity and mints initial tokens.
+//!
+//! A creator who passes `crowdfunded` at Step 1 replaces Step 4 with
+//! pledges, see `crowdfund`.
 
 use anchor_lang::prelude::*;
 use anchor_spl::{
@@ -15,7 +19,10 @@ use anchor_spl::{
     token_interface::{Mint, MintTo, TokenAccount, TokenInterface, TransferChecked, mint_to, transfer_checked},
 };
 
-use crate::state::{Config, Market, MarketStatus, Outcome};
+use crate::instructions::clock::now;
+use crate::state::{Config, Market, MarketCategory, MarketStatus, Outcome, ProtocolStats, SettlementMode, MAX_QUESTION_LEN, MAX_TAGS, MAX_TAG_LEN};
+#[cfg(feature = "test-clock")]
+use crate::state::TestClock;
 
 // =============================================================================
 // STEP 1: CREATE MARKET STATE
@@ -24,9 +31,18 @@ use crate::state::{Config, Market, MarketStatus, Outcome};
 /// Event emitted when market state is created
 #[event]
 pub struct MarketStateCreated {
+    /// Config the market was created under; `market_id` is only unique within it
+    pub config: Pubkey,
+    pub config_version: u8,
     pub market_id: u64,
     pub creator: Pubkey,
     pub end_time: u64,
+    pub settlement_mode: SettlementMode,
+    pub category: MarketCategory,
+    pub tags: Vec<String>,
+    pub min_trade_amount: u64,
+    /// Resolution source the oracle is bound to (zeros = none declared)
+    pub resolution_source_hash: [u8; 32],
 }
 
 #[derive(Accounts)]
@@ -51,24 +67,46 @@ pub struct CreateMarketState<'info> {
     )]
     pub market: Account<'info, Market>,
 
-    /// CHECK: Validated in Step 4 (FundMarket)
-    pub collateral_mint: UncheckedAccount<'info>,
+    #[account(mut, seeds = [ProtocolStats::SEED], bump = protocol_stats.bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+
+    /// Deserialized here so a non-mint key fails at creation, not at funding
+    pub collateral_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Time override (test-clock builds only, see `instructions::clock`)
+    #[cfg(feature = "test-clock")]
+    #[account(seeds = [TestClock::SEED], bump = test_clock.bump)]
+    pub test_clock: Option<Box<Account<'info, TestClock>>>,
 
     pub system_program: Program<'info, System>,
 }
 
 impl<'info> CreateMarketState<'info> {
+    #[allow(clippy::too_many_arguments)]
     pub fn create_market_state(
         &mut self,
         question: String,
         end_time: u64,
+        settlement_mode: SettlementMode,
+        category: MarketCategory,
+        tags: Vec<String>,
+        min_trade_amount: u64,
+        allow_privacy: bool,
+        crowdfunded: bool,
+        resolution_source_hash: [u8; 32],
         bumps: &CreateMarketStateBumps,
     ) -> Result<()> {
-        let clock = Clock::get()?;
-        
+        let now = now!(self)?;
+
         require!(!self.config.paused, CreateMarketError::ProtocolPaused);
-        require!(end_time > clock.unix_timestamp as u64, CreateMarketError::InvalidEndTime);
-        require!(question.len() <= 256, CreateMarketError::QuestionTooLong);
+        check_market_window(now as u64, end_time, self.config.min_market_duration, self.config.max_market_duration)?;
+        require!(
+            self.config.collateral_decimals_supported(self.collateral_mint.decimals),
+            CreateMarketError::CollateralDecimalsTooHigh
+        );
+        require!(question.len() <= MAX_QUESTION_LEN, CreateMarketError::QuestionTooLong);
+        require!(tags.len() <= MAX_TAGS, CreateMarketError::TooManyTags);
+        require!(tags.iter().all(|tag| tag.len() <= MAX_TAG_LEN), CreateMarketError::TagTooLong);
 
         let market_id = self.config.market_count;
 
@@ -77,7 +115,7 @@ impl<'info> CreateMarketState<'info> {
             creator: self.creator.key(),
             question,
             end_time,
-            created_at: clock.unix_timestamp as u64,
+            created_at: now as u64,
             yes_mint: Pubkey::default(),
             no_mint: Pubkey::default(),
             collateral_mint: self.collateral_mint.key(),
@@ -87,16 +125,45 @@ impl<'info> CreateMarketState<'info> {
             shielded_reserve_commitment: [0u8; 32],
             reserve_blinding: [0u8; 32],
             status: MarketStatus::Active,
+            funded: false,
+            total_volume: 0,
+            reward_paid: false,
             outcome: Outcome::Undetermined,
+            evidence_hash: [0u8; 32],
+            resolution_source_hash,
+            settlement_mode,
+            category,
+            locked_creator_tokens: 0,
+            liquidity_lock_until: 0,
+            min_trade_amount,
+            crowdfunded_liquidity: 0,
+            crowdfunded_claimed: 0,
+            crowdfunded,
+            total_locked_in_claims: 0,
+            shielded_deposits: 0,
+            shielded_winning_revealed: 0,
+            allow_privacy,
+            resolved_at: 0,
+            resolved_by: Pubkey::default(),
+            bond_slashed: false,
+            tags: tags.clone(),
             bump: bumps.market,
         });
 
         self.config.market_count += 1;
+        self.protocol_stats.record_market_created();
 
         emit!(MarketStateCreated {
+            config: self.config.key(),
+            config_version: Config::VERSION,
             market_id,
             creator: self.creator.key(),
             end_time,
+            settlement_mode,
+            category,
+            tags,
+            min_trade_amount,
+            resolution_source_hash,
         });
 
         Ok(())
@@ -189,6 +256,9 @@ pub struct CreateMarketVaults<'info> {
     #[account(mut)]
     pub creator: Signer<'info>,
 
+    #[account(seeds = [Config::SEED], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+
     #[account(
         constraint = market.creator == creator.key(),
         constraint = market.reserves == 0,
@@ -232,6 +302,15 @@ pub struct CreateMarketVaults<'info> {
     )]
     pub creator_no: Box<InterfaceAccount<'info, TokenAccount>>,
 
+    /// Protocol fee vault for this collateral, shared by every market settled in it
+    #[account(
+        init_if_needed,
+        payer = creator,
+        associated_token::mint = collateral_mint,
+        associated_token::authority = config,
+    )]
+    pub fee_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
@@ -273,6 +352,7 @@ pub struct FundMarket<'info> {
         constraint = market.creator == creator.key(),
         constraint = market.reserves == 0,
         constraint = market.yes_mint != Pubkey::default(),
+        constraint = !market.crowdfunded @ CreateMarketError::CrowdfundingOpen,
     )]
     pub market: Box<Account<'info, Market>>,
 
@@ -288,19 +368,39 @@ pub struct FundMarket<'info> {
     #[account(constraint = collateral_mint.key() == market.collateral_mint)]
     pub collateral_mint: InterfaceAccount<'info, Mint>,
 
-
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = creator_collateral.mint == collateral_mint.key() @ CreateMarketError::InvalidFundingAccount,
+        constraint = creator_collateral.owner == creator.key() @ CreateMarketError::InvalidFundingAccount,
+    )]
     pub creator_collateral: InterfaceAccount<'info, TokenAccount>,
 
-    #[account(mut)]
+    /// Must be the market's canonical vault, the one trades and redemptions use
+    #[account(mut, associated_token::mint = collateral_mint, associated_token::authority = market)]
     pub vault: InterfaceAccount<'info, TokenAccount>,
 
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = creator_yes.mint == market.yes_mint @ CreateMarketError::InvalidFundingAccount,
+        constraint = creator_yes.owner == creator.key() @ CreateMarketError::InvalidFundingAccount,
+    )]
     pub creator_yes: InterfaceAccount<'info, TokenAccount>,
 
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = creator_no.mint == market.no_mint @ CreateMarketError::InvalidFundingAccount,
+        constraint = creator_no.owner == creator.key() @ CreateMarketError::InvalidFundingAccount,
+    )]
     pub creator_no: InterfaceAccount<'info, TokenAccount>,
 
+    #[account(mut, seeds = [ProtocolStats::SEED], bump = protocol_stats.bump)]
+    pub protocol_stats: Box<Account<'info, ProtocolStats>>,
+
+    /// Time override (test-clock builds only, see `instructions::clock`)
+    #[cfg(feature = "test-clock")]
+    #[account(seeds = [TestClock::SEED], bump = test_clock.bump)]
+    pub test_clock: Option<Box<Account<'info, TestClock>>>,
+
     pub token_program: Interface<'info, TokenInterface>,
 }
 
@@ -312,7 +412,7 @@ impl<'info> FundMarket<'info> {
         );
 
         let reserves = initial_liquidity;
-        let token_amount = integer_sqrt((reserves as u128 * reserves as u128) / 2) as u64;
+        let token_amount = initial_token_amount(reserves);
 
         transfer_checked(
             CpiContext::new(
@@ -328,6 +428,25 @@ impl<'info> FundMarket<'info> {
             self.collateral_mint.decimals,
         )?;
 
+        self.market.reserves = initial_liquidity;
+        self.protocol_stats.record_deposit(initial_liquidity);
+        self.market.yes_supply = token_amount;
+        self.market.no_supply = token_amount;
+        self.market.funded = true;
+
+        emit!(MarketFunded {
+            market_id: self.market.id,
+            initial_liquidity,
+        });
+
+        // Locked tokens count towards supply now but are minted on unlock
+        let lock_seconds = self.config.creator_liquidity_lock_seconds;
+        if lock_seconds > 0 {
+            self.market.locked_creator_tokens = token_amount;
+            self.market.liquidity_lock_until = now!(self)?.saturating_add(lock_seconds);
+            return Ok(());
+        }
+
         let config_seeds = &[Config::SEED, &[self.config.bump]];
         let signer_seeds = &[&config_seeds[..]];
 
@@ -357,45 +476,31 @@ impl<'info> FundMarket<'info> {
             token_amount,
         )?;
 
-        self.market.reserves = initial_liquidity;
-        self.market.yes_supply = token_amount;
-        self.market.no_supply = token_amount;
-
-        emit!(MarketFunded {
-            market_id: self.market.id,
-            initial_liquidity,
-        });
-
         Ok(())
     }
 }
 
 // =============================================================================
-// LEGACY / HELPERS
+// HELPERS
 // =============================================================================
 
-#[derive(Accounts)]
-pub struct CreateMarket<'info> {
-    #[account(mut)]
-    pub creator: Signer<'info>,
-    
-    /// CHECK: Legacy placeholder - use Step 1-4 pipeline
-    pub config: AccountInfo<'info>,
-    
-    /// CHECK: Legacy placeholder - use Step 1-4 pipeline
-    pub market: AccountInfo<'info>,
+/// Validate a new market's trading window against the protocol's duration bounds
+///
+/// The clock only has one-second precision, so an `end_time` of `now + 1`
+/// is in the future yet leaves no real trading window; `min_duration`
+/// rejects such markets outright. `end_time` must also fit the clock's
+/// `i64` so `Market::has_ended` compares it without wrapping.
+pub fn check_market_window(now: u64, end_time: u64, min_duration: u64, max_duration: u64) -> Result<()> {
+    require!(end_time > now && end_time <= i64::MAX as u64, CreateMarketError::InvalidEndTime);
+    let duration = end_time - now;
+    require!(duration >= min_duration, CreateMarketError::DurationTooShort);
+    require!(duration <= max_duration, CreateMarketError::DurationTooLong);
+    Ok(())
 }
 
-impl<'info> CreateMarket<'info> {
-    pub fn create_market(
-        &mut self,
-        _question: String,
-        _end_time: u64,
-        _initial_liquidity: u64,
-        _bumps: &CreateMarketBumps,
-    ) -> Result<()> {
-        err!(CreateMarketError::Deprecated)
-    }
+/// YES and NO (each) minted against `liquidity` initial reserves: √(R²/2)
+pub(crate) fn initial_token_amount(liquidity: u64) -> u64 {
+    integer_sqrt((liquidity as u128 * liquidity as u128) / 2) as u64
 }
 
 fn integer_sqrt(x: u128) -> u128 {
@@ -409,7 +514,7 @@ fn integer_sqrt(x: u128) -> u128 {
     y
 }
 
-#[error_code]
+#[error_code(offset = 6200)]
 pub enum CreateMarketError {
     #[msg("Protocol is paused")]
     ProtocolPaused,
@@ -419,6 +524,41 @@ pub enum CreateMarketError {
     InsufficientLiquidity,
     #[msg("Question exceeds maximum length")]
     QuestionTooLong,
-    #[msg("Legacy instruction deprecated, use Step 1-4 pipeline")]
-    Deprecated,
+    #[msg("Market duration exceeds protocol maximum")]
+    DurationTooLong,
+    #[msg("Too many tags")]
+    TooManyTags,
+    #[msg("Tag exceeds maximum length")]
+    TagTooLong,
+    #[msg("Funding account has the wrong mint or owner")]
+    InvalidFundingAccount,
+    #[msg("Market is crowdfunded and must be opened with finalize_funding")]
+    CrowdfundingOpen,
+    #[msg("Market duration is below protocol minimum")]
+    DurationTooShort,
+    #[msg("Collateral mint has more decimals than the protocol supports")]
+    CollateralDecimalsTooHigh,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_market_window_boundaries() {
+        let now = 1_000_000;
+        assert!(check_market_window(now, now, 0, 100).is_err());
+        assert!(check_market_window(now, now + 1, 0, 100).is_ok());
+
+        // A one-second window is rejected once a minimum is set
+        assert!(check_market_window(now, now + 1, 60, 100).is_err());
+        assert!(check_market_window(now, now + 59, 60, 100).is_err());
+        assert!(check_market_window(now, now + 60, 60, 100).is_ok());
+        assert!(check_market_window(now, now + 100, 60, 100).is_ok());
+        assert!(check_market_window(now, now + 101, 60, 100).is_err());
+
+        // An end time past the clock's range is rejected, however long the max
+        assert!(check_market_window(now, i64::MAX as u64, 0, u64::MAX).is_ok());
+        assert!(check_market_window(now, i64::MAX as u64 + 1, 0, u64::MAX).is_err());
+    }
 }
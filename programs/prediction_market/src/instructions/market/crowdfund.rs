@@ -0,0 +1,396 @@
+//! Crowdfunded Market Funding
+//!
+//! An alternative to Step 4 (`fund_market`) for community-bootstrapped
+//! markets. The creator opts in by passing `crowdfunded` at creation, which
+//! also rules out `fund_market`, so a stray pledge can never block a creator
+//! who meant to fund alone. After the mints and vault exist, anyone may pledge collateral
+//! with `contribute_funding`; each pledge is recorded in a
+//! `LiquidityPosition` and can be pulled back with `withdraw_funding`
+//! until funding is finalized.
+//!
+//! Once the pledges reach `config.min_liquidity`, anyone may call
+//! `finalize_funding`. The pledged total becomes the initial reserves and
+//! the usual √(R²/2) YES and NO are counted in supply. Each contributor
+//! then mints their pro-rata share with `claim_funding_tokens`, which
+//! closes their position.
+
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{mint_to, transfer_checked, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::instructions::market::initial_token_amount;
+use crate::instructions::clock::now;
+use crate::state::{Config, LiquidityPosition, Market, MarketStatus, ProtocolStats};
+#[cfg(feature = "test-clock")]
+use crate::state::TestClock;
+
+#[event]
+pub struct FundingContributed {
+    pub market_id: u64,
+    pub provider: Pubkey,
+    pub amount: u64,
+    /// Pledged total after this contribution
+    pub total_pledged: u64,
+}
+
+#[event]
+pub struct FundingWithdrawn {
+    pub market_id: u64,
+    pub provider: Pubkey,
+    pub amount: u64,
+    pub total_pledged: u64,
+}
+
+#[event]
+pub struct FundingFinalized {
+    pub market_id: u64,
+    pub initial_liquidity: u64,
+    /// YES and NO (each) owed to contributors
+    pub token_amount: u64,
+}
+
+#[event]
+pub struct FundingTokensClaimed {
+    pub market_id: u64,
+    pub provider: Pubkey,
+    /// Amount of YES and of NO minted
+    pub amount: u64,
+}
+
+// =============================================================================
+// CONTRIBUTE / WITHDRAW
+// =============================================================================
+
+#[derive(Accounts)]
+pub struct ContributeFunding<'info> {
+    #[account(mut)]
+    pub provider: Signer<'info>,
+
+    #[account(seeds = [Config::SEED], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        mut,
+        constraint = market.crowdfunded @ FundingError::CrowdfundingDisabled,
+        constraint = !market.funded @ FundingError::AlreadyFunded,
+        constraint = market.status == MarketStatus::Active @ FundingError::MarketNotActive,
+        constraint = market.yes_mint != Pubkey::default() @ FundingError::MintsNotCreated,
+    )]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(
+        init_if_needed,
+        payer = provider,
+        space = 8 + LiquidityPosition::INIT_SPACE,
+        seeds = [LiquidityPosition::SEED, market.key().as_ref(), provider.key().as_ref()],
+        bump,
+    )]
+    pub liquidity_position: Box<Account<'info, LiquidityPosition>>,
+
+    #[account(constraint = collateral_mint.key() == market.collateral_mint)]
+    pub collateral_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut, associated_token::mint = collateral_mint, associated_token::authority = provider)]
+    pub provider_collateral: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, associated_token::mint = collateral_mint, associated_token::authority = market)]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ContributeFunding<'info> {
+    pub fn contribute_funding(&mut self, amount: u64, bump: u8) -> Result<()> {
+        require!(!self.config.paused, FundingError::ProtocolPaused);
+        require!(amount > 0, FundingError::ZeroAmount);
+
+        transfer_checked(
+            CpiContext::new(
+                self.token_program.to_account_info(),
+                TransferChecked {
+                    from: self.provider_collateral.to_account_info(),
+                    mint: self.collateral_mint.to_account_info(),
+                    to: self.vault.to_account_info(),
+                    authority: self.provider.to_account_info(),
+                },
+            ),
+            amount,
+            self.collateral_mint.decimals,
+        )?;
+
+        let position = &mut self.liquidity_position;
+        if position.provider == Pubkey::default() {
+            position.market = self.market.key();
+            position.provider = self.provider.key();
+            position.bump = bump;
+        }
+        position.contributed = position.contributed.checked_add(amount).ok_or(FundingError::Overflow)?;
+        self.market.crowdfunded_liquidity = self.market.crowdfunded_liquidity.checked_add(amount).ok_or(FundingError::Overflow)?;
+
+        emit!(FundingContributed {
+            market_id: self.market.id,
+            provider: self.provider.key(),
+            amount,
+            total_pledged: self.market.crowdfunded_liquidity,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct WithdrawFunding<'info> {
+    #[account(mut)]
+    pub provider: Signer<'info>,
+
+    #[account(seeds = [Config::SEED], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(mut, constraint = !market.funded @ FundingError::AlreadyFunded)]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(
+        mut,
+        seeds = [LiquidityPosition::SEED, market.key().as_ref(), provider.key().as_ref()],
+        bump = liquidity_position.bump,
+        close = provider,
+    )]
+    pub liquidity_position: Box<Account<'info, LiquidityPosition>>,
+
+    #[account(constraint = collateral_mint.key() == market.collateral_mint)]
+    pub collateral_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut, associated_token::mint = collateral_mint, associated_token::authority = provider)]
+    pub provider_collateral: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, associated_token::mint = collateral_mint, associated_token::authority = market)]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> WithdrawFunding<'info> {
+    /// Pull a whole pledge back out before funding is finalized
+    pub fn withdraw_funding(&mut self) -> Result<u64> {
+        let amount = self.liquidity_position.contributed;
+
+        let config_key = self.config.key();
+        let market_id_bytes = self.market.id.to_le_bytes();
+        let market_seeds = &[Market::SEED, config_key.as_ref(), &market_id_bytes, &[self.market.bump]];
+        let market_signer = &[&market_seeds[..]];
+        transfer_checked(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                TransferChecked {
+                    from: self.vault.to_account_info(),
+                    mint: self.collateral_mint.to_account_info(),
+                    to: self.provider_collateral.to_account_info(),
+                    authority: self.market.to_account_info(),
+                },
+                market_signer,
+            ),
+            amount,
+            self.collateral_mint.decimals,
+        )?;
+
+        self.market.crowdfunded_liquidity = self.market.crowdfunded_liquidity.checked_sub(amount).ok_or(FundingError::Overflow)?;
+
+        emit!(FundingWithdrawn {
+            market_id: self.market.id,
+            provider: self.provider.key(),
+            amount,
+            total_pledged: self.market.crowdfunded_liquidity,
+        });
+        Ok(amount)
+    }
+}
+
+// =============================================================================
+// FINALIZE / CLAIM
+// =============================================================================
+
+#[derive(Accounts)]
+pub struct FinalizeFunding<'info> {
+    pub payer: Signer<'info>,
+
+    #[account(seeds = [Config::SEED], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        mut,
+        constraint = market.crowdfunded @ FundingError::CrowdfundingDisabled,
+        constraint = !market.funded @ FundingError::AlreadyFunded,
+        constraint = market.status == MarketStatus::Active @ FundingError::MarketNotActive,
+    )]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(mut, seeds = [ProtocolStats::SEED], bump = protocol_stats.bump)]
+    pub protocol_stats: Box<Account<'info, ProtocolStats>>,
+
+    /// Time override (test-clock builds only, see `instructions::clock`)
+    #[cfg(feature = "test-clock")]
+    #[account(seeds = [TestClock::SEED], bump = test_clock.bump)]
+    pub test_clock: Option<Box<Account<'info, TestClock>>>,
+}
+
+impl<'info> FinalizeFunding<'info> {
+    pub fn finalize_funding(&mut self) -> Result<()> {
+        require!(!self.config.paused, FundingError::ProtocolPaused);
+        require!(!self.market.has_ended(now!(self)?), FundingError::MarketNotActive);
+
+        let initial_liquidity = self.market.crowdfunded_liquidity;
+        require!(initial_liquidity >= self.config.min_liquidity, FundingError::BelowMinimumLiquidity);
+
+        // Counted in supply now, minted to contributors as they claim
+        let token_amount = initial_token_amount(initial_liquidity);
+        let market = &mut self.market;
+        market.reserves = initial_liquidity;
+        market.yes_supply = token_amount;
+        market.no_supply = token_amount;
+        market.funded = true;
+        self.protocol_stats.record_deposit(initial_liquidity);
+
+        emit!(FundingFinalized {
+            market_id: market.id,
+            initial_liquidity,
+            token_amount,
+        });
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct ClaimFundingTokens<'info> {
+    #[account(mut)]
+    pub provider: Signer<'info>,
+
+    #[account(seeds = [Config::SEED], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(mut, constraint = market.funded @ FundingError::NotFinalized)]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(
+        mut,
+        seeds = [LiquidityPosition::SEED, market.key().as_ref(), provider.key().as_ref()],
+        bump = liquidity_position.bump,
+        close = provider,
+    )]
+    pub liquidity_position: Box<Account<'info, LiquidityPosition>>,
+
+    #[account(mut, constraint = yes_mint.key() == market.yes_mint)]
+    pub yes_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut, constraint = no_mint.key() == market.no_mint)]
+    pub no_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(init_if_needed, payer = provider, associated_token::mint = yes_mint, associated_token::authority = provider)]
+    pub provider_yes: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(init_if_needed, payer = provider, associated_token::mint = no_mint, associated_token::authority = provider)]
+    pub provider_no: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ClaimFundingTokens<'info> {
+    pub fn claim_funding_tokens(&mut self) -> Result<u64> {
+        let total_tokens = initial_token_amount(self.market.crowdfunded_liquidity);
+        let contributed = self.liquidity_position.contributed;
+        let amount = contributor_tokens(total_tokens, self.market.crowdfunded_claimed, contributed, self.market.crowdfunded_liquidity)?;
+        self.market.crowdfunded_claimed = self.market.crowdfunded_claimed.checked_add(contributed).ok_or(FundingError::Overflow)?;
+
+        let config_seeds = &[Config::SEED, &[self.config.bump]];
+        let signer_seeds = &[&config_seeds[..]];
+        for (mint, destination) in [(&self.yes_mint, &self.provider_yes), (&self.no_mint, &self.provider_no)] {
+            mint_to(
+                CpiContext::new_with_signer(
+                    self.token_program.to_account_info(),
+                    MintTo {
+                        mint: mint.to_account_info(),
+                        to: destination.to_account_info(),
+                        authority: self.config.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                amount,
+            )?;
+        }
+
+        emit!(FundingTokensClaimed {
+            market_id: self.market.id,
+            provider: self.provider.key(),
+            amount,
+        });
+        Ok(amount)
+    }
+}
+
+/// A contributor's YES (and NO) out of `total_tokens`, pro rata to their pledge
+///
+/// Measured against the running total of pledges already claimed, so the
+/// rounding dust is carried forward instead of lost: each share is within
+/// one token of exact, and together the claims mint exactly what was
+/// counted in supply, whatever order contributors claim in.
+pub fn contributor_tokens(total_tokens: u64, claimed: u64, contributed: u64, total_contributed: u64) -> Result<u64> {
+    let claimed_after = claimed.checked_add(contributed).ok_or(FundingError::Overflow)?;
+    require!(total_contributed > 0 && claimed_after <= total_contributed, FundingError::Overflow);
+    let share_of = |pledged: u64| (total_tokens as u128 * pledged as u128 / total_contributed as u128) as u64;
+    Ok(share_of(claimed_after) - share_of(claimed))
+}
+
+#[error_code(offset = 6300)]
+pub enum FundingError {
+    #[msg("Market is already funded")]
+    AlreadyFunded,
+    #[msg("Market funding is not finalized")]
+    NotFinalized,
+    #[msg("Market is not open for funding")]
+    MarketNotActive,
+    #[msg("Market mints have not been created")]
+    MintsNotCreated,
+    #[msg("Protocol is paused")]
+    ProtocolPaused,
+    #[msg("Contribution must be greater than zero")]
+    ZeroAmount,
+    #[msg("Pledged liquidity is below the protocol minimum")]
+    BelowMinimumLiquidity,
+    #[msg("Arithmetic overflow")]
+    Overflow,
+    #[msg("Market was not created for crowdfunding")]
+    CrowdfundingDisabled,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contributor_shares_mint_exactly_supply() {
+        let pledges = [1_000_001u64, 2_333_333, 7];
+        let total: u64 = pledges.iter().sum();
+        let total_tokens = initial_token_amount(total);
+
+        for order in [[0, 1, 2], [2, 1, 0], [1, 2, 0]] {
+            let mut claimed = 0;
+            let mut minted = 0;
+            for i in order {
+                let share = contributor_tokens(total_tokens, claimed, pledges[i], total).unwrap();
+                // Within one token of the exact pro-rata share
+                let exact = total_tokens as u128 * pledges[i] as u128 / total as u128;
+                assert!(share as u128 == exact || share as u128 == exact + 1);
+                claimed += pledges[i];
+                minted += share;
+            }
+            assert_eq!(minted, total_tokens);
+        }
+
+        assert_eq!(contributor_tokens(total_tokens, 0, total, total).unwrap(), total_tokens);
+        assert!(contributor_tokens(total_tokens, 0, total + 1, total).is_err());
+        assert!(contributor_tokens(total_tokens, pledges[0], total, total).is_err());
+    }
+}
@@ -0,0 +1,53 @@
+//! Market Close
+//!
+//! Trades past a market's end time fail with `MarketEnded`, so the status
+//! has to move to `Ended` somewhere else. The trade paths can't do it on
+//! their way out: a failed transaction rolls back every write, flip
+//! included, and letting the trade succeed as a no-op instead would report
+//! success for a fill that never happened. `end_market` is that place: anyone
+//! can call it once the deadline has passed, and it only flips the status.
+//! Resolution accepts markets that are still `Active`, so calling it is
+//! never required to settle.
+
+use anchor_lang::prelude::*;
+
+use crate::instructions::clock::now;
+use crate::state::{Market, MarketStatus};
+#[cfg(feature = "test-clock")]
+use crate::state::TestClock;
+
+/// Emitted when an expired market is flipped to `Ended`
+#[event]
+pub struct MarketEnded {
+    pub market_id: u64,
+    pub end_time: u64,
+    pub timestamp: i64,
+}
+
+#[derive(Accounts)]
+pub struct EndMarket<'info> {
+    #[account(mut, constraint = market.status == MarketStatus::Active @ EndMarketError::MarketNotActive)]
+    pub market: Account<'info, Market>,
+
+    /// Time override (test-clock builds only, see `instructions::clock`)
+    #[cfg(feature = "test-clock")]
+    #[account(seeds = [TestClock::SEED], bump = test_clock.bump)]
+    pub test_clock: Option<Box<Account<'info, TestClock>>>,
+}
+
+impl<'info> EndMarket<'info> {
+    pub fn end_market(&mut self) -> Result<()> {
+        let now = now!(self)?;
+        require!(self.market.end_if_expired(now), EndMarketError::NotExpired);
+        emit!(MarketEnded { market_id: self.market.id, end_time: self.market.end_time, timestamp: now });
+        Ok(())
+    }
+}
+
+#[error_code(offset = 6500)]
+pub enum EndMarketError {
+    #[msg("Market is not active")]
+    MarketNotActive,
+    #[msg("Market has not reached its end time")]
+    NotExpired,
+}
@@ -0,0 +1,84 @@
+//! Market Extension
+//!
+//! Real-world events slip (postponed elections, rescheduled games).
+//! The creator or oracle can push a market's end time back while it is
+//! still open, instead of voiding and recreating it.
+
+use anchor_lang::prelude::*;
+
+use crate::instructions::clock::now;
+use crate::state::{Config, Market, MarketStatus};
+#[cfg(feature = "test-clock")]
+use crate::state::TestClock;
+
+/// Event emitted when a market's end time is extended
+#[event]
+pub struct MarketExtended {
+    pub market_id: u64,
+    pub old_end_time: u64,
+    pub new_end_time: u64,
+    pub extended_by: Pubkey,
+}
+
+#[derive(Accounts)]
+pub struct ExtendMarket<'info> {
+    /// Market creator or protocol oracle
+    #[account(
+        constraint = authority.key() == market.creator ||
+                     authority.key() == config.oracle @ ExtendError::Unauthorized,
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [Config::SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        constraint = market.status == MarketStatus::Active @ ExtendError::MarketNotActive,
+    )]
+    pub market: Account<'info, Market>,
+
+    /// Time override (test-clock builds only, see `instructions::clock`)
+    #[cfg(feature = "test-clock")]
+    #[account(seeds = [TestClock::SEED], bump = test_clock.bump)]
+    pub test_clock: Option<Box<Account<'info, TestClock>>>,
+}
+
+impl<'info> ExtendMarket<'info> {
+    pub fn extend_market(&mut self, new_end_time: u64) -> Result<()> {
+        let old_end_time = self.market.end_time;
+
+        require!(!self.market.has_ended(now!(self)?), ExtendError::MarketEnded);
+        require!(new_end_time > old_end_time, ExtendError::InvalidEndTime);
+        require!(new_end_time <= i64::MAX as u64, ExtendError::InvalidEndTime);
+        require!(
+            new_end_time.saturating_sub(self.market.created_at) <= self.config.max_market_duration,
+            ExtendError::DurationTooLong
+        );
+
+        self.market.end_time = new_end_time;
+
+        emit!(MarketExtended {
+            market_id: self.market.id,
+            old_end_time,
+            new_end_time,
+            extended_by: self.authority.key(),
+        });
+
+        Ok(())
+    }
+}
+
+#[error_code(offset = 6400)]
+pub enum ExtendError {
+    #[msg("Only the market creator or oracle can extend a market")]
+    Unauthorized,
+    #[msg("Market is not active")]
+    MarketNotActive,
+    #[msg("Market has already ended")]
+    MarketEnded,
+    #[msg("New end time must be later than the current end time")]
+    InvalidEndTime,
+    #[msg("Market duration exceeds protocol maximum")]
+    DurationTooLong,
+}
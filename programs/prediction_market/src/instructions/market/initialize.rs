@@ -2,11 +2,17 @@
 //!
 //! Sets up the global configuration for the prediction market protocol.
 //! This is typically called once during deployment.
+//!
+//! The protocol fee vault is created here too, as the config's ATA for the
+//! collateral mint, so it exists before the first trade pays a fee into it.
 
 use anchor_lang::prelude::*;
-use anchor_spl::token_interface::Mint;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
 
-use crate::state::Config;
+use crate::state::{Config, FeeRounding, ProtocolStats};
 
 /// Accounts required for protocol initialization
 #[derive(Accounts)]
@@ -25,15 +31,41 @@ pub struct Initialize<'info> {
     )]
     pub config: Account<'info, Config>,
 
+    /// Protocol-wide statistics (created)
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + ProtocolStats::INIT_SPACE,
+        seeds = [ProtocolStats::SEED],
+        bump,
+    )]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+
     /// Collateral token mint (e.g., USDC)
     pub collateral_mint: InterfaceAccount<'info, Mint>,
 
+    /// Protocol fee vault (created), owned by the config
+    #[account(
+        init,
+        payer = admin,
+        associated_token::mint = collateral_mint,
+        associated_token::authority = config,
+    )]
+    pub fee_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
     /// System program
     pub system_program: Program<'info, System>,
 }
 
 impl<'info> Initialize<'info> {
     /// Initialize the protocol configuration
+    ///
+    /// `protocol_fee_bps` is in basis points (100 = 1%) and must lie in
+    /// `Config::MIN_FEE_BPS..=Config::MAX_FEE_BPS`. It also seeds the
+    /// balancing and skewing fees.
     pub fn initialize(
         &mut self,
         protocol_fee_bps: u64,
@@ -43,8 +75,8 @@ impl<'info> Initialize<'info> {
         let admin_key = self.admin.key();
         let _config_seeds = &[Config::SEED, admin_key.as_ref(), &[self.config.bump]];
         
-        // Validate fee is reasonable (max 30%)
-        require!(protocol_fee_bps <= 3000, InitializeError::FeeTooHigh);
+        require!(protocol_fee_bps >= Config::MIN_FEE_BPS, InitializeError::FeeTooLow);
+        require!(protocol_fee_bps <= Config::MAX_FEE_BPS, InitializeError::FeeTooHigh);
 
         self.config.set_inner(Config {
             admin: admin_key,
@@ -55,19 +87,56 @@ impl<'info> Initialize<'info> {
             min_liquidity: 1_000_000, 
             bump: bumps.config,
             paused: false,
+            balancing_fee_bps: protocol_fee_bps,
+            skewing_fee_bps: protocol_fee_bps,
+            paused_at: 0,
+            emergency_withdraw_delay: Config::DEFAULT_EMERGENCY_WITHDRAW_DELAY,
+            max_market_duration: Config::DEFAULT_MAX_MARKET_DURATION,
+            min_market_duration: 0,
+            enforce_slippage: false,
+            event_seq: 0,
+            recipient_allowlist: None,
+            cpi_guard_enabled: false,
+            allowed_cpi_caller: None,
+            resolver_reward: 0,
+            trade_cooldown_seconds: 0,
+            min_trade_amount: 0,
+            creator_liquidity_lock_seconds: 0,
+            creator_fee_discount_bps: 0,
+            max_privacy_position: 0,
+            reference_price_tolerance_bps: Config::DEFAULT_REFERENCE_PRICE_TOLERANCE_BPS,
+            oracle_bond: 0,
+            dispute_window_seconds: Config::DEFAULT_DISPUTE_WINDOW,
+            max_collateral_decimals: Config::DEFAULT_MAX_COLLATERAL_DECIMALS,
+            fee_rounding: FeeRounding::Down,
+            fee_vault: self.fee_vault.key(),
+            post_resolution_trade_window: 0,
+            shielded_reveal_window: Config::DEFAULT_SHIELDED_REVEAL_WINDOW,
+        });
+
+        self.protocol_stats.set_inner(ProtocolStats {
+            total_markets: 0,
+            active_markets: 0,
+            total_volume: 0,
+            total_fees: 0,
+            total_value_locked: 0,
+            bump: bumps.protocol_stats,
         });
 
         msg!("Protocol initialized!");
         msg!("Admin: {}", admin_key);
         msg!("Oracle: {}", oracle);
         msg!("Fee: {} bps", protocol_fee_bps);
+        msg!("Fee vault: {}", self.fee_vault.key());
 
         Ok(())
     }
 }
 
-#[error_code]
+#[error_code(offset = 6000)]
 pub enum InitializeError {
     #[msg("Protocol fee cannot exceed 30%")]
     FeeTooHigh,
+    #[msg("Protocol fee must be at least 1 bps")]
+    FeeTooLow,
 }
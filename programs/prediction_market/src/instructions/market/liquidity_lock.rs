@@ -0,0 +1,100 @@
+//! Creator Liquidity Lock
+//!
+//! With `config.creator_liquidity_lock_seconds` set, `fund_market` does not
+//! mint the creator's initial YES/NO. They are counted in supply but held
+//! back, so the creator cannot fund a market and immediately dump the
+//! tokens. Once the lock expires, or the market resolves or is cancelled,
+//! `unlock_creator_liquidity` mints them to the creator.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{mint_to, Mint, MintTo, TokenAccount, TokenInterface};
+
+use crate::instructions::clock::now;
+use crate::state::{Config, Market};
+#[cfg(feature = "test-clock")]
+use crate::state::TestClock;
+
+/// Event emitted when the creator's locked liquidity is released
+#[event]
+pub struct CreatorLiquidityUnlocked {
+    pub market_id: u64,
+    pub creator: Pubkey,
+    /// Amount of YES and of NO minted to the creator
+    pub amount: u64,
+}
+
+#[derive(Accounts)]
+pub struct UnlockCreatorLiquidity<'info> {
+    #[account(constraint = creator.key() == market.creator @ LiquidityLockError::Unauthorized)]
+    pub creator: Signer<'info>,
+
+    #[account(seeds = [Config::SEED], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(mut)]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(mut, constraint = yes_mint.key() == market.yes_mint)]
+    pub yes_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut, constraint = no_mint.key() == market.no_mint)]
+    pub no_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut, associated_token::mint = yes_mint, associated_token::authority = creator)]
+    pub creator_yes: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, associated_token::mint = no_mint, associated_token::authority = creator)]
+    pub creator_no: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// Time override (test-clock builds only, see `instructions::clock`)
+    #[cfg(feature = "test-clock")]
+    #[account(seeds = [TestClock::SEED], bump = test_clock.bump)]
+    pub test_clock: Option<Box<Account<'info, TestClock>>>,
+}
+
+impl<'info> UnlockCreatorLiquidity<'info> {
+    pub fn unlock_creator_liquidity(&mut self) -> Result<()> {
+        let amount = self.market.locked_creator_tokens;
+        require!(amount > 0, LiquidityLockError::NothingLocked);
+        require!(self.market.liquidity_unlocked(now!(self)?), LiquidityLockError::StillLocked);
+
+        let config_seeds = &[Config::SEED, &[self.config.bump]];
+        let signer_seeds = &[&config_seeds[..]];
+        for (mint, destination) in [(&self.yes_mint, &self.creator_yes), (&self.no_mint, &self.creator_no)] {
+            mint_to(
+                CpiContext::new_with_signer(
+                    self.token_program.to_account_info(),
+                    MintTo {
+                        mint: mint.to_account_info(),
+                        to: destination.to_account_info(),
+                        authority: self.config.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                amount,
+            )?;
+        }
+
+        self.market.locked_creator_tokens = 0;
+
+        emit!(CreatorLiquidityUnlocked {
+            market_id: self.market.id,
+            creator: self.creator.key(),
+            amount,
+        });
+
+        Ok(())
+    }
+}
+
+#[error_code(offset = 6800)]
+pub enum LiquidityLockError {
+    #[msg("Only the market creator can unlock its liquidity")]
+    Unauthorized,
+    #[msg("No locked creator liquidity")]
+    NothingLocked,
+    #[msg("Creator liquidity is still locked")]
+    StillLocked,
+}
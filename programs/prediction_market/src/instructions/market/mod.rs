@@ -1,7 +1,25 @@
 pub mod initialize;
+pub mod update_config;
 pub mod create_market;
+pub mod crowdfund;
+pub mod extend_market;
+pub mod end_market;
 pub mod resolve;
+pub mod oracle_bond;
+pub mod rescue;
+pub mod transfer_ownership;
+pub mod liquidity_lock;
+pub mod stats;
 
 pub use initialize::*;
+pub use update_config::*;
 pub use create_market::*;
+pub use crowdfund::*;
+pub use extend_market::*;
+pub use end_market::*;
 pub use resolve::*;
+pub use oracle_bond::*;
+pub use rescue::*;
+pub use transfer_ownership::*;
+pub use liquidity_lock::*;
+pub use stats::*;
@@ -0,0 +1,219 @@
+//! Oracle Bond Staking & Slashing
+//!
+//! Gives the single oracle economic skin in the game without a full
+//! multi-resolver system:
+//!
+//! 1. The oracle stakes collateral with `deposit_oracle_bond`
+//! 2. Every resolution holds the bond for `config.dispute_window_seconds`
+//! 3. If a resolution is disputed in that window and the admin upholds the
+//!    dispute, `slash_oracle_bond` moves `config.oracle_bond` to the fee vault
+//! 4. Once no window is open, `withdraw_oracle_bond` returns the stake
+//!
+//! Slashing does not rewrite the outcome; it only makes a bad resolution
+//! costly. Bonds are keyed by oracle, so a rotated-out oracle can still be
+//! slashed for its past resolutions and later withdraw what is left.
+
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::instructions::clock::now;
+use crate::state::{Config, Market, OracleBond};
+#[cfg(feature = "test-clock")]
+use crate::state::TestClock;
+
+#[event]
+pub struct OracleBondDeposited {
+    pub oracle: Pubkey,
+    pub amount: u64,
+    pub total: u64,
+}
+
+#[event]
+pub struct OracleBondWithdrawn {
+    pub oracle: Pubkey,
+    pub amount: u64,
+    pub remaining: u64,
+}
+
+#[event]
+pub struct OracleBondSlashed {
+    pub market_id: u64,
+    pub oracle: Pubkey,
+    pub admin: Pubkey,
+    pub amount: u64,
+    pub remaining: u64,
+}
+
+#[derive(Accounts)]
+pub struct DepositOracleBond<'info> {
+    #[account(mut, constraint = oracle.key() == config.oracle @ OracleBondError::Unauthorized)]
+    pub oracle: Signer<'info>,
+
+    #[account(seeds = [Config::SEED], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        init_if_needed,
+        payer = oracle,
+        space = 8 + OracleBond::INIT_SPACE,
+        seeds = [OracleBond::SEED, oracle.key().as_ref()],
+        bump,
+    )]
+    pub oracle_bond: Box<Account<'info, OracleBond>>,
+
+    #[account(address = config.collateral_mint)]
+    pub collateral_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut, associated_token::mint = collateral_mint, associated_token::authority = oracle)]
+    pub oracle_collateral: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = oracle,
+        associated_token::mint = collateral_mint,
+        associated_token::authority = oracle_bond,
+    )]
+    pub bond_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> DepositOracleBond<'info> {
+    pub fn deposit_oracle_bond(&mut self, amount: u64, bump: u8) -> Result<()> {
+        require!(amount > 0, OracleBondError::ZeroAmount);
+
+        transfer_checked(CpiContext::new(self.token_program.to_account_info(), TransferChecked { from: self.oracle_collateral.to_account_info(), mint: self.collateral_mint.to_account_info(), to: self.bond_vault.to_account_info(), authority: self.oracle.to_account_info() }), amount, self.collateral_mint.decimals)?;
+
+        let bond = &mut self.oracle_bond;
+        if bond.oracle == Pubkey::default() {
+            bond.oracle = self.oracle.key();
+            bond.bump = bump;
+        }
+        bond.amount = bond.amount.checked_add(amount).ok_or(OracleBondError::Overflow)?;
+
+        emit!(OracleBondDeposited { oracle: bond.oracle, amount, total: bond.amount });
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct WithdrawOracleBond<'info> {
+    pub oracle: Signer<'info>,
+
+    #[account(seeds = [Config::SEED], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(mut, seeds = [OracleBond::SEED, oracle.key().as_ref()], bump = oracle_bond.bump)]
+    pub oracle_bond: Box<Account<'info, OracleBond>>,
+
+    #[account(address = config.collateral_mint)]
+    pub collateral_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut, associated_token::mint = collateral_mint, associated_token::authority = oracle)]
+    pub oracle_collateral: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, associated_token::mint = collateral_mint, associated_token::authority = oracle_bond)]
+    pub bond_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Time override (test-clock builds only, see `instructions::clock`)
+    #[cfg(feature = "test-clock")]
+    #[account(seeds = [TestClock::SEED], bump = test_clock.bump)]
+    pub test_clock: Option<Box<Account<'info, TestClock>>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> WithdrawOracleBond<'info> {
+    /// Return stake to the oracle once every dispute window it backs has closed
+    pub fn withdraw_oracle_bond(&mut self, amount: u64) -> Result<()> {
+        require!(amount > 0, OracleBondError::ZeroAmount);
+        require!(self.oracle_bond.unlocked(now!(self)?), OracleBondError::BondLocked);
+        require!(amount <= self.oracle_bond.amount, OracleBondError::InsufficientBond);
+
+        let oracle_key = self.oracle.key();
+        let bond_seeds = &[OracleBond::SEED, oracle_key.as_ref(), &[self.oracle_bond.bump]];
+        let bond_signer = &[&bond_seeds[..]];
+        transfer_checked(CpiContext::new_with_signer(self.token_program.to_account_info(), TransferChecked { from: self.bond_vault.to_account_info(), mint: self.collateral_mint.to_account_info(), to: self.oracle_collateral.to_account_info(), authority: self.oracle_bond.to_account_info() }, bond_signer), amount, self.collateral_mint.decimals)?;
+
+        self.oracle_bond.amount -= amount;
+        emit!(OracleBondWithdrawn { oracle: oracle_key, amount, remaining: self.oracle_bond.amount });
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct SlashOracleBond<'info> {
+    #[account(constraint = admin.key() == config.admin @ OracleBondError::Unauthorized)]
+    pub admin: Signer<'info>,
+
+    #[account(seeds = [Config::SEED], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(mut)]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(mut, seeds = [OracleBond::SEED, market.resolved_by.as_ref()], bump = oracle_bond.bump)]
+    pub oracle_bond: Box<Account<'info, OracleBond>>,
+
+    #[account(address = config.collateral_mint)]
+    pub collateral_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut, associated_token::mint = collateral_mint, associated_token::authority = oracle_bond)]
+    pub bond_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Protocol fee vault, which receives the slashed bond
+    #[account(mut, address = config.fee_vault @ OracleBondError::InvalidFeeVault)]
+    pub fee_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Time override (test-clock builds only, see `instructions::clock`)
+    #[cfg(feature = "test-clock")]
+    #[account(seeds = [TestClock::SEED], bump = test_clock.bump)]
+    pub test_clock: Option<Box<Account<'info, TestClock>>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> SlashOracleBond<'info> {
+    /// Uphold a dispute against `market`'s resolution, forfeiting up to `config.oracle_bond`
+    pub fn slash_oracle_bond(&mut self) -> Result<u64> {
+        let now = now!(self)?;
+        require!(self.market.dispute_open(now, self.config.dispute_window_seconds), OracleBondError::DisputeWindowClosed);
+
+        let amount = self.config.oracle_bond.min(self.oracle_bond.amount);
+        require!(amount > 0, OracleBondError::InsufficientBond);
+
+        let oracle_key = self.oracle_bond.oracle;
+        let bond_seeds = &[OracleBond::SEED, oracle_key.as_ref(), &[self.oracle_bond.bump]];
+        let bond_signer = &[&bond_seeds[..]];
+        transfer_checked(CpiContext::new_with_signer(self.token_program.to_account_info(), TransferChecked { from: self.bond_vault.to_account_info(), mint: self.collateral_mint.to_account_info(), to: self.fee_vault.to_account_info(), authority: self.oracle_bond.to_account_info() }, bond_signer), amount, self.collateral_mint.decimals)?;
+
+        self.oracle_bond.amount -= amount;
+        self.market.bond_slashed = true;
+
+        emit!(OracleBondSlashed { market_id: self.market.id, oracle: oracle_key, admin: self.admin.key(), amount, remaining: self.oracle_bond.amount });
+        Ok(amount)
+    }
+}
+
+#[error_code(offset = 6700)]
+pub enum OracleBondError {
+    #[msg("Not authorized to manage this bond")]
+    Unauthorized,
+    #[msg("Bond amount must be greater than zero")]
+    ZeroAmount,
+    #[msg("Bond is held until the latest dispute window closes")]
+    BondLocked,
+    #[msg("Bond does not hold enough collateral")]
+    InsufficientBond,
+    #[msg("Resolution is outside its dispute window or was already slashed")]
+    DisputeWindowClosed,
+    #[msg("Fee vault does not match the protocol config")]
+    InvalidFeeVault,
+    #[msg("Arithmetic overflow")]
+    Overflow,
+}
@@ -0,0 +1,87 @@
+//! Stuck Token Rescue
+//!
+//! Users sometimes send unrelated SPL tokens to a market-owned account by
+//! mistake. The admin can move those tokens out, but never the market's
+//! collateral mint, so this hatch cannot be used to drain reserves.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::state::{Config, Market};
+
+/// Event emitted when stray tokens are moved out of a market-owned account
+#[event]
+pub struct TokensRescued {
+    pub market_id: u64,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub destination: Pubkey,
+}
+
+#[derive(Accounts)]
+pub struct RescueTokens<'info> {
+    #[account(constraint = admin.key() == config.admin @ RescueError::Unauthorized)]
+    pub admin: Signer<'info>,
+
+    #[account(seeds = [Config::SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    pub market: Account<'info, Market>,
+
+    /// Mint of the stray tokens; the market's collateral is off limits
+    #[account(constraint = mint.key() != market.collateral_mint @ RescueError::CollateralNotRescuable)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut, token::mint = mint, token::authority = market)]
+    pub source: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, token::mint = mint)]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> RescueTokens<'info> {
+    pub fn rescue_tokens(&mut self, amount: u64) -> Result<()> {
+        require!(amount > 0 && amount <= self.source.amount, RescueError::InvalidAmount);
+
+        let config_key = self.config.key();
+        let market_id_bytes = self.market.id.to_le_bytes();
+        let market_seeds = &[Market::SEED, config_key.as_ref(), &market_id_bytes, &[self.market.bump]];
+        let market_signer = &[&market_seeds[..]];
+
+        transfer_checked(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                TransferChecked {
+                    from: self.source.to_account_info(),
+                    mint: self.mint.to_account_info(),
+                    to: self.destination.to_account_info(),
+                    authority: self.market.to_account_info(),
+                },
+                market_signer,
+            ),
+            amount,
+            self.mint.decimals,
+        )?;
+
+        emit!(TokensRescued {
+            market_id: self.market.id,
+            mint: self.mint.key(),
+            amount,
+            destination: self.destination.key(),
+        });
+
+        Ok(())
+    }
+}
+
+#[error_code(offset = 7000)]
+pub enum RescueError {
+    #[msg("Only the admin can rescue tokens")]
+    Unauthorized,
+    #[msg("The market's collateral cannot be rescued")]
+    CollateralNotRescuable,
+    #[msg("Rescue amount must be positive and within the account balance")]
+    InvalidAmount,
+}
@@ -10,6 +10,55 @@
 //! 4. Market transitions to Resolved status
 //! 5. Winners can redeem their tokens
 //!
+//! Resolution is frozen while the protocol is paused, like trading.
+//!
+//! The oracle may attach an `evidence_hash` (e.g. the hash of the source
+//! document or URL it resolved from). It is stored on the market and emitted,
+//! giving disputes a tamper-evident link to the off-chain evidence.
+//!
+//! If nobody holds the winning side, there is no one to pay out to. The
+//! market is then voided (`Cancelled`) and every holder is refunded.
+//!
+//! ## Resolution Source
+//!
+//! A creator may commit the market to a source at creation by storing its
+//! `resolution_source_hash`. `resolve_market` and `resolve_draw` must then be
+//! given the matching preimage (e.g. the URL), so the oracle can't pick a
+//! friendlier source after the fact. Attestations and batches carry no
+//! source and can only resolve markets that declared none.
+//!
+//! ## Draws
+//!
+//! Some questions can legitimately end even (a tied match, a metric landing
+//! exactly on the threshold). `resolve_draw` records `Outcome::Draw` and
+//! voids the market the same way, so both sides redeem through the refund
+//! path while events and analytics still see why.
+//!
+//! ## Signed Attestations
+//!
+//! `resolve_with_attestation` accepts a verdict the oracle signed off-chain
+//! (see `attestation`), so any relayer can submit it. The signed message
+//! names the market and carries a deadline after which it is refused.
+//!
+//! ## Batch Resolution
+//!
+//! `resolve_batch` resolves a cluster of correlated markets (e.g. every
+//! market about one event) in a single instruction, so they all settle in
+//! the same slot. The markets are passed as writable `remaining_accounts`,
+//! one per outcome, and either all resolve or none do.
+//!
+//! ## Resolver Reward
+//!
+//! If `config.resolver_reward` is set, the resolver is paid up to that amount
+//! from the protocol fee vault for the market's collateral, once per market. A vault short of the full reward
+//! pays out what it holds.
+//!
+//! ## Oracle Bond
+//!
+//! While `config.oracle_bond` is set, the oracle must pass its `OracleBond`
+//! holding at least that much to resolve, by either path. Each resolution
+//! holds the bond for `config.dispute_window_seconds` (see `oracle_bond`).
+//!
 //! ## Oracle Integration
 //!
 //! The oracle can be:
@@ -17,10 +66,22 @@
 //! - **Multisig**: A committee of trusted resolvers
 //! - **Decentralized Oracle**: Integration with Pyth, Chainlink, etc.
 //! - **UMA-style Optimistic Oracle**: Dispute-based resolution
+//!
+//! Every path resolves on the signature of the single `config.oracle` key;
+//! there is no on-chain vote tally. A committee sits behind that key (e.g.
+//! a multisig program), which is what counts its members' approvals and
+//! keeps one member from signing twice towards the threshold.
 
 use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use anchor_lang::solana_program::sysvar::instructions as sysvar_instructions;
 
-use crate::state::{Config, Market, MarketStatus, Outcome};
+use crate::instructions::clock::now;
+use crate::instructions::attestation::{verify_preceding_ed25519, Attestation, AttestationError};
+use crate::state::{Config, Market, MarketStatus, OracleBond, Outcome, ProtocolStats};
+#[cfg(feature = "test-clock")]
+use crate::state::TestClock;
 
 /// Event emitted when a market is resolved
 #[event]
@@ -28,7 +89,21 @@ pub struct MarketResolved {
     pub market_id: u64,
     pub outcome: Outcome,
     pub resolver: Pubkey,
+    /// Hash of the resolution evidence (zeros = none given)
+    pub evidence_hash: [u8; 32],
     pub timestamp: i64,
+    /// Implied YES probability (bps, 5000 at balance) just before resolution
+    pub final_yes_probability_bps: u64,
+    /// Implied NO probability (bps, 5000 at balance) just before resolution
+    pub final_no_probability_bps: u64,
+    /// Collateral traded over the market's lifetime
+    pub total_volume: u64,
+    /// Reserves available to winners at resolution
+    pub reserves_at_resolution: u64,
+    /// Reward paid to the resolver from the fee vault
+    pub resolver_reward: u64,
+    /// Market was voided for refunds (a draw, or the winning side had no supply)
+    pub refund_all: bool,
 }
 
 /// Accounts for market resolution
@@ -54,32 +129,62 @@ pub struct ResolveMarket<'info> {
                      market.status == MarketStatus::Ended @ ResolveError::CannotResolve,
     )]
     pub market: Account<'info, Market>,
+
+    #[account(mut, seeds = [ProtocolStats::SEED], bump = protocol_stats.bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+
+    /// The oracle's bond, required only while `config.oracle_bond` is non-zero
+    #[account(mut, constraint = oracle_bond.oracle == config.oracle @ ResolveError::InvalidOracleBond)]
+    pub oracle_bond: Option<Box<Account<'info, OracleBond>>>,
+
+    /// Reward accounts, required only while `config.resolver_reward` is non-zero
+    pub collateral_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    #[account(mut)]
+    pub fee_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(mut)]
+    pub resolver_collateral: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    /// Time override (test-clock builds only, see `instructions::clock`)
+    #[cfg(feature = "test-clock")]
+    #[account(seeds = [TestClock::SEED], bump = test_clock.bump)]
+    pub test_clock: Option<Box<Account<'info, TestClock>>>,
 }
 
 impl<'info> ResolveMarket<'info> {
     /// Resolve the market with the winning outcome
-    pub fn resolve_market(&mut self, yes_wins: bool) -> Result<()> {
-        let clock = Clock::get()?;
-        
-        // Ensure market has ended
-        require!(
-            clock.unix_timestamp >= self.market.end_time as i64,
-            ResolveError::MarketNotEnded
-        );
+    pub fn resolve_market(&mut self, yes_wins: bool, evidence_hash: [u8; 32], resolution_source: Vec<u8>) -> Result<()> {
+        self.resolve(Outcome::winner(yes_wins), evidence_hash, &resolution_source)
+    }
 
-        // Set the outcome
-        self.market.outcome = if yes_wins {
-            Outcome::Yes
-        } else {
-            Outcome::No
-        };
-        self.market.status = MarketStatus::Resolved;
+    /// Resolve the market as a draw, refunding both sides
+    pub fn resolve_draw(&mut self, evidence_hash: [u8; 32], resolution_source: Vec<u8>) -> Result<()> {
+        self.resolve(Outcome::Draw, evidence_hash, &resolution_source)
+    }
+
+    fn resolve(&mut self, outcome: Outcome, evidence_hash: [u8; 32], resolution_source: &[u8]) -> Result<()> {
+        let now = now!(self)?;
+        check_resolvable(&self.config, &self.market, now, Some(resolution_source))?;
+        bond_resolution(&self.config, &mut self.market, self.oracle_bond.as_deref_mut().map(|bond| &mut **bond), now)?;
+        let (final_yes_probability_bps, final_no_probability_bps, refund_all) =
+            record_outcome(&mut self.market, &mut self.protocol_stats, outcome, evidence_hash);
+        let resolver_reward = self.pay_resolver_reward()?;
 
         emit!(MarketResolved {
             market_id: self.market.id,
             outcome: self.market.outcome,
             resolver: self.oracle.key(),
-            timestamp: clock.unix_timestamp,
+            evidence_hash,
+            timestamp: now,
+            final_yes_probability_bps,
+            final_no_probability_bps,
+            total_volume: self.market.total_volume,
+            reserves_at_resolution: self.market.reserves,
+            resolver_reward,
+            refund_all,
         });
 
         msg!(
@@ -90,9 +195,234 @@ impl<'info> ResolveMarket<'info> {
 
         Ok(())
     }
+
+    /// Pay the resolver from the fee vault, at most once per market
+    fn pay_resolver_reward(&mut self) -> Result<u64> {
+        if self.config.resolver_reward == 0 || self.market.reward_paid {
+            return Ok(0);
+        }
+
+        let (Some(mint), Some(fee_vault), Some(resolver_collateral), Some(token_program)) =
+            (&self.collateral_mint, &self.fee_vault, &self.resolver_collateral, &self.token_program)
+        else {
+            return err!(ResolveError::RewardAccountsMissing);
+        };
+        require_keys_eq!(mint.key(), self.market.collateral_mint, ResolveError::InvalidRewardAccount);
+        require_keys_eq!(resolver_collateral.mint, mint.key(), ResolveError::InvalidRewardAccount);
+        require_keys_eq!(
+            fee_vault.key(),
+            Config::fee_vault_for(&self.config.key(), &mint.key(), &token_program.key()),
+            ResolveError::InvalidRewardAccount
+        );
+
+        let reward = self.config.resolver_reward.min(fee_vault.amount);
+        if reward > 0 {
+            let config_seeds = &[Config::SEED, &[self.config.bump]];
+            let signer_seeds = &[&config_seeds[..]];
+            transfer_checked(
+                CpiContext::new_with_signer(
+                    token_program.to_account_info(),
+                    TransferChecked {
+                        from: fee_vault.to_account_info(),
+                        mint: mint.to_account_info(),
+                        to: resolver_collateral.to_account_info(),
+                        authority: self.config.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                reward,
+                mint.decimals,
+            )?;
+        }
+
+        self.market.reward_paid = true;
+        Ok(reward)
+    }
+}
+
+/// Accounts for relaying an oracle-signed resolution
+#[derive(Accounts)]
+pub struct ResolveWithAttestation<'info> {
+    /// Anyone may submit; the oracle's authority comes from the signature
+    pub relayer: Signer<'info>,
+
+    #[account(seeds = [Config::SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        constraint = market.status == MarketStatus::Active ||
+                     market.status == MarketStatus::Ended @ ResolveError::CannotResolve,
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(mut, seeds = [ProtocolStats::SEED], bump = protocol_stats.bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+
+    /// The oracle's bond, required only while `config.oracle_bond` is non-zero
+    #[account(mut, constraint = oracle_bond.oracle == config.oracle @ ResolveError::InvalidOracleBond)]
+    pub oracle_bond: Option<Box<Account<'info, OracleBond>>>,
+
+    /// CHECK: Instructions sysvar, read for the preceding Ed25519 instruction
+    #[account(address = sysvar_instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    /// Time override (test-clock builds only, see `instructions::clock`)
+    #[cfg(feature = "test-clock")]
+    #[account(seeds = [TestClock::SEED], bump = test_clock.bump)]
+    pub test_clock: Option<Box<Account<'info, TestClock>>>,
+}
+
+impl<'info> ResolveWithAttestation<'info> {
+    /// Resolve from a verdict the oracle signed off-chain
+    ///
+    /// No resolver reward is paid: the oracle did the work, the relayer only paid the fee.
+    pub fn resolve_with_attestation(&mut self, yes_wins: bool, signature: [u8; 64], message: Vec<u8>) -> Result<()> {
+        let now = now!(self)?;
+        let attestation = Attestation::from_message(&message)?;
+        require!(
+            attestation.market == self.market.key() && attestation.market_id == self.market.id && attestation.yes_wins == yes_wins,
+            AttestationError::MarketMismatch
+        );
+        require!(now <= attestation.deadline, AttestationError::Expired);
+        verify_preceding_ed25519(&self.instructions_sysvar, &self.config.oracle, &signature, &message)?;
+
+        check_resolvable(&self.config, &self.market, now, None)?;
+        bond_resolution(&self.config, &mut self.market, self.oracle_bond.as_deref_mut().map(|bond| &mut **bond), now)?;
+        let (final_yes_probability_bps, final_no_probability_bps, refund_all) =
+            record_outcome(&mut self.market, &mut self.protocol_stats, Outcome::winner(yes_wins), attestation.evidence_hash);
+
+        emit!(MarketResolved {
+            market_id: self.market.id,
+            outcome: self.market.outcome,
+            resolver: self.config.oracle,
+            evidence_hash: attestation.evidence_hash,
+            timestamp: now,
+            final_yes_probability_bps,
+            final_no_probability_bps,
+            total_volume: self.market.total_volume,
+            reserves_at_resolution: self.market.reserves,
+            resolver_reward: 0,
+            refund_all,
+        });
+
+        msg!("Market {} resolved by attestation: {:?}", self.market.id, self.market.outcome);
+        Ok(())
+    }
+}
+
+/// Accounts for resolving several markets at once
+///
+/// The markets themselves come in `remaining_accounts`, in outcome order.
+#[derive(Accounts)]
+pub struct ResolveBatch<'info> {
+    #[account(constraint = oracle.key() == config.oracle @ ResolveError::Unauthorized)]
+    pub oracle: Signer<'info>,
+
+    #[account(seeds = [Config::SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(mut, seeds = [ProtocolStats::SEED], bump = protocol_stats.bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+
+    /// The oracle's bond, required only while `config.oracle_bond` is non-zero
+    #[account(mut, constraint = oracle_bond.oracle == config.oracle @ ResolveError::InvalidOracleBond)]
+    pub oracle_bond: Option<Box<Account<'info, OracleBond>>>,
+
+    /// Time override (test-clock builds only, see `instructions::clock`)
+    #[cfg(feature = "test-clock")]
+    #[account(seeds = [TestClock::SEED], bump = test_clock.bump)]
+    pub test_clock: Option<Box<Account<'info, TestClock>>>,
+}
+
+impl<'info> ResolveBatch<'info> {
+    /// Resolve each market in `markets` with the matching outcome, all or nothing
+    ///
+    /// No resolver reward is paid, as with attestations.
+    pub fn resolve_batch(&mut self, markets: &'info [AccountInfo<'info>], outcomes: Vec<bool>, evidence_hash: [u8; 32]) -> Result<()> {
+        require!(!outcomes.is_empty() && outcomes.len() == markets.len(), ResolveError::InvalidBatch);
+        let now = now!(self)?;
+
+        for (info, yes_wins) in markets.iter().zip(outcomes) {
+            require!(info.is_writable, ResolveError::InvalidBatch);
+            let mut market = Account::<Market>::try_from(info)?;
+            require!(
+                market.status == MarketStatus::Active || market.status == MarketStatus::Ended,
+                ResolveError::CannotResolve
+            );
+
+            check_resolvable(&self.config, &market, now, None)?;
+            bond_resolution(&self.config, &mut market, self.oracle_bond.as_deref_mut().map(|bond| &mut **bond), now)?;
+            let (final_yes_probability_bps, final_no_probability_bps, refund_all) =
+                record_outcome(&mut market, &mut self.protocol_stats, Outcome::winner(yes_wins), evidence_hash);
+
+            emit!(MarketResolved {
+                market_id: market.id,
+                outcome: market.outcome,
+                resolver: self.oracle.key(),
+                evidence_hash,
+                timestamp: now,
+                final_yes_probability_bps,
+                final_no_probability_bps,
+                total_volume: market.total_volume,
+                reserves_at_resolution: market.reserves,
+                resolver_reward: 0,
+                refund_all,
+            });
+
+            // Persist now, so a market listed twice fails the status check above
+            market.exit(&crate::ID)?;
+        }
+
+        msg!("Resolved {} markets in one batch", markets.len());
+        Ok(())
+    }
+}
+
+/// Preconditions shared by every resolution path
+fn check_resolvable(config: &Config, market: &Market, now: i64, resolution_source: Option<&[u8]>) -> Result<()> {
+    // A contested outcome must not be locked in mid-incident
+    require!(!config.paused, ResolveError::ProtocolPaused);
+
+    // Ensure market has ended
+    require!(market.has_ended(now), ResolveError::MarketNotEnded);
+    require!(market.funded, ResolveError::MarketNotFunded);
+    require!(market.accepts_resolution_source(resolution_source), ResolveError::ResolutionSourceMismatch);
+    Ok(())
+}
+
+/// Check the oracle's bond covers `config.oracle_bond`, hold it through the dispute window, and stamp the resolver
+fn bond_resolution(config: &Config, market: &mut Market, oracle_bond: Option<&mut OracleBond>, now: i64) -> Result<()> {
+    match oracle_bond {
+        Some(bond) => {
+            require!(bond.amount >= config.oracle_bond, ResolveError::InsufficientOracleBond);
+            bond.lock_until(now.saturating_add(config.dispute_window_seconds));
+        }
+        None => require!(config.oracle_bond == 0, ResolveError::InsufficientOracleBond),
+    }
+    market.resolved_at = now;
+    market.resolved_by = config.oracle;
+    Ok(())
+}
+
+/// Set the outcome and status, returning the closing (YES, NO) odds and whether the market was voided
+fn record_outcome(market: &mut Market, protocol_stats: &mut ProtocolStats, outcome: Outcome, evidence_hash: [u8; 32]) -> (u64, u64, bool) {
+    // Snapshot the closing odds for calibration analysis
+    let (final_yes_probability_bps, final_no_probability_bps) = market.implied_odds();
+
+    market.outcome = outcome;
+    market.evidence_hash = evidence_hash;
+    let refund_all = match outcome {
+        Outcome::Yes => market.yes_supply == 0,
+        Outcome::No => market.no_supply == 0,
+        Outcome::Draw | Outcome::Undetermined => true,
+    };
+    market.status = if refund_all { MarketStatus::Cancelled } else { MarketStatus::Resolved };
+    protocol_stats.record_market_resolved();
+    (final_yes_probability_bps, final_no_probability_bps, refund_all)
 }
 
-#[error_code]
+#[error_code(offset = 6600)]
 pub enum ResolveError {
     #[msg("Only authorized oracle can resolve markets")]
     Unauthorized,
@@ -100,4 +430,20 @@ pub enum ResolveError {
     CannotResolve,
     #[msg("Market has not ended yet")]
     MarketNotEnded,
+    #[msg("Market was never funded")]
+    MarketNotFunded,
+    #[msg("Protocol is paused")]
+    ProtocolPaused,
+    #[msg("Resolver reward is enabled but reward accounts were not provided")]
+    RewardAccountsMissing,
+    #[msg("Reward account does not match the market collateral or fee vault")]
+    InvalidRewardAccount,
+    #[msg("Oracle bond is missing or below the configured amount")]
+    InsufficientOracleBond,
+    #[msg("Bond account does not belong to the oracle")]
+    InvalidOracleBond,
+    #[msg("Batch needs one writable market per outcome")]
+    InvalidBatch,
+    #[msg("Resolution source does not match the one declared at creation")]
+    ResolutionSourceMismatch,
 }
@@ -0,0 +1,134 @@
+//! Protocol and Market Statistics Views
+//!
+//! Read-only access to the aggregate `ProtocolStats` account and to a
+//! per-market solvency summary. Call via simulation to read the counters
+//! as return data.
+//!
+//! `assert_market_consistent` is permissionless: anyone can check a
+//! market's reserves against the curve invariant and its vault balance.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+use crate::state::{Config, Market, MarketStatus, ProtocolStats};
+
+/// Accounts for reading protocol statistics
+#[derive(Accounts)]
+pub struct GetProtocolStats<'info> {
+    #[account(seeds = [ProtocolStats::SEED], bump = protocol_stats.bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+}
+
+impl<'info> GetProtocolStats<'info> {
+    /// Return a snapshot of the protocol-wide counters
+    pub fn get_protocol_stats(&self) -> Result<ProtocolStats> {
+        Ok((*self.protocol_stats).clone())
+    }
+}
+
+/// Snapshot of a market's pool and pending private payouts
+///
+/// The market vault should cover `reserves`, and the per-claim vaults
+/// together `total_locked_in_claims`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MarketInfo {
+    /// Config the market belongs to; `market_id` is only unique within it
+    pub config: Pubkey,
+    pub config_version: u8,
+    pub market_id: u64,
+    pub status: MarketStatus,
+    pub reserves: u64,
+    /// Share of `reserves` backing YES exposure (see `Market::outcome_reserves`)
+    pub yes_reserves: u64,
+    /// Share of `reserves` backing NO exposure
+    pub no_reserves: u64,
+    pub yes_supply: u64,
+    pub no_supply: u64,
+    pub total_volume: u64,
+    pub total_locked_in_claims: u64,
+}
+
+/// Accounts for reading a market summary
+#[derive(Accounts)]
+pub struct GetMarketInfo<'info> {
+    #[account(seeds = [Config::SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(seeds = [Market::SEED, config.key().as_ref(), market.id.to_le_bytes().as_ref()], bump = market.bump)]
+    pub market: Account<'info, Market>,
+}
+
+impl<'info> GetMarketInfo<'info> {
+    /// Return the market's pool counters and collateral held for claims
+    pub fn get_market_info(&self) -> Result<MarketInfo> {
+        let market = &self.market;
+        let (yes_reserves, no_reserves) = market.outcome_reserves();
+        Ok(MarketInfo {
+            config: self.config.key(),
+            config_version: Config::VERSION,
+            market_id: market.id,
+            status: market.status,
+            reserves: market.reserves,
+            yes_reserves,
+            no_reserves,
+            yes_supply: market.yes_supply,
+            no_supply: market.no_supply,
+            total_volume: market.total_volume,
+            total_locked_in_claims: market.total_locked_in_claims,
+        })
+    }
+}
+
+/// Event emitted by `assert_market_consistent` with the full diagnostics
+#[event]
+pub struct MarketConsistencyChecked {
+    pub market_id: u64,
+    pub reserves: u64,
+    /// √(YES² + NO²) for the current supplies
+    pub invariant_reserves: u64,
+    /// |reserves − invariant_reserves|
+    pub curve_drift: u64,
+    pub tolerance: u64,
+    pub vault_balance: u64,
+    /// Held in the per-claim vaults, reported for reconciliation only
+    pub total_locked_in_claims: u64,
+    pub curve_ok: bool,
+    pub solvent: bool,
+}
+
+/// Accounts for the permissionless consistency check
+#[derive(Accounts)]
+pub struct AssertMarketConsistent<'info> {
+    pub market: Account<'info, Market>,
+
+    #[account(associated_token::mint = market.collateral_mint, associated_token::authority = market)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+}
+
+impl<'info> AssertMarketConsistent<'info> {
+    /// Check the curve invariant and vault solvency, returning whether both hold
+    ///
+    /// Never fails on an inconsistency, so the emitted diagnostics survive
+    /// for monitoring bots; callers that want a hard stop check the result.
+    pub fn assert_market_consistent(&self, tolerance: u64) -> Result<bool> {
+        let market = &self.market;
+        let invariant_reserves = market.invariant_reserves();
+        let curve_drift = market.reserves.abs_diff(invariant_reserves);
+        let curve_ok = curve_drift <= tolerance;
+        // Claim collateral already left for its own vault, so the market vault backs reserves alone
+        let solvent = self.vault.amount >= market.reserves;
+
+        emit!(MarketConsistencyChecked {
+            market_id: market.id,
+            reserves: market.reserves,
+            invariant_reserves,
+            curve_drift,
+            tolerance,
+            vault_balance: self.vault.amount,
+            total_locked_in_claims: market.total_locked_in_claims,
+            curve_ok,
+            solvent,
+        });
+        Ok(curve_ok && solvent)
+    }
+}
@@ -0,0 +1,51 @@
+//! Market Ownership Transfer
+//!
+//! Lets the current creator hand a market to another authority, such as
+//! a multisig or DAO, e.g. after a key compromise or for shared curation.
+
+use anchor_lang::prelude::*;
+
+use crate::state::Market;
+
+/// Event emitted when a market changes creator
+#[event]
+pub struct MarketOwnershipTransferred {
+    pub market_id: u64,
+    pub previous_creator: Pubkey,
+    pub new_creator: Pubkey,
+}
+
+#[derive(Accounts)]
+pub struct TransferMarketOwnership<'info> {
+    #[account(constraint = creator.key() == market.creator @ OwnershipError::Unauthorized)]
+    pub creator: Signer<'info>,
+
+    #[account(mut)]
+    pub market: Account<'info, Market>,
+}
+
+impl<'info> TransferMarketOwnership<'info> {
+    pub fn transfer_market_ownership(&mut self, new_creator: Pubkey) -> Result<()> {
+        require!(new_creator != Pubkey::default(), OwnershipError::InvalidNewCreator);
+        require!(new_creator != self.market.creator, OwnershipError::InvalidNewCreator);
+
+        let previous_creator = self.market.creator;
+        self.market.creator = new_creator;
+
+        emit!(MarketOwnershipTransferred {
+            market_id: self.market.id,
+            previous_creator,
+            new_creator,
+        });
+
+        Ok(())
+    }
+}
+
+#[error_code(offset = 6900)]
+pub enum OwnershipError {
+    #[msg("Only the market creator can transfer ownership")]
+    Unauthorized,
+    #[msg("New creator must be a different, non-default key")]
+    InvalidNewCreator,
+}
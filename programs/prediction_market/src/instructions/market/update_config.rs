@@ -0,0 +1,257 @@
+//! Protocol Configuration Updates
+//!
+//! Lets the admin adjust protocol-wide settings after deployment.
+//! Every field is optional so a single call can change just one setting.
+//!
+//! Oracle rotation has its own `set_oracle` instruction, so it can be
+//! granted (e.g. through a multisig policy) without full config authority.
+
+use anchor_lang::prelude::*;
+
+use crate::instructions::clock::now;
+use crate::state::{Config, FeeRounding};
+#[cfg(feature = "test-clock")]
+use crate::state::TestClock;
+
+/// Settings the admin may change. `None` leaves a field untouched.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default)]
+pub struct ConfigUpdate {
+    pub paused: Option<bool>,
+    pub balancing_fee_bps: Option<u64>,
+    pub skewing_fee_bps: Option<u64>,
+    pub emergency_withdraw_delay: Option<i64>,
+    pub max_market_duration: Option<u64>,
+    pub min_market_duration: Option<u64>,
+    pub enforce_slippage: Option<bool>,
+    /// `Pubkey::default()` clears the allowlist
+    pub recipient_allowlist: Option<Pubkey>,
+    pub cpi_guard_enabled: Option<bool>,
+    /// `Pubkey::default()` clears the allowed caller
+    pub allowed_cpi_caller: Option<Pubkey>,
+    pub resolver_reward: Option<u64>,
+    pub trade_cooldown_seconds: Option<i64>,
+    pub min_trade_amount: Option<u64>,
+    pub creator_liquidity_lock_seconds: Option<i64>,
+    pub creator_fee_discount_bps: Option<u64>,
+    pub max_privacy_position: Option<u64>,
+    pub reference_price_tolerance_bps: Option<u64>,
+    pub oracle_bond: Option<u64>,
+    pub dispute_window_seconds: Option<i64>,
+    pub max_collateral_decimals: Option<u8>,
+    pub fee_rounding: Option<FeeRounding>,
+    pub post_resolution_trade_window: Option<i64>,
+    pub shielded_reveal_window: Option<i64>,
+}
+
+/// Event emitted when the protocol configuration changes
+#[event]
+pub struct ConfigUpdated {
+    pub admin: Pubkey,
+    pub paused: bool,
+    pub paused_at: i64,
+    pub balancing_fee_bps: u64,
+    pub skewing_fee_bps: u64,
+}
+
+/// Event emitted when the protocol goes from running to paused
+#[event]
+pub struct ProtocolPaused {
+    pub at: i64,
+}
+
+/// Event emitted when the protocol resumes, with how long it was down
+#[event]
+pub struct ProtocolUnpaused {
+    pub at: i64,
+    pub duration: i64,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    #[account(constraint = admin.key() == config.admin @ ConfigError::Unauthorized)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Config::SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Time override (test-clock builds only, see `instructions::clock`)
+    #[cfg(feature = "test-clock")]
+    #[account(seeds = [TestClock::SEED], bump = test_clock.bump)]
+    pub test_clock: Option<Box<Account<'info, TestClock>>>,
+}
+
+impl<'info> UpdateConfig<'info> {
+    pub fn update_config(&mut self, update: ConfigUpdate) -> Result<()> {
+        let config = &mut self.config;
+
+        if let Some(paused) = update.paused {
+            let now = now!(self)?;
+            let (was_paused, paused_at) = (config.paused, config.paused_at);
+            config.set_paused(paused, now);
+            if paused && !was_paused {
+                emit!(ProtocolPaused { at: now });
+            } else if !paused && was_paused {
+                emit!(ProtocolUnpaused { at: now, duration: now.saturating_sub(paused_at) });
+            }
+        }
+        if let Some(bps) = update.balancing_fee_bps {
+            config.balancing_fee_bps = bps;
+        }
+        if let Some(bps) = update.skewing_fee_bps {
+            config.skewing_fee_bps = bps;
+        }
+        if let Some(delay) = update.emergency_withdraw_delay {
+            require!(delay >= 0, ConfigError::InvalidDelay);
+            config.emergency_withdraw_delay = delay;
+        }
+        if let Some(duration) = update.max_market_duration {
+            require!(duration > 0, ConfigError::InvalidDuration);
+            config.max_market_duration = duration;
+        }
+        if let Some(duration) = update.min_market_duration {
+            config.min_market_duration = duration;
+        }
+        if let Some(enforce) = update.enforce_slippage {
+            config.enforce_slippage = enforce;
+        }
+        if let Some(allowlist) = update.recipient_allowlist {
+            config.recipient_allowlist = (allowlist != Pubkey::default()).then_some(allowlist);
+        }
+        if let Some(enabled) = update.cpi_guard_enabled {
+            config.cpi_guard_enabled = enabled;
+        }
+        if let Some(caller) = update.allowed_cpi_caller {
+            config.allowed_cpi_caller = (caller != Pubkey::default()).then_some(caller);
+        }
+        if let Some(reward) = update.resolver_reward {
+            config.resolver_reward = reward;
+        }
+        if let Some(cooldown) = update.trade_cooldown_seconds {
+            require!(cooldown >= 0, ConfigError::InvalidDelay);
+            config.trade_cooldown_seconds = cooldown;
+        }
+        if let Some(min_trade) = update.min_trade_amount {
+            config.min_trade_amount = min_trade;
+        }
+        if let Some(lock) = update.creator_liquidity_lock_seconds {
+            require!(lock >= 0, ConfigError::InvalidDelay);
+            config.creator_liquidity_lock_seconds = lock;
+        }
+        if let Some(discount) = update.creator_fee_discount_bps {
+            require!(discount <= 10000, ConfigError::InvalidDiscount);
+            config.creator_fee_discount_bps = discount;
+        }
+        if let Some(cap) = update.max_privacy_position {
+            config.max_privacy_position = cap;
+        }
+        if let Some(tolerance) = update.reference_price_tolerance_bps {
+            require!(tolerance <= 10000, ConfigError::InvalidTolerance);
+            config.reference_price_tolerance_bps = tolerance;
+        }
+        if let Some(bond) = update.oracle_bond {
+            config.oracle_bond = bond;
+        }
+        if let Some(window) = update.dispute_window_seconds {
+            require!(window >= 0, ConfigError::InvalidDelay);
+            config.dispute_window_seconds = window;
+        }
+        if let Some(decimals) = update.max_collateral_decimals {
+            config.max_collateral_decimals = decimals;
+        }
+        if let Some(rounding) = update.fee_rounding {
+            config.fee_rounding = rounding;
+        }
+        if let Some(window) = update.post_resolution_trade_window {
+            require!(window >= 0, ConfigError::InvalidDelay);
+            config.post_resolution_trade_window = window;
+        }
+        if let Some(window) = update.shielded_reveal_window {
+            // With no window no winner could reveal, and every shielded position would pay nothing
+            require!(window > 0, ConfigError::InvalidRevealWindow);
+            config.shielded_reveal_window = window;
+        }
+
+        require!(config.min_market_duration <= config.max_market_duration, ConfigError::InvalidDuration);
+        require!(config.skewing_fee_bps <= Config::MAX_FEE_BPS, ConfigError::FeeTooHigh);
+        require!(config.balancing_fee_bps >= Config::MIN_FEE_BPS, ConfigError::FeeTooLow);
+        require!(
+            config.balancing_fee_bps <= config.skewing_fee_bps,
+            ConfigError::InvalidFeeSchedule
+        );
+
+        emit!(ConfigUpdated {
+            admin: self.admin.key(),
+            paused: config.paused,
+            paused_at: config.paused_at,
+            balancing_fee_bps: config.balancing_fee_bps,
+            skewing_fee_bps: config.skewing_fee_bps,
+        });
+
+        Ok(())
+    }
+}
+
+/// Event emitted when the resolving oracle is rotated
+#[event]
+pub struct OracleChanged {
+    pub admin: Pubkey,
+    pub old_oracle: Pubkey,
+    pub new_oracle: Pubkey,
+}
+
+#[derive(Accounts)]
+pub struct SetOracle<'info> {
+    #[account(constraint = admin.key() == config.admin @ ConfigError::Unauthorized)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [Config::SEED],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+}
+
+impl<'info> SetOracle<'info> {
+    /// Rotate `config.oracle`, leaving every other setting alone
+    pub fn set_oracle(&mut self, new_oracle: Pubkey) -> Result<()> {
+        require!(new_oracle != Pubkey::default(), ConfigError::InvalidOracle);
+        let old_oracle = self.config.oracle;
+        self.config.oracle = new_oracle;
+
+        emit!(OracleChanged {
+            admin: self.admin.key(),
+            old_oracle,
+            new_oracle,
+        });
+        Ok(())
+    }
+}
+
+#[error_code(offset = 6100)]
+pub enum ConfigError {
+    #[msg("Only the admin can update the configuration")]
+    Unauthorized,
+    #[msg("Fee cannot exceed 30%")]
+    FeeTooHigh,
+    #[msg("Balancing fee cannot exceed skewing fee")]
+    InvalidFeeSchedule,
+    #[msg("Delay cannot be negative")]
+    InvalidDelay,
+    #[msg("Market duration bounds must be positive and ordered")]
+    InvalidDuration,
+    #[msg("Creator fee discount cannot exceed 10000 bps")]
+    InvalidDiscount,
+    #[msg("Oracle cannot be the default public key")]
+    InvalidOracle,
+    #[msg("Reference price tolerance cannot exceed 10000 bps")]
+    InvalidTolerance,
+    #[msg("Fee must be at least 1 bps")]
+    FeeTooLow,
+    #[msg("Shielded reveal window must be positive")]
+    InvalidRevealWindow,
+}
@@ -1,7 +1,15 @@
+pub mod attestation;
+pub mod clock;
+pub mod compliance;
+pub mod cpi_guard;
 pub mod market;
 pub mod privacy;
 pub mod public;
 
+pub use attestation::*;
+pub use clock::*;
+pub use compliance::*;
+pub use cpi_guard::*;
 pub use market::*;
 pub use privacy::*;
 pub use public::*;
@@ -0,0 +1,85 @@
+//! Commitment Index Instructions
+//!
+//! Strictly opt-in: a trader who wants recovery help bundles
+//! `record_commitment` with a privacy trade. Everyone else skips it and
+//! leaves no link between their wallet and their commitments.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{CommitmentEntry, Market, UserCommitmentIndex};
+
+#[derive(Accounts)]
+pub struct InitCommitmentIndex<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + UserCommitmentIndex::INIT_SPACE,
+        seeds = [UserCommitmentIndex::SEED, owner.key().as_ref()],
+        bump
+    )]
+    pub commitment_index: Account<'info, UserCommitmentIndex>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitCommitmentIndex<'info> {
+    pub fn init_commitment_index(&mut self, bump: u8) -> Result<()> {
+        self.commitment_index.set_inner(UserCommitmentIndex {
+            owner: self.owner.key(),
+            entries: Vec::new(),
+            bump,
+        });
+        Ok(())
+    }
+}
+
+/// Shared accounts for recording and forgetting a commitment
+#[derive(Accounts)]
+pub struct UpdateCommitmentIndex<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [UserCommitmentIndex::SEED, owner.key().as_ref()],
+        bump = commitment_index.bump,
+    )]
+    pub commitment_index: Account<'info, UserCommitmentIndex>,
+
+    pub market: Box<Account<'info, Market>>,
+}
+
+impl<'info> UpdateCommitmentIndex<'info> {
+    fn entry(&self, commitment: [u8; 32]) -> CommitmentEntry {
+        CommitmentEntry { market: self.market.key(), commitment }
+    }
+
+    pub fn record_commitment(&mut self, commitment: [u8; 32]) -> Result<()> {
+        let entry = self.entry(commitment);
+        require!(
+            self.commitment_index.entries.len() < UserCommitmentIndex::MAX_ENTRIES,
+            CommitmentIndexError::IndexFull
+        );
+        require!(self.commitment_index.record(entry), CommitmentIndexError::AlreadyRecorded);
+        Ok(())
+    }
+
+    /// Drop a commitment once its position is redeemed or merged away
+    pub fn forget_commitment(&mut self, commitment: [u8; 32]) -> Result<()> {
+        let entry = self.entry(commitment);
+        require!(self.commitment_index.forget(&entry), CommitmentIndexError::NotRecorded);
+        Ok(())
+    }
+}
+
+#[error_code(offset = 8500)]
+pub enum CommitmentIndexError {
+    #[msg("Commitment index is full, forget a redeemed commitment first")]
+    IndexFull,
+    #[msg("Commitment already recorded")]
+    AlreadyRecorded,
+    #[msg("Commitment not recorded")]
+    NotRecorded,
+}
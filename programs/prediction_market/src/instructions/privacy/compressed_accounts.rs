@@ -5,6 +5,9 @@
 
 use anchor_lang::prelude::*;
 
+use crate::instructions::public::TradeError;
+use crate::state::Market;
+
 /// Hidden Position (using ZK-Compression)
 ///
 /// This data is hidden off-chain using ZK-Compression. 
@@ -44,8 +47,9 @@ pub struct CreateCompressedPosition<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
     
-    /// CHECK: The market account
-    pub market: AccountInfo<'info>,
+    /// The market this position belongs to
+    #[account(constraint = market.allow_privacy @ TradeError::PrivacyDisabled)]
+    pub market: Box<Account<'info, Market>>,
     
     /// CHECK: The compression system program
     pub compression_program: AccountInfo<'info>,
@@ -60,13 +64,15 @@ impl<'info> CreateCompressedPosition<'info> {
     /// Create a new hidden position
     pub fn create_compressed_position(
         &mut self,
-        _ownership_commitment: [u8; 32],
+        ownership_commitment: [u8; 32],
         _encrypted_direction: [u8; 32],
         _amount: u64,
         _compliance_commitment: [u8; 32],
         _view_key_hash: [u8; 32],
         _validity_proof: Vec<u8>,
     ) -> Result<()> {
+        require!(ownership_commitment != [0u8; 32], CompressionError::DegenerateCommitment);
+
         msg!("🏗️ Compressed position created");
         msg!("📊 Amount and wallet are private.");
         msg!("🤝 Audit key is stored.");
@@ -101,3 +107,9 @@ pub mod compression_helpers {
         keccak::hash(&data).0
     }
 }
+
+#[error_code(offset = 8100)]
+pub enum CompressionError {
+    #[msg("Ownership commitment is all zeros")]
+    DegenerateCommitment,
+}
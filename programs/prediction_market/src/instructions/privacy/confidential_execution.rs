@@ -5,6 +5,9 @@
 
 use anchor_lang::prelude::*;
 
+use crate::instructions::public::TradeError;
+use crate::state::Market;
+
 /// Confidential Position state (Choice is hidden)
 #[account]
 #[derive(InitSpace)]
@@ -39,8 +42,8 @@ pub struct TradeConfidential<'info> {
     #[account(mut)]
     pub trader: Signer<'info>,
     
-    /// CHECK: The market account
-    pub market: AccountInfo<'info>,
+    #[account(constraint = market.allow_privacy @ TradeError::PrivacyDisabled)]
+    pub market: Box<Account<'info, Market>>,
     
     #[account(
         init,
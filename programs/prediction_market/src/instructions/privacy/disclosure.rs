@@ -0,0 +1,64 @@
+//! Selective Disclosure
+//!
+//! A Dark Pool position can name an auditor at init by storing the hash of
+//! a view key. Whoever holds that key can later have the program attest to
+//! the position's holdings: the key is checked against the stored hash and
+//! the position details are emitted.
+//!
+//! The view key travels in instruction data and the event is public, so a
+//! disclosure is visible to anyone watching the chain. Hand out a key per
+//! position, and treat it as spent once it has been used.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{Market, PrivacyPosition};
+
+/// Event emitted when a privacy position is disclosed to its view key holder
+#[event]
+pub struct PositionDisclosed {
+    pub market_id: u64,
+    pub commitment: [u8; 32],
+    pub view_key_hash: [u8; 32],
+    pub yes_amount: u64,
+    pub no_amount: u64,
+    pub collateral_deposited: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(commitment: [u8; 32])]
+pub struct DisclosePosition<'info> {
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(
+        seeds = [PrivacyPosition::SEED, market.key().as_ref(), commitment.as_ref()],
+        bump = privacy_position.bump,
+    )]
+    pub privacy_position: Box<Account<'info, PrivacyPosition>>,
+}
+
+impl<'info> DisclosePosition<'info> {
+    pub fn disclose_position(&self, commitment: [u8; 32], view_key: [u8; 32]) -> Result<()> {
+        let pos = &self.privacy_position;
+        let view_key_hash = pos.view_key_hash.ok_or(DisclosureError::NoViewKey)?;
+        require!(pos.view_key_matches(&view_key), DisclosureError::InvalidViewKey);
+
+        emit!(PositionDisclosed {
+            market_id: self.market.id,
+            commitment,
+            view_key_hash,
+            yes_amount: pos.yes_amount,
+            no_amount: pos.no_amount,
+            collateral_deposited: pos.collateral_deposited,
+        });
+
+        Ok(())
+    }
+}
+
+#[error_code(offset = 8300)]
+pub enum DisclosureError {
+    #[msg("Position was opened without a view key")]
+    NoViewKey,
+    #[msg("View key does not match the position")]
+    InvalidViewKey,
+}
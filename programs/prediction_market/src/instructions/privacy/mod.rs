@@ -4,6 +4,11 @@ pub mod shielded_trading;
 pub mod confidential_execution;
 pub mod compressed_accounts;
 pub mod private_odds;
+pub mod privacy_merge;
+pub mod privacy_close;
+pub mod position_lookup;
+pub mod commitment_index;
+pub mod disclosure;
 
 pub use privacy_entry::*;
 pub use privacy_exit::*;
@@ -11,3 +16,8 @@ pub use shielded_trading::*;
 pub use confidential_execution::*;
 pub use compressed_accounts::*;
 pub use private_odds::*;
+pub use privacy_merge::*;
+pub use privacy_close::*;
+pub use position_lookup::*;
+pub use commitment_index::*;
+pub use disclosure::*;
@@ -0,0 +1,153 @@
+//! Commitment Lookup
+//!
+//! Wallets reconstructing a user's private holdings only have a list of
+//! commitments. This view checks every PDA type a commitment can seed
+//! and emits a summary for each one that exists. Claims are also keyed by
+//! nonce, so the caller names which claim slot to check.
+//! Encrypted directions are never included.
+//!
+//! `get_shielded_position_public` returns one shielded position's public
+//! fields directly, so a wallet can show "a hidden bet of N on market X"
+//! without the user revealing their secret.
+
+use anchor_lang::prelude::*;
+
+use crate::state::{Market, PrivacyClaim, PrivacyPosition, ShieldedPosition};
+
+/// Which privacy account a commitment resolved to
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PositionKind {
+    PrivacyPosition,
+    ShieldedPosition,
+    PrivacyClaim,
+}
+
+/// Public summary of one privacy account
+#[event]
+pub struct PositionFound {
+    pub market: Pubkey,
+    pub commitment: [u8; 32],
+    pub kind: PositionKind,
+    pub address: Pubkey,
+    /// YES tokens (privacy position only)
+    pub yes_amount: u64,
+    /// NO tokens (privacy position only)
+    pub no_amount: u64,
+    /// Shielded collateral or locked claim amount
+    pub amount: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(commitment: [u8; 32], claim_nonce: u64)]
+pub struct GetPositionByCommitment<'info> {
+    pub market: Box<Account<'info, Market>>,
+
+    /// CHECK: May not exist; deserialized only if owned by this program
+    #[account(seeds = [PrivacyPosition::SEED, market.key().as_ref(), commitment.as_ref()], bump)]
+    pub privacy_position: UncheckedAccount<'info>,
+
+    /// CHECK: May not exist; deserialized only if owned by this program
+    #[account(seeds = [ShieldedPosition::SEED, market.key().as_ref(), commitment.as_ref()], bump)]
+    pub shielded_position: UncheckedAccount<'info>,
+
+    /// CHECK: May not exist; deserialized only if owned by this program (claim at `claim_nonce`)
+    #[account(seeds = [PrivacyClaim::SEED, market.key().as_ref(), commitment.as_ref(), claim_nonce.to_le_bytes().as_ref()], bump)]
+    pub privacy_claim: UncheckedAccount<'info>,
+}
+
+impl<'info> GetPositionByCommitment<'info> {
+    /// Emit a `PositionFound` for each existing account, returning how many were found
+    pub fn get_position_by_commitment(&self, commitment: [u8; 32]) -> Result<u8> {
+        let market = self.market.key();
+        let mut found = 0u8;
+
+        if let Some(pos) = Self::load::<PrivacyPosition>(&self.privacy_position)? {
+            emit!(PositionFound {
+                market,
+                commitment,
+                kind: PositionKind::PrivacyPosition,
+                address: self.privacy_position.key(),
+                yes_amount: pos.yes_amount,
+                no_amount: pos.no_amount,
+                amount: 0,
+            });
+            found += 1;
+        }
+
+        if let Some(pos) = Self::load::<ShieldedPosition>(&self.shielded_position)? {
+            emit!(PositionFound {
+                market,
+                commitment,
+                kind: PositionKind::ShieldedPosition,
+                address: self.shielded_position.key(),
+                yes_amount: 0,
+                no_amount: 0,
+                amount: pos.shielded_amount,
+            });
+            found += 1;
+        }
+
+        if let Some(claim) = Self::load::<PrivacyClaim>(&self.privacy_claim)? {
+            emit!(PositionFound {
+                market,
+                commitment,
+                kind: PositionKind::PrivacyClaim,
+                address: self.privacy_claim.key(),
+                yes_amount: 0,
+                no_amount: 0,
+                amount: claim.amount,
+            });
+            found += 1;
+        }
+
+        require!(found > 0, LookupError::PositionNotFound);
+        Ok(found)
+    }
+
+    /// Deserialize the account if it exists and is owned by this program
+    fn load<T: AccountDeserialize>(account: &UncheckedAccount<'info>) -> Result<Option<T>> {
+        if account.owner != &crate::ID || account.data_is_empty() {
+            return Ok(None);
+        }
+        let data = account.try_borrow_data()?;
+        Ok(Some(T::try_deserialize(&mut &data[..])?))
+    }
+}
+
+/// What anyone can learn about a shielded position without its secret
+///
+/// The direction cipher is deliberately not part of this.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ShieldedPositionPublic {
+    pub market: Pubkey,
+    pub shielded_amount: u64,
+    pub collateral_deposited: u64,
+}
+
+impl From<&ShieldedPosition> for ShieldedPositionPublic {
+    fn from(pos: &ShieldedPosition) -> Self {
+        Self { market: pos.market, shielded_amount: pos.shielded_amount, collateral_deposited: pos.collateral_deposited }
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(commitment: [u8; 32])]
+pub struct GetShieldedPositionPublic<'info> {
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(seeds = [ShieldedPosition::SEED, market.key().as_ref(), commitment.as_ref()], bump = shielded_position.bump)]
+    pub shielded_position: Account<'info, ShieldedPosition>,
+}
+
+impl<'info> GetShieldedPositionPublic<'info> {
+    /// Return the position's market and amounts, never its direction
+    pub fn get_shielded_position_public(&self) -> Result<ShieldedPositionPublic> {
+        Ok(ShieldedPositionPublic::from(&*self.shielded_position))
+    }
+}
+
+#[error_code(offset = 8200)]
+pub enum LookupError {
+    #[msg("No privacy account exists for this commitment")]
+    PositionNotFound,
+}
@@ -0,0 +1,123 @@
+//! Privacy Position Cleanup
+//!
+//! Abandons a Dark Pool position in one transaction. The owner proves the
+//! secret, any YES/NO left in the ghost vaults (e.g. from a partial entry)
+//! is swept to their own token accounts, and both vaults and the position
+//! PDA are closed for rent.
+//!
+//! Sweeping to the owner's wallet links the position to them publicly,
+//! so this is meant for positions that are being given up, not exited.
+
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::instructions::privacy::PrivacyError;
+use crate::state::{Market, PrivacyPosition};
+
+/// Event emitted when a privacy position is force-closed
+#[event]
+pub struct PrivacyPositionClosed {
+    pub market_id: u64,
+    pub commitment: [u8; 32],
+    pub yes_swept: u64,
+    pub no_swept: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(commitment: [u8; 32])]
+pub struct ForceClosePrivacyPosition<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(
+        mut,
+        seeds = [PrivacyPosition::SEED, market.key().as_ref(), commitment.as_ref()],
+        bump = privacy_position.bump,
+        close = owner,
+    )]
+    pub privacy_position: Box<Account<'info, PrivacyPosition>>,
+
+    #[account(constraint = yes_mint.key() == market.yes_mint)]
+    pub yes_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(constraint = no_mint.key() == market.no_mint)]
+    pub no_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut, associated_token::mint = yes_mint, associated_token::authority = privacy_position)]
+    pub privacy_yes: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, associated_token::mint = no_mint, associated_token::authority = privacy_position)]
+    pub privacy_no: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(init_if_needed, payer = owner, associated_token::mint = yes_mint, associated_token::authority = owner)]
+    pub owner_yes: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(init_if_needed, payer = owner, associated_token::mint = no_mint, associated_token::authority = owner)]
+    pub owner_no: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ForceClosePrivacyPosition<'info> {
+    pub fn force_close_privacy_position(&mut self, commitment: [u8; 32], secret: [u8; 32]) -> Result<()> {
+        require!(
+            PrivacyPosition::compute_commitment(&secret, &self.owner.key()) == commitment,
+            PrivacyError::InvalidSecret
+        );
+
+        let market_key = self.market.key();
+        let pos_seeds = &[PrivacyPosition::SEED, market_key.as_ref(), commitment.as_ref(), &[self.privacy_position.bump]];
+        let pos_signer = &[&pos_seeds[..]];
+
+        let yes_swept = self.privacy_yes.amount;
+        let no_swept = self.privacy_no.amount;
+
+        for (amount, mint, from, to) in [
+            (yes_swept, &self.yes_mint, &self.privacy_yes, &self.owner_yes),
+            (no_swept, &self.no_mint, &self.privacy_no, &self.owner_no),
+        ] {
+            if amount > 0 {
+                transfer_checked(
+                    CpiContext::new_with_signer(
+                        self.token_program.to_account_info(),
+                        TransferChecked {
+                            from: from.to_account_info(),
+                            mint: mint.to_account_info(),
+                            to: to.to_account_info(),
+                            authority: self.privacy_position.to_account_info(),
+                        },
+                        pos_signer,
+                    ),
+                    amount,
+                    mint.decimals,
+                )?;
+            }
+
+            close_account(CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                CloseAccount {
+                    account: from.to_account_info(),
+                    destination: self.owner.to_account_info(),
+                    authority: self.privacy_position.to_account_info(),
+                },
+                pos_signer,
+            ))?;
+        }
+
+        emit!(PrivacyPositionClosed {
+            market_id: self.market.id,
+            commitment,
+            yes_swept,
+            no_swept,
+        });
+
+        Ok(())
+    }
+}
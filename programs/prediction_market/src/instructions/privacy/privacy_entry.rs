@@ -14,7 +14,11 @@ use anchor_spl::{
 };
 
 use crate::amm::PythagoreanCurve;
-use crate::state::{Config, Market, MarketStatus, PrivacyPosition};
+use crate::instructions::clock::now;
+use crate::state::{Config, Market, MarketStatus, PrivacyPosition, ProtocolStats};
+#[cfg(feature = "test-clock")]
+use crate::state::TestClock;
+use crate::instructions::privacy::PrivacyError;
 use crate::instructions::public::TradeError;
 
 // =============================================================================
@@ -27,6 +31,7 @@ pub struct InitPrivacyPosition<'info> {
     #[account(mut)]
     pub trader: Signer<'info>,
 
+    #[account(constraint = market.allow_privacy @ TradeError::PrivacyDisabled)]
     pub market: Box<Account<'info, Market>>,
 
     #[account(
@@ -63,12 +68,15 @@ pub struct InitPrivacyPosition<'info> {
 }
 
 impl<'info> InitPrivacyPosition<'info> {
-    pub fn init_privacy_position(&mut self, commitment: [u8; 32], bump: u8) -> Result<()> {
+    pub fn init_privacy_position(&mut self, commitment: [u8; 32], view_key_hash: Option<[u8; 32]>, bump: u8) -> Result<()> {
+        require!(commitment != [0u8; 32], PrivacyError::DegenerateCommitment);
         let pos = &mut self.privacy_position;
         pos.market = self.market.key();
         pos.commitment = commitment;
         pos.yes_amount = 0;
         pos.no_amount = 0;
+        pos.collateral_deposited = 0;
+        pos.view_key_hash = view_key_hash;
         pos.bump = bump;
         Ok(())
     }
@@ -85,6 +93,7 @@ pub struct PrivacyPositionEntered {
     pub commitment: [u8; 32],
     pub yes_amount: u64,
     pub no_amount: u64,
+    pub event_seq: u64,
 }
 
 #[derive(Accounts)]
@@ -94,6 +103,7 @@ pub struct TradePrivacy<'info> {
     pub trader: Signer<'info>,
 
     #[account(
+        mut,
         seeds = [Config::SEED],
         bump = config.bump,
     )]
@@ -148,7 +158,15 @@ pub struct TradePrivacy<'info> {
     )]
     pub privacy_no: Box<InterfaceAccount<'info, TokenAccount>>,
 
+    #[account(mut, seeds = [ProtocolStats::SEED], bump = protocol_stats.bump)]
+    pub protocol_stats: Box<Account<'info, ProtocolStats>>,
+
     pub token_program: Interface<'info, TokenInterface>,
+
+    /// Time override (test-clock builds only, see `instructions::clock`)
+    #[cfg(feature = "test-clock")]
+    #[account(seeds = [TestClock::SEED], bump = test_clock.bump)]
+    pub test_clock: Option<Box<Account<'info, TestClock>>>,
 }
 
 impl<'info> TradePrivacy<'info> {
@@ -158,6 +176,12 @@ impl<'info> TradePrivacy<'info> {
         amount: u64,
         buy_yes: bool,
     ) -> Result<()> {
+        let now = now!(self)?;
+        require!(!self.market.has_ended(now), TradeError::MarketEnded);
+        require!(
+            self.config.privacy_deposit_allowed(self.privacy_position.collateral_deposited, amount),
+            PrivacyError::PositionTooLarge
+        );
         let market = &mut self.market;
         
         let tokens_to_mint = {
@@ -166,7 +190,9 @@ impl<'info> TradePrivacy<'info> {
             } else {
                 (market.no_supply, market.yes_supply)
             };
-            PythagoreanCurve::get_tokens_to_mint(market.reserves, target_supply, other_supply, amount)?
+            let tokens = PythagoreanCurve::get_tokens_to_mint(market.reserves, target_supply, other_supply, amount)?;
+            PythagoreanCurve::check_supply_cap(target_supply, tokens)?;
+            tokens
         };
 
         transfer_checked(
@@ -205,6 +231,10 @@ impl<'info> TradePrivacy<'info> {
         )?;
 
         market.reserves += amount;
+        market.record_volume(amount);
+        self.protocol_stats.record_trade(amount, 0);
+        self.protocol_stats.record_deposit(amount);
+        self.privacy_position.collateral_deposited += amount;
         if buy_yes {
             market.yes_supply += tokens_to_mint;
             self.privacy_position.yes_amount += tokens_to_mint;
@@ -218,6 +248,7 @@ impl<'info> TradePrivacy<'info> {
             commitment,
             yes_amount: self.privacy_position.yes_amount,
             no_amount: self.privacy_position.no_amount,
+            event_seq: self.config.next_event_seq(),
         });
 
         Ok(())
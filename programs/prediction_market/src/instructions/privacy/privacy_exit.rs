@@ -5,23 +5,39 @@
 //!
 //! Step 1: InitPrivacyClaim - Pre-creates the payout PDA and its collateral vault.
 //! Step 2: Redeem - Either `redeem_privacy` (public) or `redeem_privacy_position` (dark pool).
+//!         `redeem_privacy_direct` does Steps 1 and 2 for a public position in one go.
 //! Step 3: ClaimPrivacy - Revealing the secret and releasing funds to an unlinked wallet.
+//!
+//! Payouts are locked in whole `PrivacyClaim::DENOMINATION` units. Winnings
+//! below one unit stay as unburned tokens, see `PrivacyClaim::lockable_amount`.
+//!
+//! `redeem_privacy` and `redeem_privacy_direct` take a `standardize` flag.
+//! Passing `false` locks the exact payout and burns the whole position, for
+//! users who only want the fresh-wallet unlinking. The exact amount is then
+//! public and can tie the claim back to the redeeming wallet.
 
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token_interface::{Mint, TokenAccount, TokenInterface, transfer_checked, burn, Burn, TransferChecked},
+    token_interface::{Mint, TokenAccount, TokenInterface, transfer_checked, burn, close_account, Burn, CloseAccount, TransferChecked},
 };
-use anchor_lang::solana_program::keccak;
+use anchor_lang::solana_program::{keccak, sysvar::instructions as sysvar_instructions};
 
-use crate::state::{Config, Market, MarketStatus, Outcome, PrivacyClaim, PrivacyPosition};
+use crate::instructions::clock::now;
+use crate::instructions::compliance::check_recipient;
+use crate::instructions::cpi_guard::check_cpi_caller;
+use crate::amm::AmmError;
+use crate::instructions::public::{check_exit_window_closed, TradeError};
+use crate::state::{Config, Market, MarketStatus, PrivacyClaim, PrivacyPosition, ProtocolStats, Side};
+#[cfg(feature = "test-clock")]
+use crate::state::TestClock;
 
 // =============================================================================
 // STEP 1: INITIALIZE PRIVACY CLAIM
 // =============================================================================
 
 #[derive(Accounts)]
-#[instruction(commitment: [u8; 32])]
+#[instruction(commitment: [u8; 32], nonce: u64)]
 pub struct InitPrivacyClaim<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
@@ -32,7 +48,7 @@ pub struct InitPrivacyClaim<'info> {
         init,
         payer = user,
         space = 8 + PrivacyClaim::INIT_SPACE,
-        seeds = [PrivacyClaim::SEED, market.key().as_ref(), commitment.as_ref()],
+        seeds = [PrivacyClaim::SEED, market.key().as_ref(), commitment.as_ref(), nonce.to_le_bytes().as_ref()],
         bump
     )]
     pub privacy_claim: Box<Account<'info, PrivacyClaim>>,
@@ -53,20 +69,24 @@ pub struct InitPrivacyClaim<'info> {
 }
 
 impl<'info> InitPrivacyClaim<'info> {
-    pub fn init_privacy_claim(&mut self, commitment: [u8; 32], bump: u8) -> Result<()> {
-        let claim = &mut self.privacy_claim;
-        claim.market = self.market.key();
-        claim.mint = self.collateral_mint.key();
-        claim.amount = 0;
-        claim.commitment = commitment;
-        claim.lock_until = 0;
-        claim.redeemed = false;
-        claim.nonce = 0;
-        claim.bump = bump;
-        Ok(())
+    pub fn init_privacy_claim(&mut self, commitment: [u8; 32], nonce: u64, bump: u8) -> Result<()> {
+        init_claim(&mut self.privacy_claim, self.market.key(), self.collateral_mint.key(), commitment, nonce, bump)
     }
 }
 
+/// Fill in a freshly created claim account
+fn init_claim(claim: &mut PrivacyClaim, market: Pubkey, mint: Pubkey, commitment: [u8; 32], nonce: u64, bump: u8) -> Result<()> {
+    require!(commitment != [0u8; 32], PrivacyError::DegenerateCommitment);
+    claim.market = market;
+    claim.mint = mint;
+    claim.amount = 0;
+    claim.commitment = commitment;
+    claim.lock_until = 0;
+    claim.nonce = nonce;
+    claim.bump = bump;
+    Ok(())
+}
+
 // =============================================================================
 // STEP 2A: REDEEM PRIVACY (PUBLIC -> PRIVATE)
 // =============================================================================
@@ -83,22 +103,24 @@ pub struct RedeemPrivacy<'info> {
     )]
     pub market: Box<Account<'info, Market>>,
 
-    #[account(seeds = [Config::SEED], bump = config.bump)]
+    #[account(mut, seeds = [Config::SEED], bump = config.bump)]
     pub config: Box<Account<'info, Config>>,
 
     #[account(
         mut,
-        seeds = [PrivacyClaim::SEED, market.key().as_ref(), commitment.as_ref()],
+        seeds = [PrivacyClaim::SEED, market.key().as_ref(), commitment.as_ref(), privacy_claim.nonce.to_le_bytes().as_ref()],
         bump = privacy_claim.bump,
+        constraint = privacy_claim.amount == 0 @ PrivacyError::ClaimAlreadyFunded,
     )]
     pub privacy_claim: Box<Account<'info, PrivacyClaim>>,
 
-    #[account(mut)]
+    #[account(mut, constraint = yes_mint.key() == market.yes_mint)]
     pub yes_mint: Box<InterfaceAccount<'info, Mint>>,
 
-    #[account(mut)]
+    #[account(mut, constraint = no_mint.key() == market.no_mint)]
     pub no_mint: Box<InterfaceAccount<'info, Mint>>,
 
+    #[account(constraint = collateral_mint.key() == market.collateral_mint)]
     pub collateral_mint: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(mut, associated_token::mint = yes_mint, associated_token::authority = user)]
@@ -113,49 +135,216 @@ pub struct RedeemPrivacy<'info> {
     #[account(mut, associated_token::mint = collateral_mint, associated_token::authority = privacy_claim)]
     pub privacy_vault: Box<InterfaceAccount<'info, TokenAccount>>,
 
+    #[account(mut, seeds = [ProtocolStats::SEED], bump = protocol_stats.bump)]
+    pub protocol_stats: Box<Account<'info, ProtocolStats>>,
+
+    /// CHECK: Instructions sysvar, used by the CPI guard
+    #[account(address = sysvar_instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
     pub token_program: Interface<'info, TokenInterface>,
+
+    /// Time override (test-clock builds only, see `instructions::clock`)
+    #[cfg(feature = "test-clock")]
+    #[account(seeds = [TestClock::SEED], bump = test_clock.bump)]
+    pub test_clock: Option<Box<Account<'info, TestClock>>>,
 }
 
 impl<'info> RedeemPrivacy<'info> {
-    pub fn redeem_privacy(&mut self, commitment: [u8; 32]) -> Result<()> {
-        let market = &mut self.market;
-        let (user_balance, total_supply, winning_mint, user_account) = match market.outcome {
-            Outcome::Yes => (self.user_yes.amount, market.yes_supply, self.yes_mint.to_account_info(), self.user_yes.to_account_info()),
-            Outcome::No => (self.user_no.amount, market.no_supply, self.no_mint.to_account_info(), self.user_no.to_account_info()),
-            Outcome::Undetermined => return err!(PrivacyError::NotResolved),
-        };
+    pub fn redeem_privacy(&mut self, commitment: [u8; 32], standardize: bool) -> Result<()> {
+        check_cpi_caller(&self.config, &self.instructions_sysvar)?;
+        let now = now!(self)?;
+        PublicRedeem {
+            user: &self.user,
+            config: &mut self.config,
+            market: &mut self.market,
+            privacy_claim: &mut self.privacy_claim,
+            yes_mint: &self.yes_mint,
+            no_mint: &self.no_mint,
+            collateral_mint: &self.collateral_mint,
+            user_yes: &self.user_yes,
+            user_no: &self.user_no,
+            vault: &self.vault,
+            privacy_vault: &self.privacy_vault,
+            protocol_stats: &mut self.protocol_stats,
+            token_program: &self.token_program,
+        }
+        .lock_winnings(commitment, standardize, now)
+    }
+}
+
+/// Accounts a public-to-private redemption works on, shared by the two-step and direct flows
+struct PublicRedeem<'a, 'info> {
+    user: &'a Signer<'info>,
+    config: &'a mut Account<'info, Config>,
+    market: &'a mut Account<'info, Market>,
+    privacy_claim: &'a mut Account<'info, PrivacyClaim>,
+    yes_mint: &'a InterfaceAccount<'info, Mint>,
+    no_mint: &'a InterfaceAccount<'info, Mint>,
+    collateral_mint: &'a InterfaceAccount<'info, Mint>,
+    user_yes: &'a InterfaceAccount<'info, TokenAccount>,
+    user_no: &'a InterfaceAccount<'info, TokenAccount>,
+    vault: &'a InterfaceAccount<'info, TokenAccount>,
+    privacy_vault: &'a InterfaceAccount<'info, TokenAccount>,
+    protocol_stats: &'a mut Account<'info, ProtocolStats>,
+    token_program: &'a Interface<'info, TokenInterface>,
+}
+
+impl<'a, 'info> PublicRedeem<'a, 'info> {
+    /// Burn the user's winning tokens and move the payout into the claim vault
+    fn lock_winnings(self, commitment: [u8; 32], standardize: bool, now: i64) -> Result<()> {
+        let market = self.market;
+        check_exit_window_closed(market, self.config, now)?;
+        let side = market.winning_side().ok_or(PrivacyError::NotResolved)?;
+        let (user_balance, winning_mint, user_account) = side.pick(
+            (self.user_yes.amount, self.yes_mint.to_account_info(), self.user_yes.to_account_info()),
+            (self.user_no.amount, self.no_mint.to_account_info(), self.user_no.to_account_info()),
+        );
+        let total_supply = market.supply(side);
 
         require!(user_balance > 0, PrivacyError::NoWinningTokens);
 
-        let raw_collateral = (user_balance as u128).checked_mul(market.reserves as u128).unwrap().checked_div(total_supply as u128).unwrap() as u64;
-        let denomination = 1_000_000; 
-        let collateral_to_lock = (raw_collateral / denomination) * denomination;
+        let raw_collateral = market.winning_payout(user_balance, total_supply).ok_or(AmmError::Overflow)?;
+        let collateral_to_lock = PrivacyClaim::claim_amount(raw_collateral, standardize);
         require!(collateral_to_lock > 0, PrivacyError::AmountTooSmall);
 
-        let tokens_to_burn = (collateral_to_lock as u128).checked_mul(total_supply as u128).unwrap().checked_div(market.reserves as u128).unwrap() as u64;
+        // An exact claim pays out the whole position, like a public redeem
+        let tokens_to_burn = if standardize {
+            market.tokens_for_payout(collateral_to_lock, total_supply).ok_or(AmmError::Overflow)?
+        } else {
+            user_balance
+        };
+        check_burn(tokens_to_burn, user_balance, total_supply)?;
 
         burn(CpiContext::new(self.token_program.to_account_info(), Burn { mint: winning_mint, from: user_account, authority: self.user.to_account_info() }), tokens_to_burn)?;
 
-        let clock = Clock::get()?;
         self.privacy_claim.amount = collateral_to_lock;
         self.privacy_claim.commitment = commitment;
-        self.privacy_claim.lock_until = clock.unix_timestamp + 5;
+        self.privacy_claim.lock_until = now + 5;
 
         let config_key = self.config.key();
         let market_id_bytes = market.id.to_le_bytes();
         let market_seeds = &[crate::state::market::Market::SEED, config_key.as_ref(), &market_id_bytes, &[market.bump]];
         let market_signer = &[&market_seeds[..]];
 
+        // The vault's authority is checked by Anchor, but these seeds are rebuilt
+        // by hand: refuse to sign if they derive anything but this market
+        let derived = Pubkey::create_program_address(market_seeds, &crate::ID).map_err(|_| PrivacyError::MarketSignerMismatch)?;
+        require_keys_eq!(derived, market.key(), PrivacyError::MarketSignerMismatch);
+
         transfer_checked(CpiContext::new_with_signer(self.token_program.to_account_info(), TransferChecked { from: self.vault.to_account_info(), mint: self.collateral_mint.to_account_info(), to: self.privacy_vault.to_account_info(), authority: market.to_account_info() }, market_signer), collateral_to_lock, self.collateral_mint.decimals)?;
 
         market.reserves -= collateral_to_lock;
-        if market.outcome == Outcome::Yes { market.yes_supply -= tokens_to_burn; } else { market.no_supply -= tokens_to_burn; }
+        market.total_locked_in_claims = market.total_locked_in_claims.checked_add(collateral_to_lock).ok_or(AmmError::Overflow)?;
+        self.protocol_stats.record_withdrawal(collateral_to_lock);
+        *market.supply_mut(side) -= tokens_to_burn;
+        // A no-op for ProRata markets, which carry no fixed obligation
+        require!(market.is_collateralized(), TradeError::UndercollateralizedOperation);
 
-        emit!(PrivacyClaimCreated { market_id: market.id, commitment, amount: collateral_to_lock });
+        emit!(PrivacyClaimCreated { market_id: market.id, privacy_claim: self.privacy_claim.key(), step: ClaimStep::Created, commitment, amount: collateral_to_lock, event_seq: self.config.next_event_seq() });
         Ok(())
     }
 }
 
+// =============================================================================
+// STEP 1 + 2A: REDEEM PRIVACY DIRECT (ONE TRANSACTION)
+// =============================================================================
+
+/// `init_privacy_claim` and `redeem_privacy` in one instruction
+///
+/// Saves a transaction and the timing link between the two. The two-step
+/// flow stays for callers that cannot fit these accounts on the stack.
+#[derive(Accounts)]
+#[instruction(commitment: [u8; 32], nonce: u64)]
+pub struct RedeemPrivacyDirect<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = market.status == MarketStatus::Resolved @ PrivacyError::NotResolved,
+    )]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(mut, seeds = [Config::SEED], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + PrivacyClaim::INIT_SPACE,
+        seeds = [PrivacyClaim::SEED, market.key().as_ref(), commitment.as_ref(), nonce.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub privacy_claim: Box<Account<'info, PrivacyClaim>>,
+
+    #[account(mut, constraint = yes_mint.key() == market.yes_mint)]
+    pub yes_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut, constraint = no_mint.key() == market.no_mint)]
+    pub no_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(constraint = collateral_mint.key() == market.collateral_mint)]
+    pub collateral_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut, associated_token::mint = yes_mint, associated_token::authority = user)]
+    pub user_yes: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, associated_token::mint = no_mint, associated_token::authority = user)]
+    pub user_no: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, associated_token::mint = collateral_mint, associated_token::authority = market)]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = user,
+        associated_token::mint = collateral_mint,
+        associated_token::authority = privacy_claim,
+    )]
+    pub privacy_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, seeds = [ProtocolStats::SEED], bump = protocol_stats.bump)]
+    pub protocol_stats: Box<Account<'info, ProtocolStats>>,
+
+    /// CHECK: Instructions sysvar, used by the CPI guard
+    #[account(address = sysvar_instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+
+    /// Time override (test-clock builds only, see `instructions::clock`)
+    #[cfg(feature = "test-clock")]
+    #[account(seeds = [TestClock::SEED], bump = test_clock.bump)]
+    pub test_clock: Option<Box<Account<'info, TestClock>>>,
+}
+
+impl<'info> RedeemPrivacyDirect<'info> {
+    pub fn redeem_privacy_direct(&mut self, commitment: [u8; 32], nonce: u64, standardize: bool, bump: u8) -> Result<()> {
+        check_cpi_caller(&self.config, &self.instructions_sysvar)?;
+        init_claim(&mut self.privacy_claim, self.market.key(), self.collateral_mint.key(), commitment, nonce, bump)?;
+        let now = now!(self)?;
+        PublicRedeem {
+            user: &self.user,
+            config: &mut self.config,
+            market: &mut self.market,
+            privacy_claim: &mut self.privacy_claim,
+            yes_mint: &self.yes_mint,
+            no_mint: &self.no_mint,
+            collateral_mint: &self.collateral_mint,
+            user_yes: &self.user_yes,
+            user_no: &self.user_no,
+            vault: &self.vault,
+            privacy_vault: &self.privacy_vault,
+            protocol_stats: &mut self.protocol_stats,
+            token_program: &self.token_program,
+        }
+        .lock_winnings(commitment, standardize, now)
+    }
+}
+
 // =============================================================================
 // STEP 2B: REDEEM PRIVACY POSITION (DARK POOL -> PRIVATE PAYOUT)
 // =============================================================================
@@ -166,24 +355,34 @@ pub struct RedeemPrivacyPosition<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
 
-    #[account(mut, constraint = market.status == MarketStatus::Resolved @ PrivacyError::NotResolved)]
+    #[account(
+        mut,
+        constraint = market.status == MarketStatus::Resolved || market.status == MarketStatus::Cancelled @ PrivacyError::NotResolved,
+    )]
     pub market: Box<Account<'info, Market>>,
 
-    #[account(seeds = [Config::SEED], bump = config.bump)]
+    #[account(mut, seeds = [Config::SEED], bump = config.bump)]
     pub config: Box<Account<'info, Config>>,
 
     #[account(mut, seeds = [PrivacyPosition::SEED, market.key().as_ref(), position_commitment.as_ref()], bump = privacy_position.bump)]
     pub privacy_position: Box<Account<'info, PrivacyPosition>>,
 
-    #[account(mut, seeds = [PrivacyClaim::SEED, market.key().as_ref(), payout_commitment.as_ref()], bump = privacy_claim.bump)]
+    /// Each claim is funded once, so `market.total_locked_in_claims` drops by exactly what was added
+    #[account(
+        mut,
+        seeds = [PrivacyClaim::SEED, market.key().as_ref(), payout_commitment.as_ref(), privacy_claim.nonce.to_le_bytes().as_ref()],
+        bump = privacy_claim.bump,
+        constraint = privacy_claim.amount == 0 @ PrivacyError::ClaimAlreadyFunded,
+    )]
     pub privacy_claim: Box<Account<'info, PrivacyClaim>>,
 
-    #[account(mut)]
+    #[account(mut, constraint = yes_mint.key() == market.yes_mint)]
     pub yes_mint: Box<InterfaceAccount<'info, Mint>>,
 
-    #[account(mut)]
+    #[account(mut, constraint = no_mint.key() == market.no_mint)]
     pub no_mint: Box<InterfaceAccount<'info, Mint>>,
 
+    #[account(constraint = collateral_mint.key() == market.collateral_mint)]
     pub collateral_mint: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(mut, associated_token::mint = yes_mint, associated_token::authority = privacy_position)]
@@ -198,29 +397,50 @@ pub struct RedeemPrivacyPosition<'info> {
     #[account(mut, associated_token::mint = collateral_mint, associated_token::authority = privacy_claim)]
     pub privacy_vault: Box<InterfaceAccount<'info, TokenAccount>>,
 
+    #[account(mut, seeds = [ProtocolStats::SEED], bump = protocol_stats.bump)]
+    pub protocol_stats: Box<Account<'info, ProtocolStats>>,
+
+    /// CHECK: Instructions sysvar, used by the CPI guard
+    #[account(address = sysvar_instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
     pub token_program: Interface<'info, TokenInterface>,
+
+    /// Time override (test-clock builds only, see `instructions::clock`)
+    #[cfg(feature = "test-clock")]
+    #[account(seeds = [TestClock::SEED], bump = test_clock.bump)]
+    pub test_clock: Option<Box<Account<'info, TestClock>>>,
 }
 
 impl<'info> RedeemPrivacyPosition<'info> {
-    pub fn redeem_privacy_position(&mut self, position_commitment: [u8; 32], payout_commitment: [u8; 32]) -> Result<()> {
+    pub fn redeem_privacy_position(&mut self, position_commitment: [u8; 32], payout_commitment: [u8; 32], min_locked: u64) -> Result<()> {
+        check_cpi_caller(&self.config, &self.instructions_sysvar)?;
+        let now = now!(self)?;
+        if self.market.status == MarketStatus::Cancelled {
+            return self.refund_cancelled_position(position_commitment, payout_commitment, min_locked, now);
+        }
+
+        check_exit_window_closed(&self.market, &self.config, now)?;
         let market = &mut self.market;
         let privacy_pos = &mut self.privacy_position;
         let privacy_claim = &mut self.privacy_claim;
 
-        let (pos_balance, total_supply, winning_mint, source_vault) = match market.outcome {
-            Outcome::Yes => (privacy_pos.yes_amount, market.yes_supply, self.yes_mint.to_account_info(), self.privacy_yes.to_account_info()),
-            Outcome::No => (privacy_pos.no_amount, market.no_supply, self.no_mint.to_account_info(), self.privacy_no.to_account_info()),
-            Outcome::Undetermined => return err!(PrivacyError::NotResolved),
-        };
+        let side = market.winning_side().ok_or(PrivacyError::NotResolved)?;
+        let (pos_balance, winning_mint, source_vault) = side.pick(
+            (privacy_pos.yes_amount, self.yes_mint.to_account_info(), self.privacy_yes.to_account_info()),
+            (privacy_pos.no_amount, self.no_mint.to_account_info(), self.privacy_no.to_account_info()),
+        );
+        let total_supply = market.supply(side);
 
         require!(pos_balance > 0, PrivacyError::NoWinningTokens);
 
-        let raw_collateral = (pos_balance as u128).checked_mul(market.reserves as u128).unwrap().checked_div(total_supply as u128).unwrap() as u64;
-        let denomination = 1_000_000; 
-        let collateral_to_lock = (raw_collateral / denomination) * denomination;
+        let raw_collateral = market.winning_payout(pos_balance, total_supply).ok_or(AmmError::Overflow)?;
+        let collateral_to_lock = PrivacyClaim::lockable_amount(raw_collateral);
         require!(collateral_to_lock > 0, PrivacyError::AmountTooSmall);
+        require!(collateral_to_lock >= min_locked, PrivacyError::AmountTooSmall);
 
-        let tokens_to_burn = (collateral_to_lock as u128).checked_mul(total_supply as u128).unwrap().checked_div(market.reserves as u128).unwrap() as u64;
+        let tokens_to_burn = market.tokens_for_payout(collateral_to_lock, total_supply).ok_or(AmmError::Overflow)?;
+        check_burn(tokens_to_burn, pos_balance, total_supply)?;
 
         let market_key = market.key();
         let pos_seeds = &[PrivacyPosition::SEED, market_key.as_ref(), position_commitment.as_ref(), &[privacy_pos.bump]];
@@ -228,10 +448,9 @@ impl<'info> RedeemPrivacyPosition<'info> {
 
         burn(CpiContext::new_with_signer(self.token_program.to_account_info(), Burn { mint: winning_mint, from: source_vault, authority: privacy_pos.to_account_info() }, pos_signer), tokens_to_burn)?;
 
-        let clock = Clock::get()?;
         privacy_claim.amount = collateral_to_lock;
         privacy_claim.commitment = payout_commitment;
-        privacy_claim.lock_until = clock.unix_timestamp + 5;
+        privacy_claim.lock_until = now + 5;
 
         let config_key = self.config.key();
         let market_id_bytes = market.id.to_le_bytes();
@@ -241,13 +460,111 @@ impl<'info> RedeemPrivacyPosition<'info> {
         transfer_checked(CpiContext::new_with_signer(self.token_program.to_account_info(), TransferChecked { from: self.vault.to_account_info(), mint: self.collateral_mint.to_account_info(), to: self.privacy_vault.to_account_info(), authority: market.to_account_info() }, market_signer), collateral_to_lock, self.collateral_mint.decimals)?;
 
         market.reserves -= collateral_to_lock;
-        if market.outcome == Outcome::Yes { market.yes_supply -= tokens_to_burn; privacy_pos.yes_amount -= tokens_to_burn; } else { market.no_supply -= tokens_to_burn; privacy_pos.no_amount -= tokens_to_burn; }
+        market.total_locked_in_claims = market.total_locked_in_claims.checked_add(collateral_to_lock).ok_or(AmmError::Overflow)?;
+        self.protocol_stats.record_withdrawal(collateral_to_lock);
+        *market.supply_mut(side) -= tokens_to_burn;
+        match side { Side::Yes => privacy_pos.yes_amount -= tokens_to_burn, Side::No => privacy_pos.no_amount -= tokens_to_burn }
+        // A no-op for ProRata markets, which carry no fixed obligation
+        require!(market.is_collateralized(), TradeError::UndercollateralizedOperation);
+
+        emit!(PrivacyClaimCreated { market_id: market.id, privacy_claim: self.privacy_claim.key(), step: ClaimStep::Created, commitment: payout_commitment, amount: collateral_to_lock, event_seq: self.config.next_event_seq() });
+        Ok(())
+    }
 
-        emit!(PrivacyClaimCreated { market_id: market.id, commitment: payout_commitment, amount: collateral_to_lock });
+    /// Cancelled market: both sides are refunded a proportional share of reserves
+    ///
+    /// The refund is still locked in fixed denominations, burning YES first and
+    /// then NO. Any remainder stays in the position for a later call.
+    fn refund_cancelled_position(&mut self, position_commitment: [u8; 32], payout_commitment: [u8; 32], min_locked: u64, now: i64) -> Result<()> {
+        let market = &mut self.market;
+        let privacy_pos = &mut self.privacy_position;
+        let privacy_claim = &mut self.privacy_claim;
+
+        let pos_balance = privacy_pos.yes_amount.checked_add(privacy_pos.no_amount).ok_or(AmmError::Overflow)?;
+        require!(pos_balance > 0, PrivacyError::NoWinningTokens);
+
+        let raw_collateral = market.outcome_neutral_payout(pos_balance).ok_or(AmmError::Overflow)?;
+        let collateral_to_lock = PrivacyClaim::lockable_amount(raw_collateral);
+        require!(collateral_to_lock > 0, PrivacyError::AmountTooSmall);
+        require!(collateral_to_lock >= min_locked, PrivacyError::AmountTooSmall);
+
+        let total_supply = market.yes_supply.checked_add(market.no_supply).ok_or(AmmError::Overflow)?;
+        let tokens_to_burn = ((collateral_to_lock as u128) * (total_supply as u128)).div_ceil(market.reserves as u128) as u64;
+        check_burn(tokens_to_burn, pos_balance, total_supply)?;
+        let yes_burned = tokens_to_burn.min(privacy_pos.yes_amount);
+        let no_burned = tokens_to_burn - yes_burned;
+
+        let market_key = market.key();
+        let pos_seeds = &[PrivacyPosition::SEED, market_key.as_ref(), position_commitment.as_ref(), &[privacy_pos.bump]];
+        let pos_signer = &[&pos_seeds[..]];
+
+        for (amount, mint, from) in [
+            (yes_burned, &self.yes_mint, &self.privacy_yes),
+            (no_burned, &self.no_mint, &self.privacy_no),
+        ] {
+            if amount > 0 {
+                burn(CpiContext::new_with_signer(self.token_program.to_account_info(), Burn { mint: mint.to_account_info(), from: from.to_account_info(), authority: privacy_pos.to_account_info() }, pos_signer), amount)?;
+            }
+        }
+
+        privacy_claim.amount = collateral_to_lock;
+        privacy_claim.commitment = payout_commitment;
+        privacy_claim.lock_until = now + 5;
+
+        let config_key = self.config.key();
+        let market_id_bytes = market.id.to_le_bytes();
+        let market_seeds = &[Market::SEED, config_key.as_ref(), &market_id_bytes, &[market.bump]];
+        let market_signer = &[&market_seeds[..]];
+
+        transfer_checked(CpiContext::new_with_signer(self.token_program.to_account_info(), TransferChecked { from: self.vault.to_account_info(), mint: self.collateral_mint.to_account_info(), to: self.privacy_vault.to_account_info(), authority: market.to_account_info() }, market_signer), collateral_to_lock, self.collateral_mint.decimals)?;
+
+        market.reserves -= collateral_to_lock;
+        market.total_locked_in_claims = market.total_locked_in_claims.checked_add(collateral_to_lock).ok_or(AmmError::Overflow)?;
+        market.yes_supply -= yes_burned;
+        market.no_supply -= no_burned;
+        privacy_pos.yes_amount -= yes_burned;
+        privacy_pos.no_amount -= no_burned;
+        self.protocol_stats.record_withdrawal(collateral_to_lock);
+        // Checked for FixedUnit; ProRata's obligation is always 0
+        require!(market.is_collateralized(), TradeError::UndercollateralizedOperation);
+
+        emit!(PrivacyClaimCreated { market_id: market.id, privacy_claim: self.privacy_claim.key(), step: ClaimStep::Created, commitment: payout_commitment, amount: collateral_to_lock, event_seq: self.config.next_event_seq() });
         Ok(())
     }
 }
 
+// =============================================================================
+// STEP 2C: PREPARE RECIPIENT (BEFORE A RELAYED CLAIM)
+// =============================================================================
+
+/// Creates the recipient's collateral ATA ahead of the claim.
+///
+/// Run this from any funded wallet unrelated to the trader. The relayed claim
+/// then only writes to an existing account, so the relayer never pays rent
+/// for the recipient and no funding link appears in the claim transaction.
+#[derive(Accounts)]
+pub struct PrepareRecipient<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub collateral_mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: Only used as the ATA authority
+    pub recipient: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = collateral_mint,
+        associated_token::authority = recipient,
+    )]
+    pub recipient_collateral: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
 // =============================================================================
 // STEP 3: CLAIM PRIVACY (FINAL PAYOUT)
 // =============================================================================
@@ -258,38 +575,63 @@ pub struct ClaimPrivacy<'info> {
     #[account(mut)]
     pub claimant: Signer<'info>,
 
+    #[account(mut, seeds = [Config::SEED], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+
     #[account(
         mut,
-        seeds = [PrivacyClaim::SEED, privacy_claim.market.as_ref(), commitment.as_ref()],
+        seeds = [PrivacyClaim::SEED, privacy_claim.market.as_ref(), commitment.as_ref(), privacy_claim.nonce.to_le_bytes().as_ref()],
         bump = privacy_claim.bump,
         constraint = privacy_claim.commitment == commitment @ PrivacyError::InvalidReveal,
-        constraint = !privacy_claim.redeemed @ PrivacyError::AlreadyRedeemed,
+        close = claimant,
     )]
     pub privacy_claim: Account<'info, PrivacyClaim>,
 
+    #[account(mut, address = privacy_claim.market)]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(constraint = collateral_mint.key() == privacy_claim.mint)]
     pub collateral_mint: InterfaceAccount<'info, Mint>,
 
     #[account(mut, associated_token::mint = collateral_mint, associated_token::authority = privacy_claim)]
     pub privacy_vault: InterfaceAccount<'info, TokenAccount>,
 
-    #[account(init_if_needed, payer = claimant, associated_token::mint = collateral_mint, associated_token::authority = recipient_account)]
+    /// Must already exist, see `PrepareRecipient`
+    #[account(
+        mut,
+        associated_token::mint = collateral_mint,
+        associated_token::authority = recipient_account,
+        constraint = !recipient_collateral.is_frozen() @ PrivacyError::RecipientAccountFrozen,
+    )]
     pub recipient_collateral: InterfaceAccount<'info, TokenAccount>,
 
     /// CHECK: Validated cryptographically via keccak-256
     pub recipient_account: UncheckedAccount<'info>,
 
+    /// CHECK: Must match `config.recipient_allowlist` when one is set
+    pub allowlist_program: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Instructions sysvar, used by the CPI guard
+    #[account(address = sysvar_instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    /// Time override (test-clock builds only, see `instructions::clock`)
+    #[cfg(feature = "test-clock")]
+    #[account(seeds = [TestClock::SEED], bump = test_clock.bump)]
+    pub test_clock: Option<Box<Account<'info, TestClock>>>,
+
     pub token_program: Interface<'info, TokenInterface>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
-    pub system_program: Program<'info, System>,
 }
 
 impl<'info> ClaimPrivacy<'info> {
     pub fn claim(&mut self, secret: [u8; 32]) -> Result<()> {
-        let privacy_claim = &mut self.privacy_claim;
+        check_cpi_caller(&self.config, &self.instructions_sysvar)?;
+        let claim_key = self.privacy_claim.key();
+        let privacy_claim = &self.privacy_claim;
         let recipient = self.recipient_account.key();
-        let clock = Clock::get()?;
+        let now = now!(self)?;
 
-        require!(clock.unix_timestamp >= privacy_claim.lock_until, PrivacyError::StillLocked);
+        require!(now >= privacy_claim.lock_until, PrivacyError::StillLocked);
 
         let mut data = Vec::with_capacity(72);
         data.extend_from_slice(&secret);
@@ -298,37 +640,73 @@ impl<'info> ClaimPrivacy<'info> {
         
         let reveal_hash = keccak::hash(&data).0;
         require!(reveal_hash == privacy_claim.commitment, PrivacyError::InvalidReveal);
+        check_recipient(&self.config, self.allowlist_program.as_ref(), &self.recipient_account.to_account_info())?;
 
-        let privacy_seeds = &[PrivacyClaim::SEED, privacy_claim.market.as_ref(), privacy_claim.commitment.as_ref(), &[privacy_claim.bump]];
+        let nonce_bytes = privacy_claim.nonce.to_le_bytes();
+        let privacy_seeds = &[PrivacyClaim::SEED, privacy_claim.market.as_ref(), privacy_claim.commitment.as_ref(), &nonce_bytes, &[privacy_claim.bump]];
         let privacy_signer = &[&privacy_seeds[..]];
 
-        transfer_checked(CpiContext::new_with_signer(self.token_program.to_account_info(), TransferChecked { from: self.privacy_vault.to_account_info(), mint: self.collateral_mint.to_account_info(), to: self.recipient_collateral.to_account_info(), authority: privacy_claim.to_account_info() }, privacy_signer), privacy_claim.amount, self.collateral_mint.decimals)?;
+        // Sweep the whole vault so stray deposits can't block the close below
+        let amount = self.privacy_vault.amount;
+        require!(amount >= privacy_claim.amount, PrivacyError::ClaimUnderfunded);
+        transfer_checked(CpiContext::new_with_signer(self.token_program.to_account_info(), TransferChecked { from: self.privacy_vault.to_account_info(), mint: self.collateral_mint.to_account_info(), to: self.recipient_collateral.to_account_info(), authority: privacy_claim.to_account_info() }, privacy_signer), amount, self.collateral_mint.decimals)?;
+
+        // The claim is single-use: return the vault rent now, the claim rent via `close = claimant`
+        close_account(CpiContext::new_with_signer(self.token_program.to_account_info(), CloseAccount { account: self.privacy_vault.to_account_info(), destination: self.claimant.to_account_info(), authority: privacy_claim.to_account_info() }, privacy_signer))?;
 
-        privacy_claim.redeemed = true;
-        emit!(PrivacyClaimRevealed { commitment: privacy_claim.commitment, recipient, amount: privacy_claim.amount });
+        // Stray deposits swept above were never counted, so release only the locked amount
+        self.market.total_locked_in_claims = self.market.total_locked_in_claims.saturating_sub(privacy_claim.amount);
+        emit!(PrivacyClaimRevealed { privacy_claim: claim_key, step: ClaimStep::Revealed, commitment: privacy_claim.commitment, recipient, amount, event_seq: self.config.next_event_seq() });
         Ok(())
     }
 }
 
+/// Guard the burn recomputed from a rounded payout
+///
+/// `tokens_for_payout` rounds up, so a bad rounding path would otherwise
+/// surface as an opaque token-program failure or a supply underflow.
+pub fn check_burn(tokens_to_burn: u64, balance: u64, total_supply: u64) -> Result<()> {
+    require!(tokens_to_burn <= balance, PrivacyError::BurnExceedsBalance);
+    require!(tokens_to_burn <= total_supply, PrivacyError::BurnExceedsSupply);
+    Ok(())
+}
+
 // =============================================================================
 // EVENTS & ERRORS
 // =============================================================================
 
+/// Which half of the two-step private withdrawal an event records
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ClaimStep {
+    /// Collateral locked in the claim vault (redeem)
+    Created,
+    /// Collateral released to the recipient (claim)
+    Revealed,
+}
+
 #[event]
 pub struct PrivacyClaimCreated {
     pub market_id: u64,
+    /// Claim PDA, shared with the matching `PrivacyClaimRevealed`
+    pub privacy_claim: Pubkey,
+    pub step: ClaimStep,
     pub commitment: [u8; 32],
     pub amount: u64,
+    pub event_seq: u64,
 }
 
 #[event]
 pub struct PrivacyClaimRevealed {
+    /// Claim PDA, shared with the matching `PrivacyClaimCreated`
+    pub privacy_claim: Pubkey,
+    pub step: ClaimStep,
     pub commitment: [u8; 32],
     pub recipient: Pubkey,
     pub amount: u64,
+    pub event_seq: u64,
 }
 
-#[error_code]
+#[error_code(offset = 7900)]
 pub enum PrivacyError {
     #[msg("Market is not resolved")]
     NotResolved,
@@ -336,10 +714,58 @@ pub enum PrivacyError {
     NoWinningTokens,
     #[msg("Invalid secret or recipient reveal")]
     InvalidReveal,
-    #[msg("Claim already redeemed")]
-    AlreadyRedeemed,
     #[msg("Winning amount too small for fixed denomination")]
     AmountTooSmall,
     #[msg("Privacy lock period not yet expired")]
     StillLocked,
+    #[msg("Recipient token account is frozen")]
+    RecipientAccountFrozen,
+    #[msg("Commitment is all zeros")]
+    DegenerateCommitment,
+    #[msg("Tokens to burn exceed the holder's balance")]
+    BurnExceedsBalance,
+    #[msg("Tokens to burn exceed the market supply")]
+    BurnExceedsSupply,
+    #[msg("Deposit would exceed the maximum privacy position size")]
+    PositionTooLarge,
+    #[msg("Market signer seeds do not derive the market account")]
+    MarketSignerMismatch,
+    #[msg("Claim already holds a payout, use a fresh claim")]
+    ClaimAlreadyFunded,
+    #[msg("Claim vault holds less than the locked payout")]
+    ClaimUnderfunded,
+    #[msg("Secret does not match position commitment")]
+    InvalidSecret,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::market::tests::market as base_market;
+    use crate::state::SettlementMode;
+
+    fn market(reserves: u64, yes_supply: u64) -> Market {
+        Market { yes_supply, ..base_market(reserves, SettlementMode::ProRata) }
+    }
+
+    #[test]
+    fn test_check_burn_boundaries() {
+        assert!(check_burn(10, 10, 10).is_ok());
+        assert!(check_burn(11, 10, 20).is_err());
+        assert!(check_burn(11, 20, 10).is_err());
+    }
+
+    #[test]
+    fn test_rounded_burn_never_exceeds_balance() {
+        // Awkward ratios where the payout floors and the burn rounds back up
+        for (reserves, supply, balance) in [(3_000_001, 2_999_999, 2_999_999), (7_000_003, 3, 3), (1_000_000_007, 999_999_937, 123_456_789)] {
+            let m = market(reserves, supply);
+            let payout = PrivacyClaim::lockable_amount(m.winning_payout(balance, supply).unwrap());
+            if payout == 0 {
+                continue;
+            }
+            let burn = m.tokens_for_payout(payout, supply).unwrap();
+            assert!(check_burn(burn, balance, supply).is_ok());
+        }
+    }
 }
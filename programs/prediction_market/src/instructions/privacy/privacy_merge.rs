@@ -0,0 +1,152 @@
+//! Privacy Position Merge
+//!
+//! Consolidates two Dark Pool positions in the same market.
+//! The owner proves both secrets, the source ghost vaults are swept
+//! into the destination, and the source accounts are closed for rent.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+use crate::state::{Market, PrivacyPosition};
+
+/// Event emitted when two privacy positions are merged
+#[event]
+pub struct PrivacyPositionsMerged {
+    pub market_id: u64,
+    pub source_commitment: [u8; 32],
+    pub dest_commitment: [u8; 32],
+    pub yes_amount: u64,
+    pub no_amount: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(source_commitment: [u8; 32], dest_commitment: [u8; 32])]
+pub struct MergePrivacyPositions<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(
+        mut,
+        seeds = [PrivacyPosition::SEED, market.key().as_ref(), source_commitment.as_ref()],
+        bump = source_position.bump,
+        close = owner,
+    )]
+    pub source_position: Box<Account<'info, PrivacyPosition>>,
+
+    #[account(
+        mut,
+        seeds = [PrivacyPosition::SEED, market.key().as_ref(), dest_commitment.as_ref()],
+        bump = dest_position.bump,
+    )]
+    pub dest_position: Box<Account<'info, PrivacyPosition>>,
+
+    #[account(constraint = yes_mint.key() == market.yes_mint)]
+    pub yes_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(constraint = no_mint.key() == market.no_mint)]
+    pub no_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut, associated_token::mint = yes_mint, associated_token::authority = source_position)]
+    pub source_yes: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, associated_token::mint = no_mint, associated_token::authority = source_position)]
+    pub source_no: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, associated_token::mint = yes_mint, associated_token::authority = dest_position)]
+    pub dest_yes: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, associated_token::mint = no_mint, associated_token::authority = dest_position)]
+    pub dest_no: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> MergePrivacyPositions<'info> {
+    pub fn merge_privacy_positions(
+        &mut self,
+        source_commitment: [u8; 32],
+        dest_commitment: [u8; 32],
+        source_secret: [u8; 32],
+        dest_secret: [u8; 32],
+    ) -> Result<()> {
+        require!(source_commitment != dest_commitment, MergeError::SameCommitment);
+
+        let owner = self.owner.key();
+        require!(
+            PrivacyPosition::compute_commitment(&source_secret, &owner) == source_commitment,
+            MergeError::InvalidSecret
+        );
+        require!(
+            PrivacyPosition::compute_commitment(&dest_secret, &owner) == dest_commitment,
+            MergeError::InvalidSecret
+        );
+
+        let market_key = self.market.key();
+        let source_seeds = &[PrivacyPosition::SEED, market_key.as_ref(), source_commitment.as_ref(), &[self.source_position.bump]];
+        let source_signer = &[&source_seeds[..]];
+
+        let yes_amount = self.source_yes.amount;
+        let no_amount = self.source_no.amount;
+
+        for (amount, mint, from, to) in [
+            (yes_amount, &self.yes_mint, &self.source_yes, &self.dest_yes),
+            (no_amount, &self.no_mint, &self.source_no, &self.dest_no),
+        ] {
+            if amount > 0 {
+                transfer_checked(
+                    CpiContext::new_with_signer(
+                        self.token_program.to_account_info(),
+                        TransferChecked {
+                            from: from.to_account_info(),
+                            mint: mint.to_account_info(),
+                            to: to.to_account_info(),
+                            authority: self.source_position.to_account_info(),
+                        },
+                        source_signer,
+                    ),
+                    amount,
+                    mint.decimals,
+                )?;
+            }
+
+            close_account(CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                CloseAccount {
+                    account: from.to_account_info(),
+                    destination: self.owner.to_account_info(),
+                    authority: self.source_position.to_account_info(),
+                },
+                source_signer,
+            ))?;
+        }
+
+        let dest = &mut self.dest_position;
+        dest.yes_amount = dest.yes_amount.checked_add(self.source_position.yes_amount).ok_or(MergeError::Overflow)?;
+        dest.no_amount = dest.no_amount.checked_add(self.source_position.no_amount).ok_or(MergeError::Overflow)?;
+        dest.collateral_deposited = dest.collateral_deposited.checked_add(self.source_position.collateral_deposited).ok_or(MergeError::Overflow)?;
+
+        emit!(PrivacyPositionsMerged {
+            market_id: self.market.id,
+            source_commitment,
+            dest_commitment,
+            yes_amount: dest.yes_amount,
+            no_amount: dest.no_amount,
+        });
+
+        Ok(())
+    }
+}
+
+#[error_code(offset = 8600)]
+pub enum MergeError {
+    #[msg("Source and destination positions must differ")]
+    SameCommitment,
+    #[msg("Secret does not match position commitment")]
+    InvalidSecret,
+    #[msg("Arithmetic overflow")]
+    Overflow,
+}
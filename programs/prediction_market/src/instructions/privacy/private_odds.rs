@@ -3,8 +3,18 @@
 //! total reserves (liquidity) and supply encrypted.
 //! Because the reserves are secret, the price is literally 
 //! 'invisible' to external observers, preventing front-running.
+//!
+//! Once trading closes, the admin reveals the final plaintext values with
+//! `reveal_encrypted_market`. The proof is an Ed25519 signature by the
+//! Inco key over the plaintexts and a hash of the exact ciphertexts they
+//! decrypt, checked the same way as oracle attestations.
 
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{keccak, sysvar::instructions as sysvar_instructions};
+
+use crate::amm::PythagoreanCurve;
+use crate::instructions::attestation::verify_preceding_ed25519;
+use crate::state::{Market, MarketStatus};
 
 /// Encrypted Market State using Inco FHE
 #[account]
@@ -29,10 +39,28 @@ pub struct EncryptedMarketState {
     
     /// Bump seed
     pub bump: u8,
+
+    /// Whether the final values have been revealed (no further updates)
+    pub revealed: bool,
+
+    /// Decrypted reserves, set at reveal
+    pub revealed_reserves: u64,
+
+    /// Decrypted YES supply, set at reveal
+    pub revealed_yes_supply: u64,
+
+    /// Decrypted NO supply, set at reveal
+    pub revealed_no_supply: u64,
 }
 
-// Account size: 8 (discriminator) + 8 + 64 + 64 + 64 + 32 + 32 + 1 = 273 bytes
-const ENCRYPTED_MARKET_SPACE: usize = 8 + 8 + 64 + 64 + 64 + 32 + 32 + 1;
+// Account size: 8 (discriminator) + 8 + 64 + 64 + 64 + 32 + 32 + 1 + 1 + 8 + 8 + 8 = 298 bytes
+const ENCRYPTED_MARKET_SPACE: usize = 8 + 8 + 64 + 64 + 64 + 32 + 32 + 1 + 1 + 8 + 8 + 8;
+
+/// Length of a decryption proof message
+pub const REVEAL_MESSAGE_LEN: usize = 32 + 32 + 8 + 8 + 8;
+
+/// Length of an Inco FHE ciphertext handle stored on-chain
+pub const ENCRYPTED_CIPHER_LEN: usize = 64;
 
 /// Create an encrypted market state
 #[derive(Accounts)]
@@ -67,10 +95,13 @@ impl<'info> CreateEncryptedMarket<'info> {
         self.encrypted_market.market_id = market_id;
         self.encrypted_market.inco_pubkey = inco_pubkey;
         
-        // Copy initial reserves (up to 64 bytes)
-        let mut reserves = [0u8; 64];
-        let len = initial_encrypted_reserves.len().min(64);
-        reserves[..len].copy_from_slice(&initial_encrypted_reserves[..len]);
+        // Reject malformed ciphertexts instead of zero-padding or truncating
+        require!(
+            initial_encrypted_reserves.len() == ENCRYPTED_CIPHER_LEN,
+            PrivateOddsError::InvalidCiphertextLength
+        );
+        let mut reserves = [0u8; ENCRYPTED_CIPHER_LEN];
+        reserves.copy_from_slice(&initial_encrypted_reserves);
         self.encrypted_market.encrypted_reserves = reserves;
         
         self.encrypted_market.encrypted_yes_supply = [0u8; 64];
@@ -110,6 +141,8 @@ impl<'info> UpdateEncryptedReserves<'info> {
         encrypted_delta: Vec<u8>,
         is_yes: bool,
     ) -> Result<()> {
+        require!(!self.encrypted_market.revealed, PrivateOddsError::AlreadyRevealed);
+
         // In production, this would use Inco's FHE.add() function
         // For the PoC, we XOR the delta into the existing data
         let len = encrypted_delta.len().min(64);
@@ -143,3 +176,127 @@ pub struct EncryptedReservesUpdated {
     pub market_id: u64,
     pub update_type: String,
 }
+
+/// Event emitted when an encrypted market's final values are made public
+#[event]
+pub struct EncryptedMarketRevealed {
+    pub market_id: u64,
+    pub reserves: u64,
+    pub yes_supply: u64,
+    pub no_supply: u64,
+    /// Implied YES probability (bps) from the revealed supplies
+    pub final_yes_probability_bps: u64,
+    /// Implied NO probability (bps) from the revealed supplies
+    pub final_no_probability_bps: u64,
+}
+
+/// Reveal the final plaintext state of an encrypted market
+#[derive(Accounts)]
+pub struct RevealEncryptedMarket<'info> {
+    #[account(constraint = admin.key() == encrypted_market.admin @ PrivateOddsError::Unauthorized)]
+    pub admin: Signer<'info>,
+
+    #[account(constraint = market.status != MarketStatus::Active @ PrivateOddsError::MarketStillOpen)]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(
+        mut,
+        seeds = [b"encrypted_market", market.key().as_ref()],
+        bump = encrypted_market.bump,
+    )]
+    pub encrypted_market: Account<'info, EncryptedMarketState>,
+
+    /// CHECK: Instructions sysvar, read for the preceding Ed25519 instruction
+    #[account(address = sysvar_instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+}
+
+impl<'info> RevealEncryptedMarket<'info> {
+    /// Publish the decrypted values, proven by the Inco key's signature over them
+    pub fn reveal_encrypted_market(&mut self, reserves: u64, yes_supply: u64, no_supply: u64, signature: [u8; 64]) -> Result<()> {
+        let state = &self.encrypted_market;
+        require!(!state.revealed, PrivateOddsError::AlreadyRevealed);
+
+        let message = reveal_message(&state.key(), state, reserves, yes_supply, no_supply);
+        verify_preceding_ed25519(&self.instructions_sysvar, &Pubkey::new_from_array(state.inco_pubkey), &signature, &message)?;
+
+        let final_yes_probability_bps = PythagoreanCurve::get_implied_probability_bps(yes_supply, no_supply)?;
+        let final_no_probability_bps = PythagoreanCurve::get_implied_probability_bps(no_supply, yes_supply)?;
+
+        let state = &mut self.encrypted_market;
+        state.revealed = true;
+        state.revealed_reserves = reserves;
+        state.revealed_yes_supply = yes_supply;
+        state.revealed_no_supply = no_supply;
+
+        emit!(EncryptedMarketRevealed {
+            market_id: state.market_id,
+            reserves,
+            yes_supply,
+            no_supply,
+            final_yes_probability_bps,
+            final_no_probability_bps,
+        });
+        Ok(())
+    }
+}
+
+/// Message the Inco key signs to prove a decryption
+///
+/// ```text
+/// encrypted_market (32) || keccak(reserves_ct || yes_ct || no_ct) (32) || reserves || yes_supply || no_supply (u64 LE)
+/// ```
+///
+/// Hashing the ciphertexts ties the plaintexts to the state as it is now,
+/// so a proof for an earlier state cannot be replayed after an update.
+pub fn reveal_message(encrypted_market: &Pubkey, state: &EncryptedMarketState, reserves: u64, yes_supply: u64, no_supply: u64) -> [u8; REVEAL_MESSAGE_LEN] {
+    let ciphertext_hash = keccak::hashv(&[&state.encrypted_reserves, &state.encrypted_yes_supply, &state.encrypted_no_supply]).0;
+    let mut msg = [0u8; REVEAL_MESSAGE_LEN];
+    msg[..32].copy_from_slice(encrypted_market.as_ref());
+    msg[32..64].copy_from_slice(&ciphertext_hash);
+    msg[64..72].copy_from_slice(&reserves.to_le_bytes());
+    msg[72..80].copy_from_slice(&yes_supply.to_le_bytes());
+    msg[80..].copy_from_slice(&no_supply.to_le_bytes());
+    msg
+}
+
+#[error_code(offset = 8400)]
+pub enum PrivateOddsError {
+    #[msg("Encrypted reserves must be exactly 64 bytes")]
+    InvalidCiphertextLength,
+    #[msg("Only the encrypted market's admin can reveal it")]
+    Unauthorized,
+    #[msg("Market is still trading")]
+    MarketStillOpen,
+    #[msg("Encrypted market has already been revealed")]
+    AlreadyRevealed,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reveal_message_binds_ciphertexts() {
+        let key = Pubkey::new_unique();
+        let mut state = EncryptedMarketState {
+            market_id: 1,
+            encrypted_reserves: [1u8; 64],
+            encrypted_yes_supply: [2u8; 64],
+            encrypted_no_supply: [3u8; 64],
+            inco_pubkey: [0u8; 32],
+            admin: Pubkey::new_unique(),
+            bump: 0,
+            revealed: false,
+            revealed_reserves: 0,
+            revealed_yes_supply: 0,
+            revealed_no_supply: 0,
+        };
+        let before = reveal_message(&key, &state, 100, 60, 80);
+        assert_eq!(&before[64..72], &100u64.to_le_bytes());
+        assert_ne!(before, reveal_message(&key, &state, 100, 60, 81));
+
+        state.encrypted_yes_supply[0] ^= 1;
+        assert_ne!(before, reveal_message(&key, &state, 100, 60, 80));
+    }
+}
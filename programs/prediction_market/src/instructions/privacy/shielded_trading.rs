@@ -6,17 +6,27 @@
 //! trader reveals their secret after market resolution.
 //!
 //! Step 1: TradeShielded - Enter with encrypted direction
-//! Step 2: RevealAndRedeem - Prove direction at resolution and claim payout
+//! Step 2: RevealShielded - Winners prove their direction during the reveal window
+//! Step 3: RevealAndRedeem - Claim the payout once the window closes
+//!
+//! Shielded deposits settle among themselves: each revealed winner gets
+//! its deposit's share of all shielded deposits, so the payouts can never
+//! add up to more than was deposited.
 
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
     token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked, transfer_checked},
 };
-use anchor_lang::solana_program::keccak;
+use anchor_lang::solana_program::{keccak, sysvar::instructions as sysvar_instructions};
 
-use crate::state::{Config, Market, MarketStatus, ShieldedPosition, Outcome};
-use crate::instructions::public::TradeError;
+use crate::instructions::compliance::check_recipient;
+use crate::instructions::cpi_guard::check_cpi_caller;
+use crate::instructions::clock::now;
+use crate::state::{Config, Market, MarketStatus, ProtocolStats, ShieldedPosition};
+#[cfg(feature = "test-clock")]
+use crate::state::TestClock;
+use crate::instructions::public::{check_exit_window_closed, TradeError};
 
 // =============================================================================
 // STEP 1: TRADE SHIELDED (Blind Entry)
@@ -29,6 +39,7 @@ pub struct ShieldedPositionEntered {
     pub commitment: [u8; 32],
     pub shielded_amount: u64,
     // Note: direction is NOT emitted - it's private!
+    pub event_seq: u64,
 }
 
 #[derive(Accounts)]
@@ -37,12 +48,13 @@ pub struct TradeShielded<'info> {
     #[account(mut)]
     pub trader: Signer<'info>,
 
-    #[account(seeds = [Config::SEED], bump = config.bump)]
+    #[account(mut, seeds = [Config::SEED], bump = config.bump)]
     pub config: Account<'info, Config>,
 
     #[account(
         mut,
         constraint = market.status == MarketStatus::Active @ TradeError::MarketNotActive,
+        constraint = market.allow_privacy @ TradeError::PrivacyDisabled,
     )]
     pub market: Account<'info, Market>,
 
@@ -71,9 +83,17 @@ pub struct TradeShielded<'info> {
     )]
     pub vault: InterfaceAccount<'info, TokenAccount>,
 
+    #[account(mut, seeds = [ProtocolStats::SEED], bump = protocol_stats.bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
+
+    /// Time override (test-clock builds only, see `instructions::clock`)
+    #[cfg(feature = "test-clock")]
+    #[account(seeds = [TestClock::SEED], bump = test_clock.bump)]
+    pub test_clock: Option<Box<Account<'info, TestClock>>>,
 }
 
 impl<'info> TradeShielded<'info> {
@@ -84,9 +104,12 @@ impl<'info> TradeShielded<'info> {
         amount: u64,
         bump: u8,
     ) -> Result<()> {
-        let clock = Clock::get()?;
-        require!(clock.unix_timestamp < self.market.end_time as i64, TradeError::MarketEnded);
+        // Deposits at or past `end_time` fail even while the status still reads
+        // `Active`; the flip to `Ended` is `end_market`'s, since it would roll back here
+        require!(!self.market.has_ended(now!(self)?), TradeError::MarketEnded);
         require!(!self.config.paused, TradeError::ProtocolPaused);
+        require!(commitment != [0u8; 32], ShieldedError::DegenerateCommitment);
+        require!(direction_cipher != [0u8; 32], ShieldedError::DegenerateCommitment);
 
         // Transfer collateral to vault
         transfer_checked(
@@ -105,6 +128,10 @@ impl<'info> TradeShielded<'info> {
 
         // Update market reserves (hidden supply updates happen at reveal)
         self.market.reserves += amount;
+        self.market.shielded_deposits = self.market.shielded_deposits.checked_add(amount).ok_or(ShieldedError::Overflow)?;
+        self.market.record_volume(amount);
+        self.protocol_stats.record_trade(amount, 0);
+        self.protocol_stats.record_deposit(amount);
 
         // Initialize shielded position with encrypted direction
         let pos = &mut self.shielded_position;
@@ -113,12 +140,15 @@ impl<'info> TradeShielded<'info> {
         pos.direction_cipher = direction_cipher;
         pos.shielded_amount = amount; // Stored as collateral value
         pos.collateral_deposited = amount;
+        pos.revealed = false;
+        pos.recipient = Pubkey::default();
         pos.bump = bump;
 
         emit!(ShieldedPositionEntered {
             market_id: self.market.id,
             commitment,
             shielded_amount: amount,
+            event_seq: self.config.next_event_seq(),
         });
 
         Ok(())
@@ -126,7 +156,100 @@ impl<'info> TradeShielded<'info> {
 }
 
 // =============================================================================
-// STEP 2: REVEAL AND REDEEM (Post-Resolution Claim)
+// STEP 2: REVEAL SHIELDED (Winners Step Forward)
+// =============================================================================
+
+/// Event emitted when a winning shielded position is revealed
+#[event]
+pub struct ShieldedWinRevealed {
+    pub market_id: u64,
+    pub commitment: [u8; 32],
+    pub amount: u64,
+    pub winning_revealed: u64,
+    pub event_seq: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(secret: [u8; 32], commitment: [u8; 32])]
+pub struct RevealShielded<'info> {
+    pub revealer: Signer<'info>,
+
+    #[account(mut, seeds = [Config::SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        constraint = market.status == MarketStatus::Resolved @ ShieldedError::MarketNotResolved,
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(
+        mut,
+        seeds = [ShieldedPosition::SEED, market.key().as_ref(), commitment.as_ref()],
+        bump = shielded_position.bump,
+    )]
+    pub shielded_position: Account<'info, ShieldedPosition>,
+
+    /// The wallet the payout is locked to (can differ from the revealer)
+    /// CHECK: Only its key is stored
+    pub recipient: AccountInfo<'info>,
+
+    /// CHECK: Must match `config.recipient_allowlist` when one is set
+    pub allowlist_program: Option<UncheckedAccount<'info>>,
+
+    /// Time override (test-clock builds only, see `instructions::clock`)
+    #[cfg(feature = "test-clock")]
+    #[account(seeds = [TestClock::SEED], bump = test_clock.bump)]
+    pub test_clock: Option<Box<Account<'info, TestClock>>>,
+}
+
+impl<'info> RevealShielded<'info> {
+    /// Count a winning position toward the shielded pool's split
+    ///
+    /// The secret is public once this lands, so the recipient is fixed here;
+    /// `reveal_and_redeem` can then only pay that wallet.
+    pub fn reveal_shielded(&mut self, secret: [u8; 32], commitment: [u8; 32]) -> Result<()> {
+        let now = now!(self)?;
+        require!(
+            self.market.shielded_reveal_open(now, self.config.shielded_reveal_window),
+            ShieldedError::RevealWindowClosed
+        );
+
+        let pos = &self.shielded_position;
+        require!(keccak::hash(&secret).0 == commitment, ShieldedError::InvalidSecret);
+        require!(pos.commitment == commitment, ShieldedError::CommitmentMismatch);
+        require!(!pos.revealed, ShieldedError::AlreadyRevealed);
+        check_recipient(&self.config, self.allowlist_program.as_ref(), &self.recipient)?;
+
+        let bet_yes = ShieldedPosition::decrypt_direction(&pos.direction_cipher, &secret);
+        let side = self.market.winning_side().ok_or(ShieldedError::MarketNotResolved)?;
+        require!(side.pick(bet_yes, !bet_yes), ShieldedError::NotWinningPosition);
+
+        let amount = pos.collateral_deposited;
+        self.market.shielded_winning_revealed = self
+            .market
+            .shielded_winning_revealed
+            .checked_add(amount)
+            .ok_or(ShieldedError::Overflow)?;
+
+        let pos = &mut self.shielded_position;
+        pos.revealed = true;
+        pos.recipient = self.recipient.key();
+
+        emit!(ShieldedWinRevealed {
+            market_id: self.market.id,
+            commitment,
+            amount,
+            winning_revealed: self.market.shielded_winning_revealed,
+            event_seq: self.config.next_event_seq(),
+        });
+
+        Ok(())
+    }
+}
+
+// =============================================================================
+// STEP 3: REVEAL AND REDEEM (Post-Resolution Claim)
 // =============================================================================
 
 /// Event emitted when a shielded position is revealed and redeemed
@@ -137,6 +260,7 @@ pub struct ShieldedPositionRevealed {
     pub revealed_direction: bool, // true = YES, false = NO
     pub won: bool,
     pub payout: u64,
+    pub event_seq: u64,
 }
 
 #[derive(Accounts)]
@@ -145,12 +269,12 @@ pub struct RevealAndRedeem<'info> {
     #[account(mut)]
     pub revealer: Signer<'info>,
 
-    #[account(seeds = [Config::SEED], bump = config.bump)]
+    #[account(mut, seeds = [Config::SEED], bump = config.bump)]
     pub config: Account<'info, Config>,
 
     #[account(
         mut,
-        constraint = market.status == MarketStatus::Resolved @ ShieldedError::MarketNotResolved,
+        constraint = market.status == MarketStatus::Resolved || market.status == MarketStatus::Cancelled @ ShieldedError::MarketNotResolved,
     )]
     pub market: Account<'info, Market>,
 
@@ -180,46 +304,80 @@ pub struct RevealAndRedeem<'info> {
         payer = revealer,
         associated_token::mint = collateral_mint,
         associated_token::authority = recipient,
+        constraint = !recipient_collateral.is_frozen() @ ShieldedError::RecipientAccountFrozen,
     )]
     pub recipient_collateral: InterfaceAccount<'info, TokenAccount>,
 
+    #[account(mut, seeds = [ProtocolStats::SEED], bump = protocol_stats.bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+
+    /// CHECK: Must match `config.recipient_allowlist` when one is set
+    pub allowlist_program: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Instructions sysvar, used by the CPI guard
+    #[account(address = sysvar_instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
+
+    /// Time override (test-clock builds only, see `instructions::clock`)
+    #[cfg(feature = "test-clock")]
+    #[account(seeds = [TestClock::SEED], bump = test_clock.bump)]
+    pub test_clock: Option<Box<Account<'info, TestClock>>>,
 }
 
 impl<'info> RevealAndRedeem<'info> {
     pub fn reveal_and_redeem(&mut self, secret: [u8; 32], commitment: [u8; 32]) -> Result<()> {
+        check_cpi_caller(&self.config, &self.instructions_sysvar)?;
         let pos = &self.shielded_position;
 
         // Verify commitment matches
         let computed_commitment = keccak::hash(&secret).0;
         require!(computed_commitment == commitment, ShieldedError::InvalidSecret);
         require!(pos.commitment == commitment, ShieldedError::CommitmentMismatch);
+        check_recipient(&self.config, self.allowlist_program.as_ref(), &self.recipient)?;
 
         // Decrypt direction
         let bet_yes = ShieldedPosition::decrypt_direction(&pos.direction_cipher, &secret);
 
-        // Check if won
-        let won = match self.market.outcome {
-            Outcome::Yes => bet_yes,
-            Outcome::No => !bet_yes,
-            Outcome::Undetermined => return err!(ShieldedError::MarketNotResolved),
+        // A cancelled market refunds every position regardless of direction
+        let cancelled = self.market.status == MarketStatus::Cancelled;
+
+        // Only positions revealed as winners during the window are paid
+        let won = if cancelled {
+            false
+        } else {
+            let now = now!(self)?;
+            check_exit_window_closed(&self.market, &self.config, now)?;
+            require!(
+                !self.market.shielded_reveal_open(now, self.config.shielded_reveal_window),
+                ShieldedError::RevealWindowOpen
+            );
+            pos.revealed
         };
 
-        let payout = if won {
-            // Winner gets back their collateral (simplified payout for hackathon)
-            // In production, this would be proportional to total pool
+        let payout = if cancelled {
             pos.collateral_deposited
+        } else if won {
+            require_keys_eq!(self.recipient.key(), pos.recipient, ShieldedError::RecipientMismatch);
+            self.market.shielded_payout(pos.collateral_deposited).ok_or(ShieldedError::Overflow)?
         } else {
             0
         };
 
         if payout > 0 {
+            // Public redemptions share this vault, so earlier exits can leave it short
+            check_vault_covers(self.vault.amount, payout)?;
+
             // Transfer payout from vault to recipient
+            let config_key = self.config.key();
+            let market_id_bytes = self.market.id.to_le_bytes();
             let market_seeds = &[
                 Market::SEED,
-                &self.market.id.to_le_bytes(),
+                config_key.as_ref(),
+                &market_id_bytes,
                 &[self.market.bump],
             ];
             let market_signer = &[&market_seeds[..]];
@@ -239,7 +397,8 @@ impl<'info> RevealAndRedeem<'info> {
                 self.collateral_mint.decimals,
             )?;
 
-            self.market.reserves -= payout;
+            self.market.reserves = self.market.reserves.checked_sub(payout).ok_or(ShieldedError::InsufficientReserves)?;
+            self.protocol_stats.record_withdrawal(payout);
         }
 
         emit!(ShieldedPositionRevealed {
@@ -248,17 +407,24 @@ impl<'info> RevealAndRedeem<'info> {
             revealed_direction: bet_yes,
             won,
             payout,
+            event_seq: self.config.next_event_seq(),
         });
 
         Ok(())
     }
 }
 
+/// Fail with a clear error if the vault can't pay `payout` in full
+fn check_vault_covers(vault_balance: u64, payout: u64) -> Result<()> {
+    require!(vault_balance >= payout, ShieldedError::InsufficientVault);
+    Ok(())
+}
+
 // =============================================================================
 // ERRORS
 // =============================================================================
 
-#[error_code]
+#[error_code(offset = 8000)]
 pub enum ShieldedError {
     #[msg("Invalid secret - does not match commitment")]
     InvalidSecret,
@@ -266,4 +432,64 @@ pub enum ShieldedError {
     CommitmentMismatch,
     #[msg("Market not yet resolved")]
     MarketNotResolved,
+    #[msg("Recipient token account is frozen, use a different recipient")]
+    RecipientAccountFrozen,
+    #[msg("Market reserves cannot cover this payout")]
+    InsufficientReserves,
+    #[msg("Commitment or direction cipher is all zeros")]
+    DegenerateCommitment,
+    #[msg("Market vault cannot cover this payout")]
+    InsufficientVault,
+    #[msg("Arithmetic overflow")]
+    Overflow,
+    #[msg("Shielded reveal window has closed")]
+    RevealWindowClosed,
+    #[msg("Shielded payouts wait until the reveal window closes")]
+    RevealWindowOpen,
+    #[msg("Position has already been revealed")]
+    AlreadyRevealed,
+    #[msg("Only winning positions are revealed")]
+    NotWinningPosition,
+    #[msg("Recipient does not match the one fixed at reveal")]
+    RecipientMismatch,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_winners_stop_at_an_exhausted_vault() {
+        // Three winners of 100 each against a vault left holding 250
+        let mut vault = 250u64;
+        for _ in 0..2 {
+            check_vault_covers(vault, 100).unwrap();
+            vault -= 100;
+        }
+        assert!(check_vault_covers(vault, 100).is_err());
+        assert!(check_vault_covers(vault, 50).is_ok());
+    }
+
+    #[test]
+    fn test_shielded_winners_exhaust_the_vault_exactly() {
+        use crate::state::market::tests::market;
+        use crate::state::SettlementMode;
+
+        // Deposits of 300, 200 and 100 won, 400 and 1 lost; the vault holds only the shielded pool
+        let winners = [300u64, 200, 100];
+        let mut m = market(0, SettlementMode::ProRata);
+        m.shielded_deposits = winners.iter().sum::<u64>() + 400 + 1;
+        m.shielded_winning_revealed = winners.iter().sum();
+
+        let mut vault = m.shielded_deposits;
+        for deposit in winners {
+            let payout = m.shielded_payout(deposit).unwrap();
+            check_vault_covers(vault, payout).unwrap();
+            vault -= payout;
+        }
+
+        // 500, 333 and 166 paid out: only rounding dust is left behind
+        assert!(vault < winners.len() as u64);
+        assert!(check_vault_covers(vault, m.shielded_payout(100).unwrap()).is_err());
+    }
 }
@@ -0,0 +1,169 @@
+//! Bonding Curve Sampling
+//!
+//! Read-only view that samples the Pythagorean curve for charting.
+//! Each point is computed with the same fee and curve math as `buy_tokens`,
+//! so the rendered slippage curve matches what a trade would execute.
+//!
+//! `collateral_for_target_price` inverts the curve: buying side A until its
+//! price is `p` leaves B unchanged and needs R' = B / √(1 - p²), so the net
+//! input is R' - R, grossed up by the same fee `buy_tokens` would charge.
+
+use anchor_lang::prelude::*;
+
+use crate::amm::{sqrt, AmmError, PythagoreanCurve};
+use crate::instructions::public::split_fee;
+use crate::state::{Config, FeeRounding, Market};
+
+/// Maximum number of points per call (keeps the result within return-data limits)
+pub const MAX_CURVE_SAMPLES: u8 = 32;
+
+/// One sample of the buy curve
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CurvePoint {
+    /// Gross collateral paid, before fees
+    pub collateral_in: u64,
+    /// Outcome tokens minted for that collateral
+    pub tokens_out: u64,
+    /// Price of the bought side after the trade (bps)
+    pub resulting_price: u64,
+}
+
+#[derive(Accounts)]
+pub struct SampleCurve<'info> {
+    #[account(seeds = [Config::SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    pub market: Account<'info, Market>,
+}
+
+impl<'info> SampleCurve<'info> {
+    /// Sample `steps` evenly spaced buys, up to the size of the current reserves
+    pub fn sample_curve(&self, steps: u8, buy_yes: bool) -> Result<Vec<CurvePoint>> {
+        require!(steps > 0 && steps <= MAX_CURVE_SAMPLES, CurveError::InvalidSteps);
+
+        let market = &self.market;
+        let (target_supply, other_supply) = if buy_yes { (market.yes_supply, market.no_supply) } else { (market.no_supply, market.yes_supply) };
+        let fee_bps = self.config.buy_fee_bps(target_supply, other_supply);
+
+        sample_buy_curve(market.reserves, target_supply, other_supply, fee_bps, self.config.fee_rounding, steps)
+    }
+}
+
+/// Sample buys of `reserves * i / steps` collateral for `i` in `1..=steps`
+pub fn sample_buy_curve(
+    reserves: u64,
+    target_supply: u64,
+    other_supply: u64,
+    fee_bps: u64,
+    fee_rounding: FeeRounding,
+    steps: u8,
+) -> Result<Vec<CurvePoint>> {
+    let mut points = Vec::with_capacity(steps as usize);
+
+    for i in 1..=steps as u64 {
+        let collateral_in = (reserves as u128 * i as u128 / steps as u128) as u64;
+        let (_, amount_after_fee) = split_fee(collateral_in, fee_bps, fee_rounding)?;
+
+        let tokens_out = PythagoreanCurve::get_tokens_to_mint(reserves, target_supply, other_supply, amount_after_fee)?;
+        let resulting_price = PythagoreanCurve::get_price(
+            reserves.checked_add(amount_after_fee).ok_or(AmmError::Overflow)?,
+            target_supply.checked_add(tokens_out).ok_or(AmmError::Overflow)?,
+            other_supply,
+        )?;
+
+        points.push(CurvePoint { collateral_in, tokens_out, resulting_price });
+    }
+
+    Ok(points)
+}
+
+#[derive(Accounts)]
+pub struct CollateralForTargetPrice<'info> {
+    #[account(seeds = [Config::SEED], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    pub market: Account<'info, Market>,
+}
+
+impl<'info> CollateralForTargetPrice<'info> {
+    /// Gross collateral a buy of `buy_yes` needs to lift its price to `target_price_bps`
+    pub fn collateral_for_target_price(&self, target_price_bps: u64, buy_yes: bool) -> Result<u64> {
+        let market = &self.market;
+        let (target_supply, other_supply) = if buy_yes { (market.yes_supply, market.no_supply) } else { (market.no_supply, market.yes_supply) };
+        let fee_bps = self.config.buy_fee_bps(target_supply, other_supply);
+
+        collateral_for_price(market.reserves, target_supply, other_supply, fee_bps, self.config.fee_rounding, target_price_bps)
+    }
+}
+
+/// Gross collateral to move the bought side's price to `target_price_bps` (0 if already there)
+pub fn collateral_for_price(
+    reserves: u64,
+    target_supply: u64,
+    other_supply: u64,
+    fee_bps: u64,
+    fee_rounding: FeeRounding,
+    target_price_bps: u64,
+) -> Result<u64> {
+    require!(target_price_bps < 10_000, CurveError::InvalidTargetPrice);
+    require!(fee_bps < 10_000, CurveError::InvalidTargetPrice);
+    if PythagoreanCurve::get_price(reserves, target_supply, other_supply)? >= target_price_bps {
+        return Ok(0);
+    }
+
+    // R' = B * 10000 / √(10000² - p²), rounded up so the target is reached
+    let p = target_price_bps as u128;
+    let denominator = sqrt(100_000_000 - p * p);
+    let new_reserves = (other_supply as u128 * 10_000).div_ceil(denominator);
+    let net = new_reserves.saturating_sub(reserves as u128);
+
+    // Gross up so that `split_fee(gross, fee_bps)` leaves at least `net`.
+    // Exact for a floored fee; a rounded-up fee can take one unit more.
+    let mut gross = u64::try_from((net * 10_000).div_ceil(10_000 - fee_bps as u128)).map_err(|_| error!(AmmError::Overflow))?;
+    if gross > 0 && (split_fee(gross, fee_bps, fee_rounding)?.1 as u128) < net {
+        gross = gross.checked_add(1).ok_or(AmmError::Overflow)?;
+    }
+    Ok(gross)
+}
+
+#[error_code(offset = 7400)]
+pub enum CurveError {
+    #[msg("Sample count must be between 1 and MAX_CURVE_SAMPLES")]
+    InvalidSteps,
+    #[msg("Target price must be below 10000 bps")]
+    InvalidTargetPrice,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sample_buy_curve_is_monotonic() {
+        let points = sample_buy_curve(1_000_000, 707_000, 707_000, 100, FeeRounding::Down, 8).unwrap();
+        assert_eq!(points.len(), 8);
+        assert_eq!(points[7].collateral_in, 1_000_000);
+
+        for pair in points.windows(2) {
+            assert!(pair[1].tokens_out > pair[0].tokens_out);
+            assert!(pair[1].resulting_price >= pair[0].resulting_price);
+        }
+    }
+
+    #[test]
+    fn test_collateral_for_price_reaches_target() {
+        let (reserves, yes, no) = (1_000_000u64, 707_107u64, 707_107u64);
+        for rounding in [FeeRounding::Down, FeeRounding::Up, FeeRounding::Nearest] {
+            let gross = collateral_for_price(reserves, yes, no, 100, rounding, 8_000).unwrap();
+
+            // Replaying the buy with the trade path's math lands on the target
+            let (_, net) = split_fee(gross, 100, rounding).unwrap();
+            let tokens_out = PythagoreanCurve::get_tokens_to_mint(reserves, yes, no, net).unwrap();
+            let price = PythagoreanCurve::get_price(reserves + net, yes + tokens_out, no).unwrap();
+            assert!((7_990..=8_010).contains(&price));
+        }
+
+        assert_eq!(collateral_for_price(reserves, yes, no, 100, FeeRounding::Down, 5_000).unwrap(), 0);
+        assert!(collateral_for_price(reserves, yes, no, 100, FeeRounding::Down, 10_000).is_err());
+    }
+}
@@ -0,0 +1,130 @@
+//! Emergency Withdrawal
+//!
+//! User-protection backstop for an abandoned protocol. If the admin pauses
+//! and never unpauses, funds would otherwise be stranded in market vaults.
+//! Once the pause has outlasted `config.emergency_withdraw_delay`, any holder
+//! of outcome tokens can burn them for a proportional share of reserves,
+//! regardless of the market's resolution status.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{burn, transfer_checked, Burn, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::amm::AmmError;
+use crate::instructions::clock::now;
+use crate::state::{Config, Market, ProtocolStats};
+#[cfg(feature = "test-clock")]
+use crate::state::TestClock;
+
+/// Event emitted when a user exits through the emergency hatch
+#[event]
+pub struct EmergencyWithdrawal {
+    pub market_id: u64,
+    pub user: Pubkey,
+    pub yes_burned: u64,
+    pub no_burned: u64,
+    pub collateral_out: u64,
+    pub event_seq: u64,
+}
+
+#[derive(Accounts)]
+pub struct EmergencyWithdraw<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(mut, seeds = [Config::SEED], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(mut)]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(mut, constraint = yes_mint.key() == market.yes_mint)]
+    pub yes_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut, constraint = no_mint.key() == market.no_mint)]
+    pub no_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(constraint = collateral_mint.key() == market.collateral_mint)]
+    pub collateral_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut, associated_token::mint = yes_mint, associated_token::authority = user)]
+    pub user_yes: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, associated_token::mint = no_mint, associated_token::authority = user)]
+    pub user_no: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, associated_token::mint = collateral_mint, associated_token::authority = user)]
+    pub user_collateral: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, associated_token::mint = collateral_mint, associated_token::authority = market)]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, seeds = [ProtocolStats::SEED], bump = protocol_stats.bump)]
+    pub protocol_stats: Box<Account<'info, ProtocolStats>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// Time override (test-clock builds only, see `instructions::clock`)
+    #[cfg(feature = "test-clock")]
+    #[account(seeds = [TestClock::SEED], bump = test_clock.bump)]
+    pub test_clock: Option<Box<Account<'info, TestClock>>>,
+}
+
+impl<'info> EmergencyWithdraw<'info> {
+    pub fn emergency_withdraw(&mut self) -> Result<u64> {
+        let now = now!(self)?;
+        require!(self.config.paused, EmergencyError::NotPaused);
+        require!(self.config.emergency_withdraw_open(now), EmergencyError::DelayNotElapsed);
+
+        let yes_burned = self.user_yes.amount;
+        let no_burned = self.user_no.amount;
+        let user_tokens = yes_burned.checked_add(no_burned).ok_or(AmmError::Overflow)?;
+        require!(user_tokens > 0, EmergencyError::NothingToWithdraw);
+
+        // Every outstanding token gets an equal share of the pool
+        let collateral_out = self.market.outcome_neutral_payout(user_tokens).ok_or(EmergencyError::NothingToWithdraw)?;
+
+        for (amount, mint, from) in [
+            (yes_burned, &self.yes_mint, &self.user_yes),
+            (no_burned, &self.no_mint, &self.user_no),
+        ] {
+            if amount > 0 {
+                burn(CpiContext::new(self.token_program.to_account_info(), Burn { mint: mint.to_account_info(), from: from.to_account_info(), authority: self.user.to_account_info() }), amount)?;
+            }
+        }
+
+        let config_key = self.config.key();
+        let market_id_bytes = self.market.id.to_le_bytes();
+        let market_seeds = &[Market::SEED, config_key.as_ref(), &market_id_bytes, &[self.market.bump]];
+        let market_signer = &[&market_seeds[..]];
+
+        if collateral_out > 0 {
+            transfer_checked(CpiContext::new_with_signer(self.token_program.to_account_info(), TransferChecked { from: self.vault.to_account_info(), mint: self.collateral_mint.to_account_info(), to: self.user_collateral.to_account_info(), authority: self.market.to_account_info() }, market_signer), collateral_out, self.collateral_mint.decimals)?;
+        }
+
+        self.market.reserves -= collateral_out;
+        self.market.yes_supply -= yes_burned;
+        self.market.no_supply -= no_burned;
+        self.protocol_stats.record_withdrawal(collateral_out);
+
+        emit!(EmergencyWithdrawal {
+            market_id: self.market.id,
+            user: self.user.key(),
+            yes_burned,
+            no_burned,
+            collateral_out,
+            event_seq: self.config.next_event_seq(),
+        });
+
+        Ok(collateral_out)
+    }
+}
+
+#[error_code(offset = 7700)]
+pub enum EmergencyError {
+    #[msg("Protocol is not paused")]
+    NotPaused,
+    #[msg("Emergency withdraw delay has not elapsed")]
+    DelayNotElapsed,
+    #[msg("No outcome tokens to withdraw against")]
+    NothingToWithdraw,
+}
@@ -0,0 +1,224 @@
+//! Liquidity Provision
+//!
+//! Adds or removes collateral pro rata, scaling reserves and both outcome
+//! supplies by the same factor. The Pythagorean invariant is homogeneous,
+//! so prices are unchanged and the provider holds a slice of the pool as
+//! YES + NO tokens, exactly like the creator after `fund_market`.
+//!
+//! A "share" is one complete set on the scarcer side: adding `L` collateral
+//! yields `min(yes_out, no_out)` shares, and removing those shares at the
+//! same pool state returns `L`. Both entry points take a slippage floor so
+//! a provider cannot be sandwiched by a skewing trade.
+//!
+//! `quote_add_liquidity` previews an add with the same math, read-only.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{burn, mint_to, transfer_checked, Burn, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::amm::PythagoreanCurve;
+use crate::instructions::public::TradeError;
+use crate::instructions::clock::now;
+use crate::state::{Config, Market, MarketStatus, ProtocolStats};
+#[cfg(feature = "test-clock")]
+use crate::state::TestClock;
+
+#[event]
+pub struct LiquidityAdded {
+    pub market_id: u64,
+    pub provider: Pubkey,
+    pub collateral_in: u64,
+    pub yes_out: u64,
+    pub no_out: u64,
+    pub shares_out: u64,
+}
+
+#[event]
+pub struct LiquidityRemoved {
+    pub market_id: u64,
+    pub provider: Pubkey,
+    pub shares_in: u64,
+    pub yes_burned: u64,
+    pub no_burned: u64,
+    pub collateral_out: u64,
+}
+
+/// Preview of what `add_liquidity` would mint
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LiquidityQuote {
+    pub yes_out: u64,
+    pub no_out: u64,
+    pub shares_out: u64,
+}
+
+#[derive(Accounts)]
+pub struct QuoteAddLiquidity<'info> {
+    pub market: Account<'info, Market>,
+}
+
+impl<'info> QuoteAddLiquidity<'info> {
+    pub fn quote_add_liquidity(&self, amount: u64) -> Result<LiquidityQuote> {
+        let (yes_out, no_out, shares_out) = liquidity_mint_amounts(self.market.reserves, self.market.yes_supply, self.market.no_supply, amount)?;
+        Ok(LiquidityQuote { yes_out, no_out, shares_out })
+    }
+}
+
+#[derive(Accounts)]
+pub struct ManageLiquidity<'info> {
+    pub provider: Signer<'info>,
+
+    #[account(seeds = [Config::SEED], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(mut, constraint = market.status == MarketStatus::Active @ TradeError::MarketNotActive)]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(mut, constraint = yes_mint.key() == market.yes_mint)]
+    pub yes_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut, constraint = no_mint.key() == market.no_mint)]
+    pub no_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(constraint = collateral_mint.key() == market.collateral_mint)]
+    pub collateral_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut, associated_token::mint = collateral_mint, associated_token::authority = provider)]
+    pub provider_collateral: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, associated_token::mint = yes_mint, associated_token::authority = provider)]
+    pub provider_yes: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, associated_token::mint = no_mint, associated_token::authority = provider)]
+    pub provider_no: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, associated_token::mint = collateral_mint, associated_token::authority = market)]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, seeds = [ProtocolStats::SEED], bump = protocol_stats.bump)]
+    pub protocol_stats: Box<Account<'info, ProtocolStats>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// Time override (test-clock builds only, see `instructions::clock`)
+    #[cfg(feature = "test-clock")]
+    #[account(seeds = [TestClock::SEED], bump = test_clock.bump)]
+    pub test_clock: Option<Box<Account<'info, TestClock>>>,
+}
+
+impl<'info> ManageLiquidity<'info> {
+    pub fn add_liquidity(&mut self, amount: u64, min_shares_out: u64) -> Result<u64> {
+        self.require_open()?;
+        require!(!self.config.enforce_slippage || min_shares_out > 0, TradeError::SlippageProtectionRequired);
+
+        let (yes_out, no_out, shares_out) = liquidity_mint_amounts(self.market.reserves, self.market.yes_supply, self.market.no_supply, amount)?;
+        require!(shares_out >= min_shares_out, TradeError::SlippageExceeded);
+        PythagoreanCurve::check_supply_cap(self.market.yes_supply, yes_out)?;
+        PythagoreanCurve::check_supply_cap(self.market.no_supply, no_out)?;
+
+        transfer_checked(CpiContext::new(self.token_program.to_account_info(), TransferChecked { from: self.provider_collateral.to_account_info(), mint: self.collateral_mint.to_account_info(), to: self.vault.to_account_info(), authority: self.provider.to_account_info() }), amount, self.collateral_mint.decimals)?;
+
+        let config_seeds = &[Config::SEED, &[self.config.bump]];
+        let signer_seeds = &[&config_seeds[..]];
+        mint_to(CpiContext::new_with_signer(self.token_program.to_account_info(), MintTo { mint: self.yes_mint.to_account_info(), to: self.provider_yes.to_account_info(), authority: self.config.to_account_info() }, signer_seeds), yes_out)?;
+        mint_to(CpiContext::new_with_signer(self.token_program.to_account_info(), MintTo { mint: self.no_mint.to_account_info(), to: self.provider_no.to_account_info(), authority: self.config.to_account_info() }, signer_seeds), no_out)?;
+
+        self.market.reserves += amount;
+        self.market.yes_supply += yes_out;
+        self.market.no_supply += no_out;
+        self.protocol_stats.record_deposit(amount);
+
+        emit!(LiquidityAdded { market_id: self.market.id, provider: self.provider.key(), collateral_in: amount, yes_out, no_out, shares_out });
+        Ok(shares_out)
+    }
+
+    pub fn remove_liquidity(&mut self, shares: u64, min_collateral_out: u64) -> Result<u64> {
+        self.require_open()?;
+        require!(!self.config.enforce_slippage || min_collateral_out > 0, TradeError::SlippageProtectionRequired);
+
+        let (yes_burned, no_burned, collateral_out) = liquidity_burn_amounts(self.market.reserves, self.market.yes_supply, self.market.no_supply, shares)?;
+        require!(collateral_out >= min_collateral_out, TradeError::SlippageExceeded);
+
+        burn(CpiContext::new(self.token_program.to_account_info(), Burn { mint: self.yes_mint.to_account_info(), from: self.provider_yes.to_account_info(), authority: self.provider.to_account_info() }), yes_burned)?;
+        burn(CpiContext::new(self.token_program.to_account_info(), Burn { mint: self.no_mint.to_account_info(), from: self.provider_no.to_account_info(), authority: self.provider.to_account_info() }), no_burned)?;
+
+        let config_key = self.config.key();
+        let market_id_bytes = self.market.id.to_le_bytes();
+        let market_seeds = &[Market::SEED, config_key.as_ref(), &market_id_bytes, &[self.market.bump]];
+        let market_signer = &[&market_seeds[..]];
+        transfer_checked(CpiContext::new_with_signer(self.token_program.to_account_info(), TransferChecked { from: self.vault.to_account_info(), mint: self.collateral_mint.to_account_info(), to: self.provider_collateral.to_account_info(), authority: self.market.to_account_info() }, market_signer), collateral_out, self.collateral_mint.decimals)?;
+
+        self.market.reserves -= collateral_out;
+        self.market.yes_supply -= yes_burned;
+        self.market.no_supply -= no_burned;
+        self.protocol_stats.record_withdrawal(collateral_out);
+
+        emit!(LiquidityRemoved { market_id: self.market.id, provider: self.provider.key(), shares_in: shares, yes_burned, no_burned, collateral_out });
+        Ok(collateral_out)
+    }
+
+    fn require_open(&self) -> Result<()> {
+        require!(!self.config.paused, TradeError::ProtocolPaused);
+        require!(!self.market.has_ended(now!(self)?), TradeError::MarketEnded);
+        Ok(())
+    }
+}
+
+/// Outcome tokens minted for `amount` of new collateral: (yes_out, no_out, shares)
+///
+/// Rounds down, so the pool never ends up under-collateralized.
+pub fn liquidity_mint_amounts(reserves: u64, yes_supply: u64, no_supply: u64, amount: u64) -> Result<(u64, u64, u64)> {
+    require!(amount > 0, LiquidityError::ZeroAmount);
+    require!(reserves > 0 && yes_supply > 0 && no_supply > 0, LiquidityError::EmptyPool);
+
+    let scale = |supply: u64| (supply as u128 * amount as u128 / reserves as u128) as u64;
+    let (yes_out, no_out) = (scale(yes_supply), scale(no_supply));
+    let shares = yes_out.min(no_out);
+    require!(shares > 0, LiquidityError::ZeroAmount);
+    Ok((yes_out, no_out, shares))
+}
+
+/// Outcome tokens burned and collateral released for `shares`: (yes_burned, no_burned, collateral_out)
+///
+/// Burns round up and the payout rounds down, in the pool's favour.
+pub fn liquidity_burn_amounts(reserves: u64, yes_supply: u64, no_supply: u64, shares: u64) -> Result<(u64, u64, u64)> {
+    require!(shares > 0, LiquidityError::ZeroAmount);
+    let scarce = yes_supply.min(no_supply);
+    require!(scarce > 0, LiquidityError::EmptyPool);
+    require!(shares <= scarce, LiquidityError::InsufficientShares);
+
+    let burn = |supply: u64| (supply as u128 * shares as u128).div_ceil(scarce as u128) as u64;
+    let (yes_burned, no_burned) = (burn(yes_supply), burn(no_supply));
+    let collateral_out = (reserves as u128 * shares as u128 / scarce as u128) as u64;
+    require!(yes_burned <= yes_supply && no_burned <= no_supply, LiquidityError::InsufficientShares);
+    Ok((yes_burned, no_burned, collateral_out))
+}
+
+#[error_code(offset = 7300)]
+pub enum LiquidityError {
+    #[msg("Liquidity amount must be greater than zero")]
+    ZeroAmount,
+    #[msg("Pool has no liquidity to scale")]
+    EmptyPool,
+    #[msg("Not enough pool shares")]
+    InsufficientShares,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_then_remove_round_trips() {
+        let (reserves, yes, no) = (10_000_000u64, 9_000_000u64, 4_000_000u64);
+        let (yes_out, no_out, shares) = liquidity_mint_amounts(reserves, yes, no, 1_000_000).unwrap();
+        assert_eq!((yes_out, no_out, shares), (900_000, 400_000, 400_000));
+
+        let (yes_burned, no_burned, collateral_out) = liquidity_burn_amounts(reserves, yes, no, shares).unwrap();
+        assert_eq!((yes_burned, no_burned, collateral_out), (yes_out, no_out, 1_000_000));
+    }
+
+    #[test]
+    fn test_remove_cannot_exceed_pool() {
+        assert!(liquidity_burn_amounts(100, 50, 80, 51).is_err());
+        assert!(liquidity_mint_amounts(0, 0, 0, 100).is_err());
+    }
+}
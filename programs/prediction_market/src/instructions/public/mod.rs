@@ -1,3 +1,17 @@
 pub mod standard_amm;
+pub mod emergency;
+pub mod curve;
+pub mod simulate;
+pub mod rollover;
+pub mod liquidity;
+pub mod pairs;
+pub mod price_reference;
 
 pub use standard_amm::*;
+pub use emergency::*;
+pub use curve::*;
+pub use simulate::*;
+pub use rollover::*;
+pub use liquidity::*;
+pub use pairs::*;
+pub use price_reference::*;
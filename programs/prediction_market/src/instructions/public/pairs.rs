@@ -0,0 +1,216 @@
+//! Complete Sets (Mint / Burn Pairs)
+//!
+//! Holding one YES and one NO is a hedged position: exactly one side wins.
+//! `mint_pair` splits collateral into equal YES + NO, and `burn_pair` merges
+//! them back, so a hedged trader can exit without selling each side through
+//! the curve separately.
+//!
+//! Both sides move by the same amount, and the collateral is whatever keeps
+//! R = √(YES² + NO²), so the pool stays on the invariant either way.
+//! `mint_complete_set` is the collateral-denominated form of `mint_pair`:
+//! it spends a fixed deposit and mints as many pairs as it covers.
+//! Flipping from one side to the other is `swap_outcome` in `standard_amm`
+//! rather than a pair operation, so it pays the trading fee and runs the
+//! same guards as `buy_tokens`.
+//!
+//! The rate is not fixed: a pair costs more the more balanced the pool is,
+//! so a trade landing first can shift it. Every entry point takes a bound
+//! on the other side of the exchange, and `config.enforce_slippage` makes
+//! the minimum-output ones non-zero like any other trade.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{burn, mint_to, transfer_checked, Burn, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::amm::PythagoreanCurve;
+use crate::instructions::public::TradeError;
+use crate::instructions::clock::now;
+use crate::state::{Config, Market, MarketStatus, ProtocolStats};
+#[cfg(feature = "test-clock")]
+use crate::state::TestClock;
+
+#[event]
+pub struct PairMinted {
+    pub market_id: u64,
+    pub owner: Pubkey,
+    pub pairs: u64,
+    pub collateral_in: u64,
+}
+
+#[event]
+pub struct PairBurned {
+    pub market_id: u64,
+    pub owner: Pubkey,
+    pub pairs: u64,
+    pub collateral_out: u64,
+}
+
+#[derive(Accounts)]
+pub struct TradePair<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(seeds = [Config::SEED], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(mut, constraint = market.status == MarketStatus::Active @ TradeError::MarketNotActive)]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(mut, constraint = yes_mint.key() == market.yes_mint)]
+    pub yes_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut, constraint = no_mint.key() == market.no_mint)]
+    pub no_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(constraint = collateral_mint.key() == market.collateral_mint)]
+    pub collateral_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut, associated_token::mint = collateral_mint, associated_token::authority = owner)]
+    pub owner_collateral: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, associated_token::mint = yes_mint, associated_token::authority = owner)]
+    pub owner_yes: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, associated_token::mint = no_mint, associated_token::authority = owner)]
+    pub owner_no: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, associated_token::mint = collateral_mint, associated_token::authority = market)]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, seeds = [ProtocolStats::SEED], bump = protocol_stats.bump)]
+    pub protocol_stats: Box<Account<'info, ProtocolStats>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// Time override (test-clock builds only, see `instructions::clock`)
+    #[cfg(feature = "test-clock")]
+    #[account(seeds = [TestClock::SEED], bump = test_clock.bump)]
+    pub test_clock: Option<Box<Account<'info, TestClock>>>,
+}
+
+impl<'info> TradePair<'info> {
+    /// Deposit collateral for `pairs` YES + NO, paying at most `max_collateral_in`
+    pub fn mint_pair(&mut self, pairs: u64, max_collateral_in: u64) -> Result<u64> {
+        self.require_open()?;
+
+        let collateral_in = PythagoreanCurve::get_pair_cost(self.market.reserves, self.market.yes_supply, self.market.no_supply, pairs)?;
+        require!(collateral_in <= max_collateral_in, TradeError::SlippageExceeded);
+
+        self.deposit_for_pairs(pairs, collateral_in)?;
+        Ok(collateral_in)
+    }
+
+    fn deposit_for_pairs(&mut self, pairs: u64, collateral_in: u64) -> Result<()> {
+        PythagoreanCurve::check_supply_cap(self.market.yes_supply.max(self.market.no_supply), pairs)?;
+        transfer_checked(CpiContext::new(self.token_program.to_account_info(), TransferChecked { from: self.owner_collateral.to_account_info(), mint: self.collateral_mint.to_account_info(), to: self.vault.to_account_info(), authority: self.owner.to_account_info() }), collateral_in, self.collateral_mint.decimals)?;
+
+        let config_seeds = &[Config::SEED, &[self.config.bump]];
+        let signer_seeds = &[&config_seeds[..]];
+        mint_to(CpiContext::new_with_signer(self.token_program.to_account_info(), MintTo { mint: self.yes_mint.to_account_info(), to: self.owner_yes.to_account_info(), authority: self.config.to_account_info() }, signer_seeds), pairs)?;
+        mint_to(CpiContext::new_with_signer(self.token_program.to_account_info(), MintTo { mint: self.no_mint.to_account_info(), to: self.owner_no.to_account_info(), authority: self.config.to_account_info() }, signer_seeds), pairs)?;
+
+        self.market.reserves += collateral_in;
+        self.market.yes_supply += pairs;
+        self.market.no_supply += pairs;
+        self.protocol_stats.record_deposit(collateral_in);
+
+        emit!(PairMinted { market_id: self.market.id, owner: self.owner.key(), pairs, collateral_in });
+        Ok(())
+    }
+
+    /// Deposit exactly `collateral_in` for as many YES + NO pairs as it buys
+    pub fn mint_complete_set(&mut self, collateral_in: u64, min_pairs_out: u64) -> Result<u64> {
+        self.require_open()?;
+        require!(!self.config.enforce_slippage || min_pairs_out > 0, TradeError::SlippageProtectionRequired);
+
+        let pairs = complete_set_pairs(&self.market, collateral_in, min_pairs_out)?;
+
+        self.deposit_for_pairs(pairs, collateral_in)?;
+        Ok(pairs)
+    }
+
+    /// Burn `pairs` YES + NO for collateral, receiving at least `min_collateral_out`
+    pub fn burn_pair(&mut self, pairs: u64, min_collateral_out: u64) -> Result<u64> {
+        self.require_open()?;
+        require!(!self.config.enforce_slippage || min_collateral_out > 0, TradeError::SlippageProtectionRequired);
+
+        let collateral_out = pair_release(&self.market, pairs, min_collateral_out)?;
+
+        burn(CpiContext::new(self.token_program.to_account_info(), Burn { mint: self.yes_mint.to_account_info(), from: self.owner_yes.to_account_info(), authority: self.owner.to_account_info() }), pairs)?;
+        burn(CpiContext::new(self.token_program.to_account_info(), Burn { mint: self.no_mint.to_account_info(), from: self.owner_no.to_account_info(), authority: self.owner.to_account_info() }), pairs)?;
+
+        let config_key = self.config.key();
+        let market_id_bytes = self.market.id.to_le_bytes();
+        let market_seeds = &[Market::SEED, config_key.as_ref(), &market_id_bytes, &[self.market.bump]];
+        let market_signer = &[&market_seeds[..]];
+        transfer_checked(CpiContext::new_with_signer(self.token_program.to_account_info(), TransferChecked { from: self.vault.to_account_info(), mint: self.collateral_mint.to_account_info(), to: self.owner_collateral.to_account_info(), authority: self.market.to_account_info() }, market_signer), collateral_out, self.collateral_mint.decimals)?;
+
+        self.market.reserves -= collateral_out;
+        self.market.yes_supply -= pairs;
+        self.market.no_supply -= pairs;
+        self.protocol_stats.record_withdrawal(collateral_out);
+
+        emit!(PairBurned { market_id: self.market.id, owner: self.owner.key(), pairs, collateral_out });
+        Ok(collateral_out)
+    }
+
+    fn require_open(&self) -> Result<()> {
+        require!(!self.config.paused, TradeError::ProtocolPaused);
+        // Pair collateral on an unfunded market would block `fund_market` and be overwritten by crowdfunding
+        require!(self.market.funded, TradeError::MarketNotFunded);
+        require!(!self.market.has_ended(now!(self)?), TradeError::MarketEnded);
+        Ok(())
+    }
+}
+
+/// Pairs `collateral_in` mints at the current supplies, failing below `min_pairs_out`
+fn complete_set_pairs(market: &Market, collateral_in: u64, min_pairs_out: u64) -> Result<u64> {
+    let pairs = PythagoreanCurve::get_pairs_for_collateral(market.reserves, market.yes_supply, market.no_supply, collateral_in)?;
+    require!(pairs >= min_pairs_out, TradeError::SlippageExceeded);
+    Ok(pairs)
+}
+
+/// Collateral released for burning `pairs`, failing below `min_collateral_out`
+fn pair_release(market: &Market, pairs: u64, min_collateral_out: u64) -> Result<u64> {
+    let collateral_out = PythagoreanCurve::get_pair_release(market.reserves, market.yes_supply, market.no_supply, pairs)?;
+    require!(collateral_out >= min_collateral_out, TradeError::SlippageExceeded);
+    Ok(collateral_out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::market::tests::market as base_market;
+    use crate::state::SettlementMode;
+
+    fn market(yes_supply: u64, no_supply: u64) -> Market {
+        let mut m = base_market(0, SettlementMode::ProRata);
+        m.yes_supply = yes_supply;
+        m.no_supply = no_supply;
+        m.reserves = m.invariant_reserves();
+        m
+    }
+
+    #[test]
+    fn test_complete_set_guard_trips_when_rate_degrades() {
+        // Quoted against a skewed pool, where pairs are cheap
+        let skewed = market(9_000_000, 1_000_000);
+        let quoted = complete_set_pairs(&skewed, 100_000, 0).unwrap();
+        assert!(complete_set_pairs(&skewed, 100_000, quoted).is_ok());
+
+        // A trade landing first rebalances the pool, so the same deposit buys fewer pairs
+        let rebalanced = market(9_000_000, 9_000_000);
+        assert!(complete_set_pairs(&rebalanced, 100_000, 0).unwrap() < quoted);
+        assert!(complete_set_pairs(&rebalanced, 100_000, quoted).is_err());
+    }
+
+    #[test]
+    fn test_pair_release_guard_trips_when_rate_degrades() {
+        let balanced = market(5_000_000, 5_000_000);
+        let quoted = pair_release(&balanced, 100_000, 0).unwrap();
+        assert!(pair_release(&balanced, 100_000, quoted).is_ok());
+
+        // Skewing the pool first shrinks what a pair releases
+        let skewed = market(9_000_000, 5_000_000);
+        assert!(pair_release(&skewed, 100_000, 0).unwrap() < quoted);
+        assert!(pair_release(&skewed, 100_000, quoted).is_err());
+    }
+}
@@ -0,0 +1,77 @@
+//! Reference Price Publishing
+//!
+//! Lets any integration publish the prices its users are shown for a
+//! market. A trader who passes that account to `buy_tokens` or
+//! `sell_tokens` only gets filled near those prices, so a thin pool
+//! manipulated between quote and execution cannot fill them off-market.
+//!
+//! The account is keyed by publisher, so traders choose whose reference
+//! they trust; nobody can overwrite another publisher's prices.
+
+use anchor_lang::prelude::*;
+
+use crate::instructions::clock::now;
+use crate::state::{Market, PriceReference};
+#[cfg(feature = "test-clock")]
+use crate::state::TestClock;
+
+#[event]
+pub struct PriceReferencePublished {
+    pub market: Pubkey,
+    pub publisher: Pubkey,
+    pub yes_price_bps: u64,
+    pub no_price_bps: u64,
+    pub updated_at: i64,
+}
+
+#[derive(Accounts)]
+pub struct PublishPriceReference<'info> {
+    #[account(mut)]
+    pub publisher: Signer<'info>,
+
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(
+        init_if_needed,
+        payer = publisher,
+        space = 8 + PriceReference::INIT_SPACE,
+        seeds = [PriceReference::SEED, market.key().as_ref(), publisher.key().as_ref()],
+        bump,
+    )]
+    pub price_reference: Box<Account<'info, PriceReference>>,
+
+    /// Time override (test-clock builds only, see `instructions::clock`)
+    #[cfg(feature = "test-clock")]
+    #[account(seeds = [TestClock::SEED], bump = test_clock.bump)]
+    pub test_clock: Option<Box<Account<'info, TestClock>>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> PublishPriceReference<'info> {
+    pub fn publish_price_reference(&mut self, yes_price_bps: u64, no_price_bps: u64, bump: u8) -> Result<()> {
+        require!(
+            (1..=10_000).contains(&yes_price_bps) && (1..=10_000).contains(&no_price_bps),
+            PriceReferenceError::InvalidPrice
+        );
+
+        let now = now!(self)?;
+        self.price_reference.set_inner(PriceReference {
+            market: self.market.key(),
+            publisher: self.publisher.key(),
+            yes_price_bps,
+            no_price_bps,
+            updated_at: now,
+            bump,
+        });
+
+        emit!(PriceReferencePublished { market: self.market.key(), publisher: self.publisher.key(), yes_price_bps, no_price_bps, updated_at: now });
+        Ok(())
+    }
+}
+
+#[error_code(offset = 7600)]
+pub enum PriceReferenceError {
+    #[msg("Reference prices must be between 1 and 10000 bps")]
+    InvalidPrice,
+}
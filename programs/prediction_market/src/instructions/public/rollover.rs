@@ -0,0 +1,191 @@
+//! Redeem & Rollover
+//!
+//! Redeems a resolved position and immediately buys into another market
+//! with the proceeds, in one instruction. The collateral makes the same
+//! round trip as a separate `redeem` + `buy_tokens` (vault -> trader -> vault),
+//! and the buy runs through the same `PublicBuy` as `buy_tokens`, so fees,
+//! guards, events and stats are identical to doing it by hand.
+
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{burn, transfer_checked, Burn, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::amm::AmmError;
+use crate::instructions::compliance::check_recipient;
+use crate::instructions::public::{check_exit_window_closed, PositionRedeemed, PublicBuy, RedeemError, TradeError};
+use crate::instructions::clock::now;
+use crate::state::{Config, Market, MarketStatus, PriceReference, ProtocolStats, TraderRecord};
+#[cfg(feature = "test-clock")]
+use crate::state::TestClock;
+
+#[derive(Accounts)]
+pub struct RedeemAndRollover<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(mut, seeds = [Config::SEED], bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(mut, constraint = redeem_market.status == MarketStatus::Resolved @ RedeemError::NotResolved)]
+    pub redeem_market: Box<Account<'info, Market>>,
+
+    #[account(mut, constraint = redeem_yes_mint.key() == redeem_market.yes_mint)]
+    pub redeem_yes_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut, constraint = redeem_no_mint.key() == redeem_market.no_mint)]
+    pub redeem_no_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut, associated_token::mint = redeem_yes_mint, associated_token::authority = user)]
+    pub user_redeem_yes: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, associated_token::mint = redeem_no_mint, associated_token::authority = user)]
+    pub user_redeem_no: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, associated_token::mint = collateral_mint, associated_token::authority = redeem_market)]
+    pub redeem_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = target_market.status == MarketStatus::Active @ TradeError::MarketNotActive,
+        constraint = target_market.collateral_mint == redeem_market.collateral_mint @ RolloverError::CollateralMismatch,
+    )]
+    pub target_market: Box<Account<'info, Market>>,
+
+    #[account(mut, constraint = target_yes_mint.key() == target_market.yes_mint)]
+    pub target_yes_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut, constraint = target_no_mint.key() == target_market.no_mint)]
+    pub target_no_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = target_yes_mint,
+        associated_token::authority = user,
+    )]
+    pub user_target_yes: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = target_no_mint,
+        associated_token::authority = user,
+    )]
+    pub user_target_no: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, associated_token::mint = collateral_mint, associated_token::authority = target_market)]
+    pub target_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Protocol fee vault for the collateral, which receives the buy fee
+    #[account(mut, address = Config::fee_vault_for(&config.key(), &collateral_mint.key(), &token_program.key()) @ TradeError::InvalidFeeVault)]
+    pub fee_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(constraint = collateral_mint.key() == redeem_market.collateral_mint @ RolloverError::CollateralMismatch)]
+    pub collateral_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = collateral_mint,
+        associated_token::authority = user,
+        constraint = !user_collateral.is_frozen() @ RedeemError::RecipientAccountFrozen,
+    )]
+    pub user_collateral: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, seeds = [ProtocolStats::SEED], bump = protocol_stats.bump)]
+    pub protocol_stats: Box<Account<'info, ProtocolStats>>,
+
+    /// The user's cooldown record on `target_market`, shared with `buy_tokens`
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + TraderRecord::INIT_SPACE,
+        seeds = [TraderRecord::SEED, target_market.key().as_ref(), user.key().as_ref()],
+        bump,
+    )]
+    pub trader_record: Box<Account<'info, TraderRecord>>,
+
+    /// Opt-in reference price for the buy on `target_market`
+    #[account(constraint = price_reference.market == target_market.key() @ TradeError::PriceReferenceMismatch)]
+    pub price_reference: Option<Box<Account<'info, PriceReference>>>,
+
+    /// CHECK: Must match `config.recipient_allowlist` when one is set
+    pub allowlist_program: Option<UncheckedAccount<'info>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+
+    /// Time override (test-clock builds only, see `instructions::clock`)
+    #[cfg(feature = "test-clock")]
+    #[account(seeds = [TestClock::SEED], bump = test_clock.bump)]
+    pub test_clock: Option<Box<Account<'info, TestClock>>>,
+}
+
+impl<'info> RedeemAndRollover<'info> {
+    /// Redeem the winning side of `redeem_market`, then buy `buy_yes` on `target_market`
+    pub fn redeem_and_rollover(&mut self, buy_yes: bool, min_tokens_out: u64, record_bump: u8) -> Result<u64> {
+        let now = now!(self)?;
+        require!(!self.target_market.has_ended(now), TradeError::MarketEnded);
+        require!(!self.config.paused, TradeError::ProtocolPaused);
+        require!(!self.config.enforce_slippage || min_tokens_out > 0, TradeError::SlippageProtectionRequired);
+        check_recipient(&self.config, self.allowlist_program.as_ref(), &self.user.to_account_info())?;
+
+        let payout = self.redeem(now)?;
+        PublicBuy {
+            trader: &self.user,
+            config: &mut self.config,
+            market: &mut self.target_market,
+            yes_mint: &self.target_yes_mint,
+            no_mint: &self.target_no_mint,
+            collateral_mint: &self.collateral_mint,
+            trader_collateral: &self.user_collateral,
+            trader_yes: &self.user_target_yes,
+            trader_no: &self.user_target_no,
+            vault: &self.target_vault,
+            fee_vault: &self.fee_vault,
+            protocol_stats: &mut self.protocol_stats,
+            trader_record: &mut self.trader_record,
+            price_reference: self.price_reference.as_deref(),
+            token_program: &self.token_program,
+        }
+        .buy(now, record_bump, payout, buy_yes, min_tokens_out)
+    }
+
+    fn redeem(&mut self, now: i64) -> Result<u64> {
+        let market = &self.redeem_market;
+        check_exit_window_closed(market, &self.config, now)?;
+        let side = market.winning_side().ok_or(RedeemError::NotResolved)?;
+        let (user_balance, winning_mint, user_account) = side.pick(
+            (self.user_redeem_yes.amount, &self.redeem_yes_mint, &self.user_redeem_yes),
+            (self.user_redeem_no.amount, &self.redeem_no_mint, &self.user_redeem_no),
+        );
+        let total_supply = market.supply(side);
+        require!(user_balance > 0, RedeemError::NoWinningTokens);
+
+        let payout = market.winning_payout(user_balance, total_supply).ok_or(AmmError::Overflow)?;
+
+        burn(CpiContext::new(self.token_program.to_account_info(), Burn { mint: winning_mint.to_account_info(), from: user_account.to_account_info(), authority: self.user.to_account_info() }), user_balance)?;
+
+        let config_key = self.config.key();
+        let market_id_bytes = market.id.to_le_bytes();
+        let market_seeds = &[Market::SEED, config_key.as_ref(), &market_id_bytes, &[market.bump]];
+        let market_signer = &[&market_seeds[..]];
+
+        transfer_checked(CpiContext::new_with_signer(self.token_program.to_account_info(), TransferChecked { from: self.redeem_vault.to_account_info(), mint: self.collateral_mint.to_account_info(), to: self.user_collateral.to_account_info(), authority: market.to_account_info() }, market_signer), payout, self.collateral_mint.decimals)?;
+
+        let market = &mut self.redeem_market;
+        market.reserves -= payout;
+        *market.supply_mut(side) -= user_balance;
+        self.protocol_stats.record_withdrawal(payout);
+        emit!(PositionRedeemed { market_id: market.id, redeemer: self.user.key(), tokens_burned: user_balance, collateral_received: payout, settlement_mode: market.settlement_mode, event_seq: self.config.next_event_seq() });
+        Ok(payout)
+    }
+}
+
+#[error_code(offset = 7500)]
+pub enum RolloverError {
+    #[msg("Both markets must use the same collateral mint")]
+    CollateralMismatch,
+}
@@ -0,0 +1,41 @@
+//! Resolution Simulation
+//!
+//! Read-only view answering "if YES wins I get X, if NO wins I get Y" for a
+//! given holding, using the market's current reserves, supplies and
+//! settlement mode. Call via simulation to read the result as return data.
+
+use anchor_lang::prelude::*;
+
+use crate::amm::AmmError;
+use crate::state::Market;
+
+/// Redemption collateral under each possible outcome
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ResolutionPayouts {
+    pub if_yes: u64,
+    pub if_no: u64,
+}
+
+#[derive(Accounts)]
+pub struct SimulateResolution<'info> {
+    pub market: Account<'info, Market>,
+}
+
+impl<'info> SimulateResolution<'info> {
+    pub fn simulate_resolution(&self, user_yes: u64, user_no: u64) -> Result<ResolutionPayouts> {
+        let market = &self.market;
+        require!(user_yes <= market.yes_supply && user_no <= market.no_supply, AmmError::InsufficientTokens);
+
+        let payout = |tokens: u64, total_supply: u64| -> Result<u64> {
+            if tokens == 0 {
+                return Ok(0);
+            }
+            Ok(market.winning_payout(tokens, total_supply).ok_or(AmmError::Overflow)?)
+        };
+
+        Ok(ResolutionPayouts {
+            if_yes: payout(user_yes, market.yes_supply)?,
+            if_no: payout(user_no, market.no_supply)?,
+        })
+    }
+}
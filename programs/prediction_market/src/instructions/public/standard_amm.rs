@@ -2,15 +2,23 @@
 //!
 //! This file contains the logic for public, non-private trading
 //! and standard winning token redemptions.
+//!
+//! Trading fees are paid straight into the protocol fee vault for the
+//! market's collateral (`Config::fee_vault_for`, created with the market's vaults). `market.reserves` and the market vault only ever hold
+//! collateral backing outstanding tokens, √(YES² + NO²) up to rounding.
 
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token_interface::{Mint, MintTo, TokenAccount, TokenInterface, TransferChecked, Burn, mint_to, transfer_checked, burn},
+    token_interface::{Mint, MintTo, TokenAccount, TokenInterface, TransferChecked, Burn, CloseAccount, mint_to, transfer_checked, burn, close_account},
 };
 
-use crate::amm::PythagoreanCurve;
-use crate::state::{Config, Market, MarketStatus, Outcome};
+use crate::instructions::clock::now;
+use crate::instructions::compliance::check_recipient;
+use crate::amm::{AmmError, PythagoreanCurve};
+use crate::state::{Config, FeeRounding, Market, MarketStatus, PriceReference, ProtocolStats, SettlementMode, TraderRecord};
+#[cfg(feature = "test-clock")]
+use crate::state::TestClock;
 
 // =============================================================================
 // PUBLIC TRADING (AMM)
@@ -23,6 +31,7 @@ pub struct TokensBought {
     pub is_yes: bool,
     pub collateral_in: u64,
     pub tokens_out: u64,
+    pub event_seq: u64,
 }
 
 #[event]
@@ -32,6 +41,18 @@ pub struct TokensSold {
     pub is_yes: bool,
     pub tokens_in: u64,
     pub collateral_out: u64,
+    pub event_seq: u64,
+}
+
+#[event]
+pub struct OutcomeSwapped {
+    pub market_id: u64,
+    pub trader: Pubkey,
+    pub from_yes: bool,
+    pub tokens_in: u64,
+    pub tokens_out: u64,
+    pub fee: u64,
+    pub event_seq: u64,
 }
 
 #[derive(Accounts)]
@@ -68,10 +89,13 @@ pub struct Trade<'info> {
     #[account(mut)]
     pub trader: Signer<'info>,
 
-    #[account(seeds = [Config::SEED], bump = config.bump)]
+    #[account(mut, seeds = [Config::SEED], bump = config.bump)]
     pub config: Account<'info, Config>,
 
-    #[account(mut, constraint = market.status == MarketStatus::Active @ TradeError::MarketNotActive)]
+    #[account(
+        mut,
+        constraint = market.status == MarketStatus::Active || market.status == MarketStatus::Resolved @ TradeError::MarketNotActive,
+    )]
     pub market: Account<'info, Market>,
 
     #[account(mut, constraint = yes_mint.key() == market.yes_mint)]
@@ -85,59 +109,108 @@ pub struct Trade<'info> {
     #[account(mut, associated_token::mint = collateral_mint, associated_token::authority = trader)]
     pub trader_collateral: InterfaceAccount<'info, TokenAccount>,
 
-    #[account(mut, associated_token::mint = yes_mint, associated_token::authority = trader)]
-    pub trader_yes: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = trader,
+        associated_token::mint = yes_mint,
+        associated_token::authority = trader,
+    )]
+    pub trader_yes: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    #[account(mut, associated_token::mint = no_mint, associated_token::authority = trader)]
-    pub trader_no: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = trader,
+        associated_token::mint = no_mint,
+        associated_token::authority = trader,
+    )]
+    pub trader_no: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(mut, associated_token::mint = collateral_mint, associated_token::authority = market)]
     pub vault: InterfaceAccount<'info, TokenAccount>,
 
-    pub token_program: Interface<'info, TokenInterface>,
-}
-
-impl<'info> Trade<'info> {
-    pub fn buy_tokens(&mut self, amount: u64, buy_yes: bool, min_tokens_out: u64) -> Result<u64> {
-        let clock = Clock::get()?;
-        require!(clock.unix_timestamp < self.market.end_time as i64, TradeError::MarketEnded);
-        require!(!self.config.paused, TradeError::ProtocolPaused);
-
-        let fee = amount.checked_mul(self.config.protocol_fee_bps).unwrap().checked_div(10000).unwrap();
-        let amount_after_fee = amount.checked_sub(fee).unwrap();
-
-        let (target_supply, other_supply) = if buy_yes { (self.market.yes_supply, self.market.no_supply) } else { (self.market.no_supply, self.market.yes_supply) };
-        let tokens_out = PythagoreanCurve::get_tokens_to_mint(self.market.reserves, target_supply, other_supply, amount_after_fee)?;
+    /// Protocol fee vault for the market's collateral, which receives trading fees
+    #[account(mut, address = Config::fee_vault_for(&config.key(), &collateral_mint.key(), &token_program.key()) @ TradeError::InvalidFeeVault)]
+    pub fee_vault: Box<InterfaceAccount<'info, TokenAccount>>,
 
-        require!(tokens_out >= min_tokens_out, TradeError::SlippageExceeded);
+    #[account(mut, seeds = [ProtocolStats::SEED], bump = protocol_stats.bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
 
-        transfer_checked(CpiContext::new(self.token_program.to_account_info(), TransferChecked { from: self.trader_collateral.to_account_info(), mint: self.collateral_mint.to_account_info(), to: self.vault.to_account_info(), authority: self.trader.to_account_info() }), amount_after_fee, self.collateral_mint.decimals)?;
+    #[account(
+        init_if_needed,
+        payer = trader,
+        space = 8 + TraderRecord::INIT_SPACE,
+        seeds = [TraderRecord::SEED, market.key().as_ref(), trader.key().as_ref()],
+        bump,
+    )]
+    pub trader_record: Box<Account<'info, TraderRecord>>,
 
-        let config_seeds = &[Config::SEED, &[self.config.bump]];
-        let signer_seeds = &[&config_seeds[..]];
-        let (mint, destination) = if buy_yes { (&self.yes_mint, &self.trader_yes) } else { (&self.no_mint, &self.trader_no) };
+    /// Opt-in reference price the fill must land near (see `Config::within_reference_band`)
+    #[account(constraint = price_reference.market == market.key() @ TradeError::PriceReferenceMismatch)]
+    pub price_reference: Option<Box<Account<'info, PriceReference>>>,
 
-        mint_to(CpiContext::new_with_signer(self.token_program.to_account_info(), MintTo { mint: mint.to_account_info(), to: destination.to_account_info(), authority: self.config.to_account_info() }, signer_seeds), tokens_out)?;
+    /// Time override (test-clock builds only, see `instructions::clock`)
+    #[cfg(feature = "test-clock")]
+    #[account(seeds = [TestClock::SEED], bump = test_clock.bump)]
+    pub test_clock: Option<Box<Account<'info, TestClock>>>,
 
-        self.market.reserves += amount_after_fee;
-        if buy_yes { self.market.yes_supply += tokens_out; } else { self.market.no_supply += tokens_out; }
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
 
-        emit!(TokensBought { market_id: self.market.id, buyer: self.trader.key(), is_yes: buy_yes, collateral_in: amount, tokens_out });
-        Ok(tokens_out)
+impl<'info> Trade<'info> {
+    pub fn buy_tokens(&mut self, amount: u64, buy_yes: bool, min_tokens_out: u64, record_bump: u8) -> Result<u64> {
+        let now = now!(self)?;
+        require!(self.market.status == MarketStatus::Active, TradeError::MarketNotActive);
+        // The status stays `Active` until `end_market`; a flip here would roll back with the error
+        require!(!self.market.has_ended(now), TradeError::MarketEnded);
+        require!(!self.config.paused, TradeError::ProtocolPaused);
+        require!(!self.config.enforce_slippage || min_tokens_out > 0, TradeError::SlippageProtectionRequired);
+        PublicBuy {
+            trader: &self.trader,
+            config: &mut self.config,
+            market: &mut self.market,
+            yes_mint: &self.yes_mint,
+            no_mint: &self.no_mint,
+            collateral_mint: &self.collateral_mint,
+            trader_collateral: &self.trader_collateral,
+            trader_yes: &self.trader_yes,
+            trader_no: &self.trader_no,
+            vault: &self.vault,
+            fee_vault: &self.fee_vault,
+            protocol_stats: &mut self.protocol_stats,
+            trader_record: &mut self.trader_record,
+            price_reference: self.price_reference.as_deref(),
+            token_program: &self.token_program,
+        }
+        .buy(now, record_bump, amount, buy_yes, min_tokens_out)
     }
 
-    pub fn sell_tokens(&mut self, amount: u64, sell_yes: bool, min_collateral_out: u64) -> Result<u64> {
-        let clock = Clock::get()?;
-        require!(clock.unix_timestamp < self.market.end_time as i64, TradeError::MarketEnded);
+    pub fn sell_tokens(&mut self, amount: u64, sell_yes: bool, min_collateral_out: u64, record_bump: u8) -> Result<u64> {
+        let now = now!(self)?;
+        let resolved = self.market.status == MarketStatus::Resolved;
+        if resolved {
+            self.check_post_resolution_sell(now, sell_yes)?;
+        } else {
+            require!(!self.market.has_ended(now), TradeError::MarketEnded);
+        }
         require!(!self.config.paused, TradeError::ProtocolPaused);
+        require!(!self.config.enforce_slippage || min_collateral_out > 0, TradeError::SlippageProtectionRequired);
+        touch_trader_record(&mut self.trader_record, &self.config, self.trader.key(), self.market.key(), now, record_bump)?;
 
         let (target_supply, other_supply) = if sell_yes { (self.market.yes_supply, self.market.no_supply) } else { (self.market.no_supply, self.market.yes_supply) };
-        let collateral_out = PythagoreanCurve::get_reserve_to_release(self.market.reserves, target_supply, other_supply, amount)?;
+        let mut collateral_out = PythagoreanCurve::get_reserve_to_release(self.market.reserves, target_supply, other_supply, amount)?;
+        if resolved {
+            collateral_out = collateral_out.min(self.market.loser_exit_cap());
+        }
+
+        require!(collateral_out >= self.market.min_trade(self.config.min_trade_amount), TradeError::BelowMinimumTrade);
 
-        let fee = collateral_out.checked_mul(self.config.protocol_fee_bps).unwrap().checked_div(10000).unwrap();
-        let collateral_after_fee = collateral_out.checked_sub(fee).unwrap();
+        let fee_bps = self.config.trader_fee_bps(self.config.protocol_fee_bps, self.is_creator());
+        let (fee, collateral_after_fee) = split_fee(collateral_out, fee_bps, self.config.fee_rounding)?;
 
         require!(collateral_after_fee >= min_collateral_out, TradeError::SlippageExceeded);
+        check_reference_price(&self.config, self.price_reference.as_deref(), sell_yes, collateral_after_fee, amount)?;
 
         let (mint, source) = if sell_yes { (&self.yes_mint, &self.trader_yes) } else { (&self.no_mint, &self.trader_no) };
         burn(CpiContext::new(self.token_program.to_account_info(), Burn { mint: mint.to_account_info(), from: source.to_account_info(), authority: self.trader.to_account_info() }), amount)?;
@@ -148,13 +221,201 @@ impl<'info> Trade<'info> {
         let market_signer = &[&market_seeds[..]];
 
         transfer_checked(CpiContext::new_with_signer(self.token_program.to_account_info(), TransferChecked { from: self.vault.to_account_info(), mint: self.collateral_mint.to_account_info(), to: self.trader_collateral.to_account_info(), authority: self.market.to_account_info() }, market_signer), collateral_after_fee, self.collateral_mint.decimals)?;
+        // The fee leaves the vault too, so reserves drop by the full release
+        if fee > 0 {
+            transfer_checked(CpiContext::new_with_signer(self.token_program.to_account_info(), TransferChecked { from: self.vault.to_account_info(), mint: self.collateral_mint.to_account_info(), to: self.fee_vault.to_account_info(), authority: self.market.to_account_info() }, market_signer), fee, self.collateral_mint.decimals)?;
+        }
 
         self.market.reserves -= collateral_out;
+        self.market.record_volume(collateral_out);
+        self.protocol_stats.record_trade(collateral_out, fee);
+        self.protocol_stats.record_withdrawal(collateral_out);
         if sell_yes { self.market.yes_supply -= amount; } else { self.market.no_supply -= amount; }
+        // Binds FixedUnit only: ProRata owes no fixed payout, see `settlement_obligation`
+        require!(self.market.is_collateralized(), TradeError::UndercollateralizedOperation);
 
-        emit!(TokensSold { market_id: self.market.id, seller: self.trader.key(), is_yes: sell_yes, tokens_in: amount, collateral_out: collateral_after_fee });
+        emit!(TokensSold { market_id: self.market.id, seller: self.trader.key(), is_yes: sell_yes, tokens_in: amount, collateral_out: collateral_after_fee, event_seq: self.config.next_event_seq() });
         Ok(collateral_after_fee)
     }
+
+    /// Burn `amount` of one side for the other side's tokens, receiving at least `min_tokens_out`
+    ///
+    /// Priced as a sell followed by a buy with its proceeds, charging only
+    /// the sell's fee (see `swap_quote`). Every other guard of a trade
+    /// applies: cooldown, minimum size, reference band and supply cap.
+    pub fn swap_outcome(&mut self, from_yes: bool, amount: u64, min_tokens_out: u64, record_bump: u8) -> Result<u64> {
+        let now = now!(self)?;
+        require!(self.market.status == MarketStatus::Active, TradeError::MarketNotActive);
+        require!(!self.market.has_ended(now), TradeError::MarketEnded);
+        require!(!self.config.paused, TradeError::ProtocolPaused);
+        require!(!self.config.enforce_slippage || min_tokens_out > 0, TradeError::SlippageProtectionRequired);
+        touch_trader_record(&mut self.trader_record, &self.config, self.trader.key(), self.market.key(), now, record_bump)?;
+
+        let fee_bps = self.config.trader_fee_bps(self.config.protocol_fee_bps, self.is_creator());
+        let (value, fee, tokens_out) = swap_quote(&self.market, from_yes, amount, fee_bps, self.config.fee_rounding)?;
+
+        require!(value >= self.market.min_trade(self.config.min_trade_amount), TradeError::BelowMinimumTrade);
+        require!(tokens_out >= min_tokens_out, TradeError::SlippageExceeded);
+        check_reference_price(&self.config, self.price_reference.as_deref(), !from_yes, value, tokens_out)?;
+        let to_supply = if from_yes { self.market.no_supply } else { self.market.yes_supply };
+        PythagoreanCurve::check_supply_cap(to_supply, tokens_out)?;
+
+        let (from_mint, source, to_mint, destination) = if from_yes {
+            (&self.yes_mint, &self.trader_yes, &self.no_mint, &self.trader_no)
+        } else {
+            (&self.no_mint, &self.trader_no, &self.yes_mint, &self.trader_yes)
+        };
+        burn(CpiContext::new(self.token_program.to_account_info(), Burn { mint: from_mint.to_account_info(), from: source.to_account_info(), authority: self.trader.to_account_info() }), amount)?;
+
+        let config_seeds = &[Config::SEED, &[self.config.bump]];
+        let config_signer = &[&config_seeds[..]];
+        mint_to(CpiContext::new_with_signer(self.token_program.to_account_info(), MintTo { mint: to_mint.to_account_info(), to: destination.to_account_info(), authority: self.config.to_account_info() }, config_signer), tokens_out)?;
+
+        // The fee is the only collateral that moves; the rest of the sell's value stays in reserves
+        if fee > 0 {
+            let config_key = self.config.key();
+            let market_id_bytes = self.market.id.to_le_bytes();
+            let market_seeds = &[Market::SEED, config_key.as_ref(), &market_id_bytes, &[self.market.bump]];
+            let market_signer = &[&market_seeds[..]];
+            transfer_checked(CpiContext::new_with_signer(self.token_program.to_account_info(), TransferChecked { from: self.vault.to_account_info(), mint: self.collateral_mint.to_account_info(), to: self.fee_vault.to_account_info(), authority: self.market.to_account_info() }, market_signer), fee, self.collateral_mint.decimals)?;
+        }
+
+        self.market.reserves -= fee;
+        self.market.record_volume(value);
+        self.protocol_stats.record_trade(value, fee);
+        self.protocol_stats.record_withdrawal(fee);
+        if from_yes {
+            self.market.yes_supply -= amount;
+            self.market.no_supply += tokens_out;
+        } else {
+            self.market.no_supply -= amount;
+            self.market.yes_supply += tokens_out;
+        }
+        // Only FixedUnit can trip this, a swap can grow the larger side past reserves
+        require!(self.market.is_collateralized(), TradeError::UndercollateralizedOperation);
+
+        emit!(OutcomeSwapped { market_id: self.market.id, trader: self.trader.key(), from_yes, tokens_in: amount, tokens_out, fee, event_seq: self.config.next_event_seq() });
+        Ok(tokens_out)
+    }
+
+    /// Allow a sell on a resolved market only for losing tokens inside the exit window
+    ///
+    /// Only FixedUnit markets open the window (see `Market::exit_window_open`).
+    /// Losers sell along the same curve, which releases at most R − W, and
+    /// `loser_exit_cap` absorbs its rounding, so they only ever take the
+    /// surplus above the winners' one unit per token.
+    fn check_post_resolution_sell(&self, now: i64, sell_yes: bool) -> Result<()> {
+        require!(
+            self.market.exit_window_open(now, self.config.post_resolution_trade_window),
+            TradeError::ExitWindowClosed
+        );
+        let winner = self.market.winning_side().ok_or(TradeError::NotLosingSide)?;
+        require!(winner.pick(!sell_yes, sell_yes), TradeError::NotLosingSide);
+        Ok(())
+    }
+
+    /// Whether the trader created this market (eligible for the creator fee discount)
+    fn is_creator(&self) -> bool {
+        self.trader.key() == self.market.creator
+    }
+}
+
+/// Accounts a buy works on, shared by `buy_tokens` and `redeem_and_rollover`
+///
+/// Every guard past the entry checks lives here (cooldown, minimum trade,
+/// creator discount, reference band), so neither path can skip one.
+pub(crate) struct PublicBuy<'a, 'info> {
+    pub trader: &'a Signer<'info>,
+    pub config: &'a mut Account<'info, Config>,
+    pub market: &'a mut Account<'info, Market>,
+    pub yes_mint: &'a InterfaceAccount<'info, Mint>,
+    pub no_mint: &'a InterfaceAccount<'info, Mint>,
+    pub collateral_mint: &'a InterfaceAccount<'info, Mint>,
+    pub trader_collateral: &'a InterfaceAccount<'info, TokenAccount>,
+    pub trader_yes: &'a InterfaceAccount<'info, TokenAccount>,
+    pub trader_no: &'a InterfaceAccount<'info, TokenAccount>,
+    pub vault: &'a InterfaceAccount<'info, TokenAccount>,
+    pub fee_vault: &'a InterfaceAccount<'info, TokenAccount>,
+    pub protocol_stats: &'a mut Account<'info, ProtocolStats>,
+    pub trader_record: &'a mut Account<'info, TraderRecord>,
+    pub price_reference: Option<&'a Account<'info, PriceReference>>,
+    pub token_program: &'a Interface<'info, TokenInterface>,
+}
+
+impl<'a, 'info> PublicBuy<'a, 'info> {
+    /// Spend `amount` collateral (fee included) on `buy_yes` tokens
+    pub(crate) fn buy(self, now: i64, record_bump: u8, amount: u64, buy_yes: bool, min_tokens_out: u64) -> Result<u64> {
+        touch_trader_record(self.trader_record, self.config, self.trader.key(), self.market.key(), now, record_bump)?;
+
+        let (target_supply, other_supply) = if buy_yes { (self.market.yes_supply, self.market.no_supply) } else { (self.market.no_supply, self.market.yes_supply) };
+
+        let is_creator = self.trader.key() == self.market.creator;
+        let fee_bps = self.config.trader_fee_bps(self.config.buy_fee_bps(target_supply, other_supply), is_creator);
+        let (fee, amount_after_fee) = split_fee(amount, fee_bps, self.config.fee_rounding)?;
+
+        require!(amount >= self.market.min_trade(self.config.min_trade_amount), TradeError::BelowMinimumTrade);
+
+        let tokens_out = PythagoreanCurve::get_tokens_to_mint(self.market.reserves, target_supply, other_supply, amount_after_fee)?;
+
+        require!(tokens_out >= min_tokens_out, TradeError::SlippageExceeded);
+        check_reference_price(self.config, self.price_reference, buy_yes, amount, tokens_out)?;
+        PythagoreanCurve::check_supply_cap(target_supply, tokens_out)?;
+
+        transfer_checked(CpiContext::new(self.token_program.to_account_info(), TransferChecked { from: self.trader_collateral.to_account_info(), mint: self.collateral_mint.to_account_info(), to: self.vault.to_account_info(), authority: self.trader.to_account_info() }), amount_after_fee, self.collateral_mint.decimals)?;
+        if fee > 0 {
+            transfer_checked(CpiContext::new(self.token_program.to_account_info(), TransferChecked { from: self.trader_collateral.to_account_info(), mint: self.collateral_mint.to_account_info(), to: self.fee_vault.to_account_info(), authority: self.trader.to_account_info() }), fee, self.collateral_mint.decimals)?;
+        }
+
+        let config_seeds = &[Config::SEED, &[self.config.bump]];
+        let signer_seeds = &[&config_seeds[..]];
+        let (mint, destination) = if buy_yes { (self.yes_mint, self.trader_yes) } else { (self.no_mint, self.trader_no) };
+
+        mint_to(CpiContext::new_with_signer(self.token_program.to_account_info(), MintTo { mint: mint.to_account_info(), to: destination.to_account_info(), authority: self.config.to_account_info() }, signer_seeds), tokens_out)?;
+
+        self.market.reserves += amount_after_fee;
+        self.market.record_volume(amount);
+        self.protocol_stats.record_trade(amount, fee);
+        self.protocol_stats.record_deposit(amount_after_fee);
+        if buy_yes { self.market.yes_supply += tokens_out; } else { self.market.no_supply += tokens_out; }
+
+        emit!(TokensBought { market_id: self.market.id, buyer: self.trader.key(), is_yes: buy_yes, collateral_in: amount, tokens_out, event_seq: self.config.next_event_seq() });
+        Ok(tokens_out)
+    }
+}
+
+/// Enforce the trade cooldown and stamp this trade
+fn touch_trader_record(record: &mut TraderRecord, config: &Config, trader: Pubkey, market: Pubkey, now: i64, bump: u8) -> Result<()> {
+    require!(record.cooldown_elapsed(now, config.trade_cooldown_seconds), TradeError::TradeCooldown);
+    if record.trader == Pubkey::default() {
+        record.trader = trader;
+        record.market = market;
+        record.bump = bump;
+    }
+    record.last_trade_ts = now;
+    Ok(())
+}
+
+/// Price a swap of `amount` tokens into the other side: `(value, fee, tokens_out)`
+///
+/// `value` is the collateral a sell of `amount` would release. The fee is
+/// taken on it once and leaves the pool; the rest stays in reserves and
+/// buys the other side fee-free along the same curve. A sell then buy pays
+/// the sell fee, then a buy fee on what is left, and loses the buy's
+/// `PRECISION_SCALE` truncation, so the swap saves roughly one fee.
+fn swap_quote(market: &Market, from_yes: bool, amount: u64, fee_bps: u64, rounding: FeeRounding) -> Result<(u64, u64, u64)> {
+    let (from_supply, to_supply) = if from_yes { (market.yes_supply, market.no_supply) } else { (market.no_supply, market.yes_supply) };
+    let value = PythagoreanCurve::get_reserve_to_release(market.reserves, from_supply, to_supply, amount)?;
+    let (fee, _) = split_fee(value, fee_bps, rounding)?;
+    let tokens_out = PythagoreanCurve::get_swap_output(market.reserves - fee, from_supply, to_supply, amount)?;
+    Ok((value, fee, tokens_out))
+}
+
+/// Reject a fill that strays too far from the trader's reference price, if one was passed
+fn check_reference_price(config: &Config, reference: Option<&Account<PriceReference>>, is_yes: bool, collateral: u64, tokens: u64) -> Result<()> {
+    if let Some(reference) = reference {
+        require!(config.within_reference_band(collateral, tokens, reference.price_bps(is_yes)), TradeError::PriceOffReference);
+    }
+    Ok(())
 }
 
 // =============================================================================
@@ -167,6 +428,8 @@ pub struct PositionRedeemed {
     pub redeemer: Pubkey,
     pub tokens_burned: u64,
     pub collateral_received: u64,
+    pub settlement_mode: SettlementMode,
+    pub event_seq: u64,
 }
 
 #[derive(Accounts)]
@@ -174,10 +437,13 @@ pub struct Redeem<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
 
-    #[account(seeds = [Config::SEED], bump = config.bump)]
+    #[account(mut, seeds = [Config::SEED], bump = config.bump)]
     pub config: Account<'info, Config>,
 
-    #[account(mut, constraint = market.status == MarketStatus::Resolved @ RedeemError::NotResolved)]
+    #[account(
+        mut,
+        constraint = market.status == MarketStatus::Resolved || market.status == MarketStatus::Cancelled @ RedeemError::NotResolved,
+    )]
     pub market: Account<'info, Market>,
 
     #[account(mut, constraint = yes_mint.key() == market.yes_mint)]
@@ -194,26 +460,47 @@ pub struct Redeem<'info> {
     #[account(mut, associated_token::mint = no_mint, associated_token::authority = user)]
     pub user_no: InterfaceAccount<'info, TokenAccount>,
 
-    #[account(mut, associated_token::mint = collateral_mint, associated_token::authority = user)]
+    #[account(
+        mut,
+        associated_token::mint = collateral_mint,
+        associated_token::authority = user,
+        constraint = !user_collateral.is_frozen() @ RedeemError::RecipientAccountFrozen,
+    )]
     pub user_collateral: InterfaceAccount<'info, TokenAccount>,
 
     #[account(mut, associated_token::mint = collateral_mint, associated_token::authority = market)]
     pub vault: InterfaceAccount<'info, TokenAccount>,
 
+    #[account(mut, seeds = [ProtocolStats::SEED], bump = protocol_stats.bump)]
+    pub protocol_stats: Account<'info, ProtocolStats>,
+
+    /// CHECK: Must match `config.recipient_allowlist` when one is set
+    pub allowlist_program: Option<UncheckedAccount<'info>>,
+
     pub token_program: Interface<'info, TokenInterface>,
+
+    /// Time override (test-clock builds only, see `instructions::clock`)
+    #[cfg(feature = "test-clock")]
+    #[account(seeds = [TestClock::SEED], bump = test_clock.bump)]
+    pub test_clock: Option<Box<Account<'info, TestClock>>>,
 }
 
 impl<'info> Redeem<'info> {
     pub fn redeem(&mut self) -> Result<u64> {
-        let (user_balance, total_supply, winning_mint, user_account) = match self.market.outcome {
-            Outcome::Yes => (self.user_yes.amount, self.market.yes_supply, &self.yes_mint, &self.user_yes),
-            Outcome::No => (self.user_no.amount, self.market.no_supply, &self.no_mint, &self.user_no),
-            Outcome::Undetermined => return err!(RedeemError::NotResolved),
-        };
+        check_recipient(&self.config, self.allowlist_program.as_ref(), &self.user.to_account_info())?;
+        if self.market.status == MarketStatus::Cancelled {
+            return self.refund_cancelled();
+        }
+
+        check_exit_window_closed(&self.market, &self.config, now!(self)?)?;
+        let side = self.market.winning_side().ok_or(RedeemError::NotResolved)?;
+        let (user_balance, winning_mint, user_account) =
+            side.pick((self.user_yes.amount, &self.yes_mint, &self.user_yes), (self.user_no.amount, &self.no_mint, &self.user_no));
+        let total_supply = self.market.supply(side);
 
         require!(user_balance > 0, RedeemError::NoWinningTokens);
 
-        let collateral_to_receive = (user_balance as u128).checked_mul(self.market.reserves as u128).unwrap().checked_div(total_supply as u128).unwrap() as u64;
+        let collateral_to_receive = self.market.winning_payout(user_balance, total_supply).ok_or(AmmError::Overflow)?;
 
         burn(CpiContext::new(self.token_program.to_account_info(), Burn { mint: winning_mint.to_account_info(), from: user_account.to_account_info(), authority: self.user.to_account_info() }), user_balance)?;
 
@@ -224,17 +511,121 @@ impl<'info> Redeem<'info> {
 
         transfer_checked(CpiContext::new_with_signer(self.token_program.to_account_info(), TransferChecked { from: self.vault.to_account_info(), mint: self.collateral_mint.to_account_info(), to: self.user_collateral.to_account_info(), authority: self.market.to_account_info() }, market_signer), collateral_to_receive, self.collateral_mint.decimals)?;
 
+        // Shrink the winning supply too, so later redeemers keep their full pro-rata share
         self.market.reserves -= collateral_to_receive;
-        emit!(PositionRedeemed { market_id: self.market.id, redeemer: self.user.key(), tokens_burned: user_balance, collateral_received: collateral_to_receive });
+        *self.market.supply_mut(side) -= user_balance;
+        self.protocol_stats.record_withdrawal(collateral_to_receive);
+        emit!(PositionRedeemed { market_id: self.market.id, redeemer: self.user.key(), tokens_burned: user_balance, collateral_received: collateral_to_receive, settlement_mode: self.market.settlement_mode, event_seq: self.config.next_event_seq() });
         Ok(collateral_to_receive)
     }
+
+    /// Cancelled market: burn both sides for an equal per-token share of reserves
+    fn refund_cancelled(&mut self) -> Result<u64> {
+        let yes_burned = self.user_yes.amount;
+        let no_burned = self.user_no.amount;
+        let user_tokens = yes_burned.checked_add(no_burned).ok_or(AmmError::Overflow)?;
+        require!(user_tokens > 0, RedeemError::NoWinningTokens);
+
+        let refund = self.market.outcome_neutral_payout(user_tokens).ok_or(AmmError::Overflow)?;
+
+        for (amount, mint, from) in [
+            (yes_burned, &self.yes_mint, &self.user_yes),
+            (no_burned, &self.no_mint, &self.user_no),
+        ] {
+            if amount > 0 {
+                burn(CpiContext::new(self.token_program.to_account_info(), Burn { mint: mint.to_account_info(), from: from.to_account_info(), authority: self.user.to_account_info() }), amount)?;
+            }
+        }
+
+        let config_key = self.config.key();
+        let market_id_bytes = self.market.id.to_le_bytes();
+        let market_seeds = &[Market::SEED, config_key.as_ref(), &market_id_bytes, &[self.market.bump]];
+        let market_signer = &[&market_seeds[..]];
+
+        if refund > 0 {
+            transfer_checked(CpiContext::new_with_signer(self.token_program.to_account_info(), TransferChecked { from: self.vault.to_account_info(), mint: self.collateral_mint.to_account_info(), to: self.user_collateral.to_account_info(), authority: self.market.to_account_info() }, market_signer), refund, self.collateral_mint.decimals)?;
+        }
+
+        self.market.reserves -= refund;
+        self.market.yes_supply -= yes_burned;
+        self.market.no_supply -= no_burned;
+        self.protocol_stats.record_withdrawal(refund);
+        emit!(PositionRedeemed { market_id: self.market.id, redeemer: self.user.key(), tokens_burned: user_tokens, collateral_received: refund, settlement_mode: self.market.settlement_mode, event_seq: self.config.next_event_seq() });
+        Ok(refund)
+    }
+}
+
+// =============================================================================
+// LOSING TOKEN CLEANUP
+// =============================================================================
+
+#[event]
+pub struct LosingTokensBurned {
+    pub market_id: u64,
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub tokens_burned: u64,
+}
+
+#[derive(Accounts)]
+pub struct BurnLosingTokens<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(mut, constraint = market.status == MarketStatus::Resolved @ RedeemError::NotResolved)]
+    pub market: Box<Account<'info, Market>>,
+
+    #[account(mut, constraint = market.losing_mint() == Some(losing_mint.key()) @ RedeemError::NotLosingSide)]
+    pub losing_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut, associated_token::mint = losing_mint, associated_token::authority = user)]
+    pub user_losing: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> BurnLosingTokens<'info> {
+    /// Burn a worthless losing balance and close the ATA, refunding its rent to the user
+    pub fn burn_losing_tokens(&mut self) -> Result<u64> {
+        let amount = self.user_losing.amount;
+        if amount > 0 {
+            burn(CpiContext::new(self.token_program.to_account_info(), Burn { mint: self.losing_mint.to_account_info(), from: self.user_losing.to_account_info(), authority: self.user.to_account_info() }), amount)?;
+            if let Some(side) = self.market.winning_side() { *self.market.supply_mut(side.opposite()) -= amount; }
+        }
+
+        close_account(CpiContext::new(self.token_program.to_account_info(), CloseAccount { account: self.user_losing.to_account_info(), destination: self.user.to_account_info(), authority: self.user.to_account_info() }))?;
+
+        emit!(LosingTokensBurned { market_id: self.market.id, owner: self.user.key(), mint: self.losing_mint.key(), tokens_burned: amount });
+        Ok(amount)
+    }
+}
+
+/// Hold winning redemptions while losers can still sell back to the curve
+pub fn check_exit_window_closed(market: &Market, config: &Config, now: i64) -> Result<()> {
+    require!(!market.exit_window_open(now, config.post_resolution_trade_window), RedeemError::ExitWindowOpen);
+    Ok(())
+}
+
+/// Split `amount` into `(fee, amount_after_fee)` at `fee_bps`, rounding the fee per `rounding`
+///
+/// A fee that would consume the whole amount fails cleanly instead of
+/// underflowing, whatever the configured rate.
+pub fn split_fee(amount: u64, fee_bps: u64, rounding: FeeRounding) -> Result<(u64, u64)> {
+    let fee = rounding.div_bps(
+        (amount as u128)
+            .checked_mul(fee_bps as u128)
+            .ok_or(AmmError::Overflow)?,
+    );
+    require!(fee == 0 || fee < amount as u128, TradeError::FeeExceedsAmount);
+    let fee = fee as u64;
+    Ok((fee, amount.saturating_sub(fee)))
 }
 
 // =============================================================================
 // ERRORS
 // =============================================================================
 
-#[error_code]
+#[error_code(offset = 7100)]
 pub enum TradeError {
     #[msg("Market is not active")]
     MarketNotActive,
@@ -244,12 +635,206 @@ pub enum TradeError {
     ProtocolPaused,
     #[msg("Slippage tolerance exceeded")]
     SlippageExceeded,
+    #[msg("A non-zero minimum output is required")]
+    SlippageProtectionRequired,
+    #[msg("Fee would consume the entire trade amount")]
+    FeeExceedsAmount,
+    #[msg("Trade cooldown has not elapsed")]
+    TradeCooldown,
+    #[msg("Trade is below the market's minimum size")]
+    BelowMinimumTrade,
+    #[msg("Privacy features are disabled for this market")]
+    PrivacyDisabled,
+    #[msg("Operation would leave reserves below settlement obligations")]
+    UndercollateralizedOperation,
+    #[msg("Reference price belongs to a different market")]
+    PriceReferenceMismatch,
+    #[msg("Execution price is too far from the reference price")]
+    PriceOffReference,
+    #[msg("Fee vault is not the protocol fee vault for this collateral")]
+    InvalidFeeVault,
+    #[msg("Market has not been funded yet")]
+    MarketNotFunded,
+    #[msg("Post-resolution exit window is closed or the market is not FixedUnit")]
+    ExitWindowClosed,
+    #[msg("Only the losing side can be sold after resolution")]
+    NotLosingSide,
 }
 
-#[error_code]
+#[error_code(offset = 7200)]
 pub enum RedeemError {
     #[msg("Market is not resolved")]
     NotResolved,
     #[msg("No winning tokens to redeem")]
     NoWinningTokens,
+    #[msg("Recipient token account is frozen")]
+    RecipientAccountFrozen,
+    #[msg("Mint is not the losing side of a resolved market")]
+    NotLosingSide,
+    #[msg("Losers can still sell, redemption opens when the exit window closes")]
+    ExitWindowOpen,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_fee_boundaries() {
+        let down = FeeRounding::Down;
+        assert_eq!(split_fee(10_000, 0, down).unwrap(), (0, 10_000));
+        assert_eq!(split_fee(10_000, 3000, down).unwrap(), (3000, 7000));
+        assert_eq!(split_fee(10_000, 9999, down).unwrap(), (9999, 1));
+        assert!(split_fee(10_000, 10_000, down).is_err());
+        assert!(split_fee(10_000, u64::MAX, down).is_err());
+        // Dust rounds the fee down to zero rather than failing
+        assert_eq!(split_fee(1, 3000, down).unwrap(), (0, 1));
+        assert_eq!(split_fee(0, 3000, down).unwrap(), (0, 0));
+    }
+
+    #[test]
+    fn test_split_fee_rounding_direction() {
+        // 1_001 at 1% is a fee of 10.01
+        assert_eq!(split_fee(1_001, 100, FeeRounding::Down).unwrap(), (10, 991));
+        assert_eq!(split_fee(1_001, 100, FeeRounding::Up).unwrap(), (11, 990));
+        assert_eq!(split_fee(1_001, 100, FeeRounding::Nearest).unwrap(), (10, 991));
+        // 1_050 at 1% is 10.5, which `Nearest` rounds up
+        assert_eq!(split_fee(1_050, 100, FeeRounding::Nearest).unwrap(), (11, 1_039));
+        // Rounding up can't charge a fee that eats the whole amount
+        assert!(split_fee(1, 3000, FeeRounding::Up).is_err());
+        assert_eq!(split_fee(0, 3000, FeeRounding::Up).unwrap(), (0, 0));
+    }
+
+    #[test]
+    fn test_fees_stay_out_of_reserves() {
+        use crate::instructions::market::create_market::initial_token_amount;
+        use crate::state::market::tests::market;
+
+        let liquidity = 1_000_000_000;
+        let mut m = market(liquidity, SettlementMode::FixedUnit);
+        m.yes_supply = initial_token_amount(liquidity);
+        m.no_supply = m.yes_supply;
+        let (mut vault, mut fee_vault, mut fees_charged) = (liquidity, 0u64, 0u64);
+
+        // (buy?, yes?, collateral in or tokens out)
+        let trades = [(true, true, 50_000_000), (true, false, 120_000_000), (false, true, 30_000_000), (true, true, 7_777_777), (false, false, 60_000_000)];
+        for (is_buy, yes, amount) in trades {
+            let (target, other) = if yes { (m.yes_supply, m.no_supply) } else { (m.no_supply, m.yes_supply) };
+            if is_buy {
+                let (fee, net) = split_fee(amount, 200, FeeRounding::Down).unwrap();
+                let tokens = PythagoreanCurve::get_tokens_to_mint(m.reserves, target, other, net).unwrap();
+                vault += net;
+                fee_vault += fee;
+                fees_charged += fee;
+                m.reserves += net;
+                if yes { m.yes_supply += tokens } else { m.no_supply += tokens }
+            } else {
+                let out = PythagoreanCurve::get_reserve_to_release(m.reserves, target, other, amount).unwrap();
+                let (fee, _) = split_fee(out, 200, FeeRounding::Down).unwrap();
+                vault -= out;
+                fee_vault += fee;
+                fees_charged += fee;
+                m.reserves -= out;
+                if yes { m.yes_supply -= amount } else { m.no_supply -= amount }
+            }
+        }
+
+        assert!(fees_charged > 0);
+        assert_eq!(fee_vault, fees_charged);
+        assert_eq!(vault, m.reserves);
+        // The curve works at a 1_000-unit precision, so allow that much drift per trade
+        let drift = m.reserves.abs_diff(m.invariant_reserves());
+        assert!(drift <= 1_000 * trades.len() as u64, "drift {drift}");
+    }
+
+    #[test]
+    fn test_loser_exits_leave_winners_covered() {
+        use crate::instructions::market::create_market::initial_token_amount;
+        use crate::state::market::tests::market;
+
+        let liquidity = 1_000_000_000;
+        let mut m = market(liquidity, SettlementMode::FixedUnit);
+        m.yes_supply = initial_token_amount(liquidity) + 300_000_000;
+        m.no_supply = initial_token_amount(liquidity);
+        m.reserves = m.invariant_reserves();
+
+        // Resolved YES: NO holders sell out in chunks during the exit window
+        m.resolved_at = 1_000;
+        assert!(m.exit_window_open(1_010, 60));
+        let chunk = m.no_supply / 4;
+        while m.no_supply > 0 {
+            let amount = chunk.min(m.no_supply);
+            let out = PythagoreanCurve::get_reserve_to_release(m.reserves, m.no_supply, m.yes_supply, amount).unwrap().min(m.loser_exit_cap());
+            m.reserves -= out;
+            m.no_supply -= amount;
+            assert!(m.reserves >= m.yes_supply);
+        }
+
+        // Winners are still paid in full, and losers took nearly all the surplus
+        assert_eq!(m.winning_payout(m.yes_supply, m.yes_supply), Some(m.yes_supply));
+        assert!(m.reserves - m.yes_supply <= 1_000 * 4, "surplus left {}", m.reserves - m.yes_supply);
+    }
+
+    /// Tokens a sell of `amount` then a buy with the proceeds would get, at `fee_bps` on both legs
+    fn sell_then_buy(m: &Market, amount: u64, fee_bps: u64) -> u64 {
+        let out = PythagoreanCurve::get_reserve_to_release(m.reserves, m.yes_supply, m.no_supply, amount).unwrap();
+        let (_, proceeds) = split_fee(out, fee_bps, FeeRounding::Down).unwrap();
+        let (_, net) = split_fee(proceeds, fee_bps, FeeRounding::Down).unwrap();
+        PythagoreanCurve::get_tokens_to_mint(m.reserves - out, m.no_supply, m.yes_supply - amount, net).unwrap()
+    }
+
+    #[test]
+    fn test_swap_charges_one_fee() {
+        use crate::instructions::market::create_market::initial_token_amount;
+        use crate::state::market::tests::market;
+
+        let liquidity = 1_000_000_000;
+        let mut m = market(liquidity, SettlementMode::FixedUnit);
+        m.yes_supply = initial_token_amount(liquidity) + 200_000_000;
+        m.no_supply = initial_token_amount(liquidity);
+        m.reserves = m.invariant_reserves();
+        let amount = 100_000_000;
+
+        // Fee-free, the swap matches the two-step route up to the buy's precision truncation
+        let (value, fee, free_out) = swap_quote(&m, true, amount, 0, FeeRounding::Down).unwrap();
+        assert_eq!(fee, 0);
+        assert!(value > 0);
+        let naive = sell_then_buy(&m, amount, 0);
+        assert!(free_out >= naive && free_out - naive <= 5_000, "swap {free_out} vs {naive}");
+
+        // With a fee, the swap pays it once and comes out ahead of sell then buy
+        let (value, fee, tokens_out) = swap_quote(&m, true, amount, 200, FeeRounding::Down).unwrap();
+        assert_eq!(fee, value * 200 / 10_000);
+        assert!(tokens_out < free_out);
+        assert!(tokens_out > sell_then_buy(&m, amount, 200));
+    }
+
+    #[test]
+    fn test_swap_keeps_pool_collateralized() {
+        use crate::instructions::market::create_market::initial_token_amount;
+        use crate::state::market::tests::market;
+
+        let liquidity = 1_000_000_000;
+        let mut m = market(liquidity, SettlementMode::FixedUnit);
+        m.yes_supply = initial_token_amount(liquidity);
+        m.no_supply = m.yes_supply;
+        m.reserves = m.invariant_reserves();
+
+        for (from_yes, amount) in [(true, 50_000_000), (false, 120_000_000), (true, 7_777_777)] {
+            let (_, fee, tokens_out) = swap_quote(&m, from_yes, amount, 200, FeeRounding::Down).unwrap();
+            m.reserves -= fee;
+            if from_yes {
+                m.yes_supply -= amount;
+                m.no_supply += tokens_out;
+            } else {
+                m.no_supply -= amount;
+                m.yes_supply += tokens_out;
+            }
+            assert!(m.is_collateralized());
+            assert!(m.invariant_reserves() <= m.reserves);
+        }
+
+        // Swapping more than the side's supply is rejected
+        assert!(swap_quote(&m, true, m.yes_supply + 1, 200, FeeRounding::Down).is_err());
+    }
 }
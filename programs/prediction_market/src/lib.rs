@@ -11,6 +11,32 @@
 //! - Inco FHE protects the choices and the market odds.
 //! - Light Protocol ZK-Compression protects the user's identity and money.
 //!
+//! ## Error codes
+//!
+//! Each module's error enum owns its own block of 100 codes, so a code
+//! names exactly one variant even where modules share a variant name
+//! (e.g. `ProtocolPaused`, `Unauthorized`, `Overflow`). New enums take
+//! the next free block.
+//!
+//! | Codes | Enum | Codes | Enum |
+//! |-------|------|-------|------|
+//! | 6000 | `InitializeError` | 7600 | `PriceReferenceError` |
+//! | 6100 | `ConfigError` | 7700 | `EmergencyError` |
+//! | 6200 | `CreateMarketError` | 7800 | `AmmError` |
+//! | 6300 | `FundingError` | 7900 | `PrivacyError` |
+//! | 6400 | `ExtendError` | 8000 | `ShieldedError` |
+//! | 6500 | `EndMarketError` | 8100 | `CompressionError` |
+//! | 6600 | `ResolveError` | 8200 | `LookupError` |
+//! | 6700 | `OracleBondError` | 8300 | `DisclosureError` |
+//! | 6800 | `LiquidityLockError` | 8400 | `PrivateOddsError` |
+//! | 6900 | `OwnershipError` | 8500 | `CommitmentIndexError` |
+//! | 7000 | `RescueError` | 8600 | `MergeError` |
+//! | 7100 | `TradeError` | 8700 | `AttestationError` |
+//! | 7200 | `RedeemError` | 8800 | `ComplianceError` |
+//! | 7300 | `LiquidityError` | 8900 | `CpiGuardError` |
+//! | 7400 | `CurveError` | 9000 | `ClockError` |
+//! | 7500 | `RolloverError` | | |
+//!
 
 use anchor_lang::prelude::*;
 
@@ -20,6 +46,7 @@ pub mod state;
 
 pub use amm::*;
 pub use instructions::*;
+pub use state::{FeeRounding, MarketCategory, ProtocolStats, SettlementMode};
 
 // Replace with your deployed program ID
 declare_id!("8NeEkxgPMV5AnZ8o5ksjPhqsHwkWXdvGCGyHmEt6tJTn");
@@ -30,21 +57,41 @@ pub mod private_pnp {
     use super::*;
 
     /// Initialize the protocol with global configuration
+    ///
+    /// `protocol_fee_bps` is in basis points: 100 = 1%, 3000 = 30% (max), 0 is rejected
     pub fn initialize(
         ctx: Context<Initialize>,
-        protocol_fee: u64,
+        protocol_fee_bps: u64,
         oracle: Pubkey,
     ) -> Result<()> {
-        ctx.accounts.initialize(protocol_fee, oracle, &ctx.bumps)
+        ctx.accounts.initialize(protocol_fee_bps, oracle, &ctx.bumps)
+    }
+
+    /// Update protocol configuration (admin only)
+    pub fn update_config(ctx: Context<UpdateConfig>, update: ConfigUpdate) -> Result<()> {
+        ctx.accounts.update_config(update)
+    }
+
+    /// Rotate the resolving oracle (admin only)
+    pub fn set_oracle(ctx: Context<SetOracle>, new_oracle: Pubkey) -> Result<()> {
+        ctx.accounts.set_oracle(new_oracle)
     }
 
     /// Create market state (Step 1)
+    #[allow(clippy::too_many_arguments)]
     pub fn create_market_state(
         ctx: Context<CreateMarketState>,
         question: String,
         end_time: u64,
+        settlement_mode: SettlementMode,
+        category: MarketCategory,
+        tags: Vec<String>,
+        min_trade_amount: u64,
+        allow_privacy: bool,
+        crowdfunded: bool,
+        resolution_source_hash: [u8; 32],
     ) -> Result<()> {
-        ctx.accounts.create_market_state(question, end_time, &ctx.bumps)
+        ctx.accounts.create_market_state(question, end_time, settlement_mode, category, tags, min_trade_amount, allow_privacy, crowdfunded, resolution_source_hash, &ctx.bumps)
     }
 
     /// Create YES/NO token mints (Step 2)
@@ -62,11 +109,49 @@ pub mod private_pnp {
         ctx.accounts.fund_market(initial_liquidity)
     }
 
+    /// Pledge collateral toward an unfunded market's initial liquidity
+    pub fn contribute_funding(ctx: Context<ContributeFunding>, amount: u64) -> Result<()> {
+        ctx.accounts.contribute_funding(amount, ctx.bumps.liquidity_position)
+    }
+
+    /// Take a pledge back before funding is finalized
+    pub fn withdraw_funding(ctx: Context<WithdrawFunding>) -> Result<u64> {
+        ctx.accounts.withdraw_funding()
+    }
 
+    /// Turn the pledges into the market's initial liquidity once they reach the minimum
+    pub fn finalize_funding(ctx: Context<FinalizeFunding>) -> Result<()> {
+        ctx.accounts.finalize_funding()
+    }
+
+    /// Mint a contributor's share of the initial YES/NO after finalization
+    pub fn claim_funding_tokens(ctx: Context<ClaimFundingTokens>) -> Result<u64> {
+        ctx.accounts.claim_funding_tokens()
+    }
 
-    /// Step 1: Open a private position
-    pub fn init_privacy_position(ctx: Context<InitPrivacyPosition>, commitment: [u8; 32]) -> Result<()> {
-        ctx.accounts.init_privacy_position(commitment, ctx.bumps.privacy_position)
+    /// Push back a market's end time before it closes (creator or oracle)
+    pub fn extend_market(ctx: Context<ExtendMarket>, new_end_time: u64) -> Result<()> {
+        ctx.accounts.extend_market(new_end_time)
+    }
+
+    /// Flip a market past its end time to `Ended` (permissionless)
+    pub fn end_market(ctx: Context<EndMarket>) -> Result<()> {
+        ctx.accounts.end_market()
+    }
+
+    /// Mint the creator's locked initial YES/NO once the lock has expired
+    pub fn unlock_creator_liquidity(ctx: Context<UnlockCreatorLiquidity>) -> Result<()> {
+        ctx.accounts.unlock_creator_liquidity()
+    }
+
+    /// Hand a market to a new creator (current creator only)
+    pub fn transfer_market_ownership(ctx: Context<TransferMarketOwnership>, new_creator: Pubkey) -> Result<()> {
+        ctx.accounts.transfer_market_ownership(new_creator)
+    }
+
+    /// Step 1: Open a private position, optionally naming an auditor view key
+    pub fn init_privacy_position(ctx: Context<InitPrivacyPosition>, commitment: [u8; 32], view_key_hash: Option<[u8; 32]>) -> Result<()> {
+        ctx.accounts.init_privacy_position(commitment, view_key_hash, ctx.bumps.privacy_position)
     }
 
     /// Step 2: Buy tokens privately
@@ -79,9 +164,45 @@ pub mod private_pnp {
         ctx.accounts.trade_privacy(commitment, amount, buy_yes)
     }
 
+    /// Create the caller's opt-in commitment index
+    pub fn init_commitment_index(ctx: Context<InitCommitmentIndex>) -> Result<()> {
+        ctx.accounts.init_commitment_index(ctx.bumps.commitment_index)
+    }
+
+    /// Record a commitment in the caller's index (opt-in, links wallet to commitment)
+    pub fn record_commitment(ctx: Context<UpdateCommitmentIndex>, commitment: [u8; 32]) -> Result<()> {
+        ctx.accounts.record_commitment(commitment)
+    }
+
+    /// Remove a commitment from the caller's index
+    pub fn forget_commitment(ctx: Context<UpdateCommitmentIndex>, commitment: [u8; 32]) -> Result<()> {
+        ctx.accounts.forget_commitment(commitment)
+    }
+
+    /// Consolidate two Dark Pool positions into one
+    pub fn merge_privacy_positions(
+        ctx: Context<MergePrivacyPositions>,
+        source_commitment: [u8; 32],
+        dest_commitment: [u8; 32],
+        source_secret: [u8; 32],
+        dest_secret: [u8; 32],
+    ) -> Result<()> {
+        ctx.accounts.merge_privacy_positions(source_commitment, dest_commitment, source_secret, dest_secret)
+    }
+
+    /// Abandon a Dark Pool position: sweep leftovers to the owner and reclaim all rent
+    pub fn force_close_privacy_position(ctx: Context<ForceClosePrivacyPosition>, commitment: [u8; 32], secret: [u8; 32]) -> Result<()> {
+        ctx.accounts.force_close_privacy_position(commitment, secret)
+    }
+
+    /// Reveal a Dark Pool position's holdings to the holder of its view key
+    pub fn disclose_position(ctx: Context<DisclosePosition>, commitment: [u8; 32], view_key: [u8; 32]) -> Result<()> {
+        ctx.accounts.disclose_position(commitment, view_key)
+    }
+
     /// Initialize a privacy payout claim (Step 1 of Dark Pool Exit)
-    pub fn init_privacy_claim(ctx: Context<InitPrivacyClaim>, commitment: [u8; 32]) -> Result<()> {
-        ctx.accounts.init_privacy_claim(commitment, ctx.bumps.privacy_claim)
+    pub fn init_privacy_claim(ctx: Context<InitPrivacyClaim>, commitment: [u8; 32], nonce: u64) -> Result<()> {
+        ctx.accounts.init_privacy_claim(commitment, nonce, ctx.bumps.privacy_claim)
     }
 
     /// Redeem a privacy position (Step 2 of Dark Pool Exit)
@@ -89,8 +210,9 @@ pub mod private_pnp {
         ctx: Context<RedeemPrivacyPosition>,
         position_commitment: [u8; 32],
         payout_commitment: [u8; 32],
+        min_locked: u64,
     ) -> Result<()> {
-        ctx.accounts.redeem_privacy_position(position_commitment, payout_commitment)
+        ctx.accounts.redeem_privacy_position(position_commitment, payout_commitment, min_locked)
     }
 
     /// Initialize trader tokens accounts (Standard AMM)
@@ -108,7 +230,12 @@ pub mod private_pnp {
         ctx.accounts.trade_shielded(commitment, direction_cipher, amount, ctx.bumps.shielded_position)
     }
 
-    /// Reveal direction and redeem payout (post-resolution)
+    /// Reveal a winning shielded position during the reveal window, fixing its recipient
+    pub fn reveal_shielded(ctx: Context<RevealShielded>, secret: [u8; 32], commitment: [u8; 32]) -> Result<()> {
+        ctx.accounts.reveal_shielded(secret, commitment)
+    }
+
+    /// Redeem a shielded position's payout once the reveal window closes
     pub fn reveal_and_redeem(
         ctx: Context<RevealAndRedeem>,
         secret: [u8; 32],
@@ -166,6 +293,17 @@ pub mod private_pnp {
         ctx.accounts.update_encrypted_reserves(encrypted_delta, is_yes)
     }
 
+    /// Reveal an encrypted market's final values with an Inco decryption proof
+    pub fn reveal_encrypted_market(
+        ctx: Context<RevealEncryptedMarket>,
+        reserves: u64,
+        yes_supply: u64,
+        no_supply: u64,
+        signature: [u8; 64],
+    ) -> Result<()> {
+        ctx.accounts.reveal_encrypted_market(reserves, yes_supply, no_supply, signature)
+    }
+
     /// Buy outcome tokens (YES or NO)
     pub fn buy_tokens(
         ctx: Context<Trade>,
@@ -173,7 +311,7 @@ pub mod private_pnp {
         buy_yes: bool,
         min_tokens_out: u64,
     ) -> Result<u64> {
-        ctx.accounts.buy_tokens(amount, buy_yes, min_tokens_out)
+        ctx.accounts.buy_tokens(amount, buy_yes, min_tokens_out, ctx.bumps.trader_record)
     }
 
     /// Sell outcome tokens back to the pool
@@ -184,12 +322,89 @@ pub mod private_pnp {
         min_collateral_out: u64,
     ) -> Result<u64> {
         ctx.accounts
-            .sell_tokens(amount, sell_yes, min_collateral_out)
+            .sell_tokens(amount, sell_yes, min_collateral_out, ctx.bumps.trader_record)
+    }
+
+    /// Flip `amount` tokens to the other side in one step, paying one trading fee instead of two
+    pub fn swap_outcome(ctx: Context<Trade>, from_yes: bool, amount: u64, min_tokens_out: u64) -> Result<u64> {
+        ctx.accounts.swap_outcome(from_yes, amount, min_tokens_out, ctx.bumps.trader_record)
+    }
+
+    /// Publish reference prices that traders may require their fills to match
+    pub fn publish_price_reference(ctx: Context<PublishPriceReference>, yes_price_bps: u64, no_price_bps: u64) -> Result<()> {
+        ctx.accounts.publish_price_reference(yes_price_bps, no_price_bps, ctx.bumps.price_reference)
+    }
+
+    /// Add collateral pro rata, receiving YES + NO tokens at the current prices
+    pub fn add_liquidity(ctx: Context<ManageLiquidity>, amount: u64, min_shares_out: u64) -> Result<u64> {
+        ctx.accounts.add_liquidity(amount, min_shares_out)
+    }
+
+    /// Burn a pro-rata slice of YES + NO tokens for collateral
+    pub fn remove_liquidity(ctx: Context<ManageLiquidity>, shares: u64, min_collateral_out: u64) -> Result<u64> {
+        ctx.accounts.remove_liquidity(shares, min_collateral_out)
+    }
+
+    /// Split collateral into equal YES + NO tokens
+    pub fn mint_pair(ctx: Context<TradePair>, pairs: u64, max_collateral_in: u64) -> Result<u64> {
+        ctx.accounts.mint_pair(pairs, max_collateral_in)
+    }
+
+    /// Spend a fixed amount of collateral on equal YES + NO tokens
+    pub fn mint_complete_set(ctx: Context<TradePair>, collateral_in: u64, min_pairs_out: u64) -> Result<u64> {
+        ctx.accounts.mint_complete_set(collateral_in, min_pairs_out)
+    }
+
+    /// Merge equal YES + NO tokens back into collateral
+    pub fn burn_pair(ctx: Context<TradePair>, pairs: u64, min_collateral_out: u64) -> Result<u64> {
+        ctx.accounts.burn_pair(pairs, min_collateral_out)
+    }
+
+    /// Resolve the market (oracle/AI only), optionally attaching an evidence hash
+    ///
+    /// `resolution_source` must hash to the market's declared source, if it has one.
+    pub fn resolve_market(
+        ctx: Context<ResolveMarket>,
+        yes_wins: bool,
+        evidence_hash: [u8; 32],
+        resolution_source: Vec<u8>,
+    ) -> Result<()> {
+        ctx.accounts.resolve_market(yes_wins, evidence_hash, resolution_source)
+    }
+
+    /// Resolve the market as a draw (oracle only), refunding both sides
+    pub fn resolve_draw(ctx: Context<ResolveMarket>, evidence_hash: [u8; 32], resolution_source: Vec<u8>) -> Result<()> {
+        ctx.accounts.resolve_draw(evidence_hash, resolution_source)
+    }
+
+    /// Resolve from an oracle-signed attestation, submitted by any relayer
+    pub fn resolve_with_attestation(
+        ctx: Context<ResolveWithAttestation>,
+        yes_wins: bool,
+        signature: [u8; 64],
+        message: Vec<u8>,
+    ) -> Result<()> {
+        ctx.accounts.resolve_with_attestation(yes_wins, signature, message)
+    }
+
+    /// Resolve several correlated markets atomically (markets in remaining accounts)
+    pub fn resolve_batch<'info>(ctx: Context<'_, '_, 'info, 'info, ResolveBatch<'info>>, outcomes: Vec<bool>, evidence_hash: [u8; 32]) -> Result<()> {
+        ctx.accounts.resolve_batch(ctx.remaining_accounts, outcomes, evidence_hash)
     }
 
-    /// Resolve the market (oracle/AI only)
-    pub fn resolve_market(ctx: Context<ResolveMarket>, yes_wins: bool) -> Result<()> {
-        ctx.accounts.resolve_market(yes_wins)
+    /// Stake collateral behind the oracle's resolutions
+    pub fn deposit_oracle_bond(ctx: Context<DepositOracleBond>, amount: u64) -> Result<()> {
+        ctx.accounts.deposit_oracle_bond(amount, ctx.bumps.oracle_bond)
+    }
+
+    /// Withdraw oracle stake once no dispute window is open
+    pub fn withdraw_oracle_bond(ctx: Context<WithdrawOracleBond>, amount: u64) -> Result<()> {
+        ctx.accounts.withdraw_oracle_bond(amount)
+    }
+
+    /// Uphold a dispute, slashing the resolving oracle's bond (admin only)
+    pub fn slash_oracle_bond(ctx: Context<SlashOracleBond>) -> Result<u64> {
+        ctx.accounts.slash_oracle_bond()
     }
 
     /// Redeem winning tokens for collateral
@@ -197,13 +412,93 @@ pub mod private_pnp {
         ctx.accounts.redeem()
     }
 
-    /// Step 1: Collect winnings privately
-    pub fn redeem_privacy(ctx: Context<RedeemPrivacy>, commitment: [u8; 32]) -> Result<()> {
-        ctx.accounts.redeem_privacy(commitment)
+    /// Burn a worthless losing balance and close its token account to reclaim rent
+    pub fn burn_losing_tokens(ctx: Context<BurnLosingTokens>) -> Result<u64> {
+        ctx.accounts.burn_losing_tokens()
+    }
+
+    /// Redeem a resolved position and buy into another market with the proceeds
+    pub fn redeem_and_rollover(ctx: Context<RedeemAndRollover>, buy_yes: bool, min_tokens_out: u64) -> Result<u64> {
+        ctx.accounts.redeem_and_rollover(buy_yes, min_tokens_out, ctx.bumps.trader_record)
+    }
+
+    /// Move stray non-collateral tokens out of a market-owned account (admin only)
+    pub fn rescue_tokens(ctx: Context<RescueTokens>, amount: u64) -> Result<()> {
+        ctx.accounts.rescue_tokens(amount)
+    }
+
+    /// Burn outcome tokens for a share of reserves after a prolonged pause
+    pub fn emergency_withdraw(ctx: Context<EmergencyWithdraw>) -> Result<u64> {
+        ctx.accounts.emergency_withdraw()
+    }
+
+    /// Step 1: Collect winnings privately (`standardize = false` locks the exact, publicly visible amount)
+    pub fn redeem_privacy(ctx: Context<RedeemPrivacy>, commitment: [u8; 32], standardize: bool) -> Result<()> {
+        ctx.accounts.redeem_privacy(commitment, standardize)
+    }
+
+    /// Steps 1 + 2 in one transaction: create the claim and collect winnings into it
+    pub fn redeem_privacy_direct(ctx: Context<RedeemPrivacyDirect>, commitment: [u8; 32], nonce: u64, standardize: bool) -> Result<()> {
+        ctx.accounts.redeem_privacy_direct(commitment, nonce, standardize, ctx.bumps.privacy_claim)
+    }
+
+    /// Create the fresh wallet's collateral account from an unrelated payer
+    pub fn prepare_recipient(_ctx: Context<PrepareRecipient>) -> Result<()> {
+        Ok(())
     }
 
     /// Step 2: Withdraw money to a fresh wallet
     pub fn claim_privacy(ctx: Context<ClaimPrivacy>, secret: [u8; 32], _commitment: [u8; 32]) -> Result<()> {
         ctx.accounts.claim(secret)
     }
+
+    /// Pin the time seen by handlers passed the test clock (admin only, test-clock builds)
+    pub fn set_test_clock(ctx: Context<SetTestClock>, unix_timestamp: i64) -> Result<()> {
+        ctx.accounts.set_test_clock(unix_timestamp, ctx.bumps.test_clock)
+    }
+
+    /// Read protocol-wide statistics (view)
+    pub fn get_protocol_stats(ctx: Context<GetProtocolStats>) -> Result<ProtocolStats> {
+        ctx.accounts.get_protocol_stats()
+    }
+
+    /// Pool counters and collateral locked in pending privacy claims (view)
+    pub fn get_market_info(ctx: Context<GetMarketInfo>) -> Result<MarketInfo> {
+        ctx.accounts.get_market_info()
+    }
+
+    /// Check reserves against the curve invariant and the vault balance (view)
+    pub fn assert_market_consistent(ctx: Context<AssertMarketConsistent>, tolerance: u64) -> Result<bool> {
+        ctx.accounts.assert_market_consistent(tolerance)
+    }
+
+    /// Find and summarize the privacy accounts seeded by a commitment (view)
+    pub fn get_position_by_commitment(ctx: Context<GetPositionByCommitment>, commitment: [u8; 32], _claim_nonce: u64) -> Result<u8> {
+        ctx.accounts.get_position_by_commitment(commitment)
+    }
+
+    /// Market and amounts of a shielded position, without its direction (view)
+    pub fn get_shielded_position_public(ctx: Context<GetShieldedPositionPublic>, _commitment: [u8; 32]) -> Result<ShieldedPositionPublic> {
+        ctx.accounts.get_shielded_position_public()
+    }
+
+    /// Payout for a holding if YES wins and if NO wins (view)
+    pub fn simulate_resolution(ctx: Context<SimulateResolution>, user_yes: u64, user_no: u64) -> Result<ResolutionPayouts> {
+        ctx.accounts.simulate_resolution(user_yes, user_no)
+    }
+
+    /// Shares and YES/NO tokens `add_liquidity` would mint for `amount` (view)
+    pub fn quote_add_liquidity(ctx: Context<QuoteAddLiquidity>, amount: u64) -> Result<LiquidityQuote> {
+        ctx.accounts.quote_add_liquidity(amount)
+    }
+
+    /// Collateral needed to push one side to `target_price_bps` (view)
+    pub fn collateral_for_target_price(ctx: Context<CollateralForTargetPrice>, target_price_bps: u64, buy_yes: bool) -> Result<u64> {
+        ctx.accounts.collateral_for_target_price(target_price_bps, buy_yes)
+    }
+
+    /// Sample the buy curve at `steps` points for charting (view)
+    pub fn sample_curve(ctx: Context<SampleCurve>, steps: u8, buy_yes: bool) -> Result<Vec<CurvePoint>> {
+        ctx.accounts.sample_curve(steps, buy_yes)
+    }
 }
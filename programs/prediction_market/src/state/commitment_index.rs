@@ -0,0 +1,78 @@
+//! User Commitment Index
+//!
+//! Opt-in list of the privacy commitments a wallet has created, so the
+//! wallet can enumerate open positions and prompt for the matching secrets.
+//! Recording a commitment links it to the owner on-chain, so privacy-
+//! maximizing users simply never record.
+
+use anchor_lang::prelude::*;
+
+/// One recorded commitment and the market it belongs to
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub struct CommitmentEntry {
+    pub market: Pubkey,
+    pub commitment: [u8; 32],
+}
+
+/// Per-wallet commitment index
+///
+/// Seeds: ["commitment_index", owner]
+#[account]
+#[derive(InitSpace)]
+pub struct UserCommitmentIndex {
+    /// Wallet that owns the index
+    pub owner: Pubkey,
+
+    /// Recorded commitments, oldest first
+    #[max_len(32)]
+    pub entries: Vec<CommitmentEntry>,
+
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl UserCommitmentIndex {
+    pub const SEED: &'static [u8] = b"commitment_index";
+
+    /// Maximum number of recorded commitments
+    pub const MAX_ENTRIES: usize = 32;
+
+    /// Add an entry; false if it is already recorded or the index is full
+    pub fn record(&mut self, entry: CommitmentEntry) -> bool {
+        if self.entries.len() >= Self::MAX_ENTRIES || self.entries.contains(&entry) {
+            return false;
+        }
+        self.entries.push(entry);
+        true
+    }
+
+    /// Remove an entry; false if it was not recorded
+    pub fn forget(&mut self, entry: &CommitmentEntry) -> bool {
+        let len = self.entries.len();
+        self.entries.retain(|e| e != entry);
+        self.entries.len() != len
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(byte: u8) -> CommitmentEntry {
+        CommitmentEntry { market: Pubkey::default(), commitment: [byte; 32] }
+    }
+
+    #[test]
+    fn test_record_and_forget() {
+        let mut index = UserCommitmentIndex { owner: Pubkey::default(), entries: Vec::new(), bump: 0 };
+        assert!(index.record(entry(1)));
+        assert!(!index.record(entry(1)));
+        assert!(index.forget(&entry(1)));
+        assert!(!index.forget(&entry(1)));
+
+        for i in 0..UserCommitmentIndex::MAX_ENTRIES as u8 {
+            assert!(index.record(entry(i)));
+        }
+        assert!(!index.record(entry(255)));
+    }
+}
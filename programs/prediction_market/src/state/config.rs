@@ -3,12 +3,13 @@
 //! This account stores protocol-wide settings that apply to all markets.
 
 use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
 
 /// Global configuration account (singleton PDA)
 ///
 /// Seeds: ["config"]
 #[account]
-#[derive(InitSpace)]
+#[derive(InitSpace, Default)]
 pub struct Config {
     /// Protocol administrator with special privileges
     pub admin: Pubkey,
@@ -23,7 +24,7 @@ pub struct Config {
     /// Collateral token mint (e.g., USDC, SOL wrapped)
     pub collateral_mint: Pubkey,
 
-    /// Protocol fee in basis points (100 = 1%, max 10000 = 100%)
+    /// Protocol fee in basis points (100 = 1%), within `MIN_FEE_BPS..=MAX_FEE_BPS`
     pub protocol_fee_bps: u64,
 
     /// Total markets created (used as incrementing ID)
@@ -37,8 +38,361 @@ pub struct Config {
 
     /// Whether the protocol is paused
     pub paused: bool,
+
+    /// Fee (bps) for buys that move the market toward 50/50
+    pub balancing_fee_bps: u64,
+
+    /// Fee (bps) for buys that push the market further from 50/50
+    pub skewing_fee_bps: u64,
+
+    /// Unix timestamp when the protocol was paused (0 while unpaused)
+    pub paused_at: i64,
+
+    /// Seconds the protocol must stay paused before users may emergency-withdraw
+    pub emergency_withdraw_delay: i64,
+
+    /// Longest allowed market lifetime in seconds (creation to end)
+    pub max_market_duration: u64,
+
+    /// Shortest allowed market lifetime in seconds (0 = any future end time)
+    pub min_market_duration: u64,
+
+    /// Reject trades that pass a zero minimum output (no slippage protection)
+    pub enforce_slippage: bool,
+
+    /// Sequence number stamped into trade/redeem events (last value issued)
+    pub event_seq: u64,
+
+    /// Program screening payout recipients (None = permissionless)
+    pub recipient_allowlist: Option<Pubkey>,
+
+    /// Reject privacy exits invoked via CPI (see `allowed_cpi_caller`)
+    pub cpi_guard_enabled: bool,
+
+    /// Program allowed to drive privacy exits via CPI while the guard is on
+    pub allowed_cpi_caller: Option<Pubkey>,
+
+    /// Collateral paid from the fee vault to whoever resolves a market
+    pub resolver_reward: u64,
+
+    /// Minimum seconds between trades by one trader in one market (0 = off)
+    pub trade_cooldown_seconds: i64,
+
+    /// Default minimum collateral per trade, for markets without their own (0 = none)
+    pub min_trade_amount: u64,
+
+    /// Seconds the creator's initial YES/NO stay locked after funding (0 = no lock)
+    pub creator_liquidity_lock_seconds: i64,
+
+    /// Share (bps) of the trading fee waived on a market creator's own trades (0 = off, 10000 = exempt)
+    pub creator_fee_discount_bps: u64,
+
+    /// Most collateral one dark pool position may take in (0 = no cap)
+    pub max_privacy_position: u64,
+
+    /// Widest gap (bps of the reference) a fill may land from a passed reference price
+    pub reference_price_tolerance_bps: u64,
+
+    /// Bond the oracle must have staked to resolve (0 = no bond required)
+    pub oracle_bond: u64,
+
+    /// Seconds after resolution during which the oracle's bond can be slashed
+    pub dispute_window_seconds: i64,
+
+    /// Most decimals a market's collateral mint may have (outcome mints inherit them)
+    pub max_collateral_decimals: u8,
+
+    /// Which way fractional trading fees round (see `FeeRounding`)
+    pub fee_rounding: FeeRounding,
+
+    /// Protocol fee vault: the config's ATA for `collateral_mint`, created by `initialize`
+    ///
+    /// Markets in another collateral pay into `Config::fee_vault_for` their mint.
+    pub fee_vault: Pubkey,
+
+    /// Seconds after resolution during which losing FixedUnit tokens can still be sold and redemption waits (0 = off)
+    pub post_resolution_trade_window: i64,
+
+    /// Seconds after resolution during which shielded winners reveal, before any shielded payout
+    pub shielded_reveal_window: i64,
 }
 
 impl Config {
-    pub const SEED: &'static [u8] = b"config_v7";
+    pub const SEED: &'static [u8] = b"config_v8";
+
+    /// Generation of the config PDA, matching the suffix of `SEED`
+    ///
+    /// Each generation counts market ids from 0, so ids repeat across
+    /// generations. Off-chain consumers key markets on (config, market_id).
+    pub const VERSION: u8 = 8;
+
+    /// Fee vault for markets settled in `mint`: the config's ATA for it
+    ///
+    /// `create_market_vaults` creates it for any mint that doesn't have one yet.
+    pub fn fee_vault_for(config: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+        get_associated_token_address_with_program_id(config, mint, token_program)
+    }
+
+    /// Minimum protocol fee in basis points (0.01%)
+    ///
+    /// A zero fee is almost always an unset or mis-encoded argument rather
+    /// than a deliberate choice, so `initialize` and `update_config` reject it.
+    pub const MIN_FEE_BPS: u64 = 1;
+
+    /// Maximum fee in basis points (30%)
+    pub const MAX_FEE_BPS: u64 = 3000;
+
+    /// Default emergency-withdraw delay (30 days)
+    pub const DEFAULT_EMERGENCY_WITHDRAW_DELAY: i64 = 30 * 24 * 60 * 60;
+
+    /// Default maximum market lifetime (2 years)
+    pub const DEFAULT_MAX_MARKET_DURATION: u64 = 2 * 365 * 24 * 60 * 60;
+
+    /// Default dispute window (3 days)
+    pub const DEFAULT_DISPUTE_WINDOW: i64 = 3 * 24 * 60 * 60;
+
+    /// Default shielded reveal window (1 day)
+    pub const DEFAULT_SHIELDED_REVEAL_WINDOW: i64 = 24 * 60 * 60;
+
+    /// Default collateral decimals cap
+    ///
+    /// Covers wrapped SOL (9) and USDC/USDT (6). The curve's fixed
+    /// `PRECISION_SCALE` is tuned for amounts in that range; an 18-decimal
+    /// token puts ordinary trade sizes near the supply cap, where the
+    /// scaled math loses precision.
+    pub const DEFAULT_MAX_COLLATERAL_DECIMALS: u8 = 9;
+
+    /// Default reference price tolerance (5%)
+    pub const DEFAULT_REFERENCE_PRICE_TOLERANCE_BPS: u64 = 500;
+
+    /// Set the pause flag, recording when a pause starts
+    ///
+    /// `paused_at` is stamped on the false -> true transition only, so
+    /// re-pausing an already paused protocol doesn't restart the clock.
+    /// Unpausing resets it to 0.
+    pub fn set_paused(&mut self, paused: bool, now: i64) {
+        if paused && !self.paused {
+            self.paused_at = now;
+        } else if !paused {
+            self.paused_at = 0;
+        }
+        self.paused = paused;
+    }
+
+    /// Issue the next event sequence number
+    ///
+    /// Gives indexers a total order over trade/redeem events that doesn't
+    /// depend on slot or transaction index. The first event gets 1.
+    pub fn next_event_seq(&mut self) -> u64 {
+        self.event_seq += 1;
+        self.event_seq
+    }
+
+    /// Whether the pause has lasted long enough to open the escape hatch
+    pub fn emergency_withdraw_open(&self, now: i64) -> bool {
+        self.paused && now.saturating_sub(self.paused_at) >= self.emergency_withdraw_delay
+    }
+
+    /// Fee for buying `target_supply` against `other_supply`
+    ///
+    /// Buying the scarcer side rebalances the pool and pays the balancing fee.
+    /// Buying the larger side (or either side at exact balance) pays the skewing fee.
+    pub fn buy_fee_bps(&self, target_supply: u64, other_supply: u64) -> u64 {
+        if target_supply < other_supply {
+            self.balancing_fee_bps
+        } else {
+            self.skewing_fee_bps
+        }
+    }
+
+    /// Whether a privacy position holding `deposited` may take `amount` more
+    ///
+    /// One outsized position is identifiable by size alone, so large private
+    /// bets have to be spread over several commitments.
+    pub fn privacy_deposit_allowed(&self, deposited: u64, amount: u64) -> bool {
+        self.max_privacy_position == 0
+            || deposited.checked_add(amount).is_some_and(|total| total <= self.max_privacy_position)
+    }
+
+    /// Whether paying/receiving `collateral` for `tokens` lands within tolerance of `reference_bps`
+    ///
+    /// Compares the fill's average price, in the same bps unit as the
+    /// marginal price, so small trades sit right at the pool price.
+    pub fn within_reference_band(&self, collateral: u64, tokens: u64, reference_bps: u64) -> bool {
+        if tokens == 0 {
+            return false;
+        }
+        let execution_bps = collateral as u128 * 10_000 / tokens as u128;
+        execution_bps.abs_diff(reference_bps as u128) * 10_000 <= reference_bps as u128 * self.reference_price_tolerance_bps as u128
+    }
+
+    /// Whether markets may use a collateral mint with `decimals` (0 through `max_collateral_decimals`)
+    pub fn collateral_decimals_supported(&self, decimals: u8) -> bool {
+        decimals <= self.max_collateral_decimals
+    }
+
+    /// Apply the creator discount to `fee_bps` when the trader created the market
+    pub fn trader_fee_bps(&self, fee_bps: u64, is_creator: bool) -> u64 {
+        if !is_creator {
+            return fee_bps;
+        }
+        fee_bps - fee_bps * self.creator_fee_discount_bps.min(10000) / 10000
+    }
+}
+
+/// Rounding applied to `amount * fee_bps / 10000`
+///
+/// Flooring hands the fractional unit of every fee to the trader, which adds
+/// up to lost revenue over many trades. `Up` keeps it for the protocol and
+/// `Nearest` splits the difference (exact halves round up).
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug, Default)]
+pub enum FeeRounding {
+    /// Floor: favors the trader
+    #[default]
+    Down,
+    /// Ceiling: favors the protocol
+    Up,
+    /// Nearest unit, halves up
+    Nearest,
+}
+
+impl FeeRounding {
+    /// Divide a fee numerator (`amount * fee_bps`) by 10000 in this direction
+    pub fn div_bps(self, numerator: u128) -> u128 {
+        match self {
+            FeeRounding::Down => numerator / 10_000,
+            FeeRounding::Up => numerator.div_ceil(10_000),
+            FeeRounding::Nearest => (numerator + 5_000) / 10_000,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_emergency_withdraw_window() {
+        let mut config = Config {
+            emergency_withdraw_delay: 100,
+            ..Default::default()
+        };
+        assert!(!config.emergency_withdraw_open(1_000));
+
+        config.paused = true;
+        config.paused_at = 1_000;
+        assert!(!config.emergency_withdraw_open(1_099));
+        assert!(config.emergency_withdraw_open(1_100));
+    }
+
+    #[test]
+    fn test_reference_band_boundaries() {
+        let config = Config {
+            reference_price_tolerance_bps: 500,
+            ..Default::default()
+        };
+        // Reference 0.6: the band is 0.57 ..= 0.63 collateral per token
+        assert!(config.within_reference_band(6_000, 10_000, 6_000));
+        assert!(config.within_reference_band(5_700, 10_000, 6_000));
+        assert!(config.within_reference_band(6_300, 10_000, 6_000));
+        assert!(!config.within_reference_band(5_699, 10_000, 6_000));
+        assert!(!config.within_reference_band(6_301, 10_000, 6_000));
+        assert!(!config.within_reference_band(6_000, 0, 6_000));
+    }
+
+    #[test]
+    fn test_fee_rounding_at_fractional_boundaries() {
+        // 12_345 * 30 bps = 37.035 units
+        assert_eq!(FeeRounding::Down.div_bps(12_345 * 30), 37);
+        assert_eq!(FeeRounding::Up.div_bps(12_345 * 30), 38);
+        assert_eq!(FeeRounding::Nearest.div_bps(12_345 * 30), 37);
+
+        // Exactly half a unit
+        assert_eq!(FeeRounding::Down.div_bps(5_000), 0);
+        assert_eq!(FeeRounding::Up.div_bps(5_000), 1);
+        assert_eq!(FeeRounding::Nearest.div_bps(5_000), 1);
+        assert_eq!(FeeRounding::Nearest.div_bps(4_999), 0);
+
+        // Whole units never move
+        for rounding in [FeeRounding::Down, FeeRounding::Up, FeeRounding::Nearest] {
+            assert_eq!(rounding.div_bps(30_000), 3);
+            assert_eq!(rounding.div_bps(0), 0);
+        }
+    }
+
+    #[test]
+    fn test_version_matches_seed() {
+        assert_eq!(Config::SEED, format!("config_v{}", Config::VERSION).as_bytes());
+    }
+
+    #[test]
+    fn test_collateral_decimals_bounds() {
+        let config = Config {
+            max_collateral_decimals: Config::DEFAULT_MAX_COLLATERAL_DECIMALS,
+            ..Default::default()
+        };
+        assert!(config.collateral_decimals_supported(0));
+        assert!(config.collateral_decimals_supported(9));
+        assert!(!config.collateral_decimals_supported(10));
+        assert!(!config.collateral_decimals_supported(18));
+    }
+
+    #[test]
+    fn test_set_paused_tracks_transitions() {
+        let mut config = Config::default();
+        config.set_paused(true, 1_000);
+        assert_eq!(config.paused_at, 1_000);
+
+        // Re-pausing keeps the original timestamp
+        config.set_paused(true, 2_000);
+        assert_eq!(config.paused_at, 1_000);
+
+        config.set_paused(false, 3_000);
+        assert!(!config.paused);
+        assert_eq!(config.paused_at, 0);
+    }
+
+    #[test]
+    fn test_event_seq_is_monotonic() {
+        let mut config = Config::default();
+        assert_eq!(config.next_event_seq(), 1);
+        assert_eq!(config.next_event_seq(), 2);
+        assert_eq!(config.event_seq, 2);
+    }
+
+    #[test]
+    fn test_buy_fee_rewards_balancing_side() {
+        let config = Config {
+            balancing_fee_bps: 50,
+            skewing_fee_bps: 150,
+            ..Default::default()
+        };
+        assert_eq!(config.buy_fee_bps(400, 600), 50);
+        assert_eq!(config.buy_fee_bps(600, 400), 150);
+        assert_eq!(config.buy_fee_bps(500, 500), 150);
+    }
+
+    #[test]
+    fn test_privacy_position_cap() {
+        let mut config = Config::default();
+        assert!(config.privacy_deposit_allowed(u64::MAX, 1));
+
+        config.max_privacy_position = 1_000;
+        assert!(config.privacy_deposit_allowed(400, 600));
+        assert!(!config.privacy_deposit_allowed(400, 601));
+        assert!(!config.privacy_deposit_allowed(u64::MAX, 1));
+    }
+
+    #[test]
+    fn test_creator_fee_discount() {
+        let mut config = Config::default();
+        assert_eq!(config.trader_fee_bps(150, true), 150);
+
+        config.creator_fee_discount_bps = 5000;
+        assert_eq!(config.trader_fee_bps(150, true), 75);
+        assert_eq!(config.trader_fee_bps(150, false), 150);
+
+        config.creator_fee_discount_bps = 10000;
+        assert_eq!(config.trader_fee_bps(150, true), 0);
+    }
 }
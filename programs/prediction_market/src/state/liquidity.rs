@@ -0,0 +1,26 @@
+//! Crowdfunded Liquidity
+//!
+//! Records one contributor's pledge toward a market's initial liquidity,
+//! so the YES/NO minted at `finalize_funding` can be split pro rata.
+
+use anchor_lang::prelude::*;
+
+/// A contributor's share of a market's crowdfunded initial liquidity
+///
+/// Seeds: ["liquidity_position", market, provider]
+#[account]
+#[derive(InitSpace)]
+pub struct LiquidityPosition {
+    pub market: Pubkey,
+    pub provider: Pubkey,
+
+    /// Collateral contributed before funding was finalized
+    pub contributed: u64,
+
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl LiquidityPosition {
+    pub const SEED: &'static [u8] = b"liquidity_position";
+}
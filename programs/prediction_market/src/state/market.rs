@@ -4,6 +4,20 @@
 
 use anchor_lang::prelude::*;
 
+use crate::amm::PythagoreanCurve;
+
+/// Maximum length of a market question in bytes
+///
+/// Sizes the account (`#[max_len]`) and bounds `create_market_state`, so the
+/// two can't drift apart.
+pub const MAX_QUESTION_LEN: usize = 256;
+
+/// Maximum number of tags per market
+pub const MAX_TAGS: usize = 5;
+
+/// Maximum length of a single tag in bytes
+pub const MAX_TAG_LEN: usize = 32;
+
 /// Individual prediction market account
 ///
 /// Seeds: ["market", market_id.to_le_bytes()]
@@ -18,7 +32,7 @@ pub struct Market {
 
     /// The prediction question
     /// Example: "Will ETH flip BTC by market cap in 2025?"
-    #[max_len(256)]
+    #[max_len(MAX_QUESTION_LEN)]
     pub question: String,
 
     /// Unix timestamp when trading ends
@@ -55,9 +69,73 @@ pub struct Market {
     /// Market resolution status
     pub status: MarketStatus,
 
+    /// Whether initial liquidity was deposited (Step 4 of creation)
+    pub funded: bool,
+
+    /// Collateral traded in this market (buys and sells, all paths)
+    pub total_volume: u64,
+
+    /// Whether the resolver reward has been paid for this market
+    pub reward_paid: bool,
+
     /// Winning outcome (only valid after resolution)
     pub outcome: Outcome,
 
+    /// Hash of the off-chain evidence backing the resolution (zeros = none given)
+    pub evidence_hash: [u8; 32],
+
+    /// keccak256 of the source the market must resolve from, fixed at creation (zeros = none declared)
+    pub resolution_source_hash: [u8; 32],
+
+    /// How winning tokens are settled at redemption
+    pub settlement_mode: SettlementMode,
+
+    /// Topic used by front-ends for browsing
+    pub category: MarketCategory,
+
+    /// Creator's initial YES and NO (each) still locked, counted in supply but not yet minted
+    pub locked_creator_tokens: u64,
+
+    /// When the creator may unlock early; resolution or cancellation also unlocks
+    pub liquidity_lock_until: i64,
+
+    /// Minimum collateral per trade in this market (0 = use `config.min_trade_amount`)
+    pub min_trade_amount: u64,
+
+    /// Collateral pledged through `contribute_funding` (0 = funded directly by the creator)
+    pub crowdfunded_liquidity: u64,
+
+    /// Pledges whose YES and NO have been minted through `claim_funding_tokens`
+    pub crowdfunded_claimed: u64,
+
+    /// Whether the creator opened initial funding to pledges instead of `fund_market`, fixed at creation
+    pub crowdfunded: bool,
+
+    /// Collateral moved into pending `PrivacyClaim` vaults and not yet claimed
+    pub total_locked_in_claims: u64,
+
+    /// Collateral deposited through `trade_shielded`, the pool shielded winners split
+    pub shielded_deposits: u64,
+
+    /// Shielded deposits revealed on the winning side during the reveal window
+    pub shielded_winning_revealed: u64,
+
+    /// Whether the privacy entry points (dark pool, shielded, confidential, compressed) may be used
+    pub allow_privacy: bool,
+
+    /// Unix timestamp of resolution (0 = not resolved)
+    pub resolved_at: i64,
+
+    /// Oracle that resolved the market, whose bond backs the outcome
+    pub resolved_by: Pubkey,
+
+    /// Whether the resolver's bond was already slashed over this market
+    pub bond_slashed: bool,
+
+    /// Free-form discovery tags (at most `MAX_TAGS`, each up to `MAX_TAG_LEN` bytes)
+    #[max_len(MAX_TAGS, MAX_TAG_LEN)]
+    pub tags: Vec<String>,
+
     /// PDA bump seed
     pub bump: u8,
 }
@@ -80,6 +158,250 @@ impl Market {
         self.reserve_blinding = new_blinding;
         self.shielded_reserve_commitment = Self::compute_reserve_commitment(self.reserves, &new_blinding);
     }
+
+    /// Add a trade's collateral to the market's volume (saturating, never blocks a trade)
+    pub fn record_volume(&mut self, amount: u64) {
+        self.total_volume = self.total_volume.saturating_add(amount);
+    }
+
+    /// Whether the creator's locked liquidity may be released at `now`
+    pub fn liquidity_unlocked(&self, now: i64) -> bool {
+        matches!(self.status, MarketStatus::Resolved | MarketStatus::Cancelled) || now >= self.liquidity_lock_until
+    }
+
+    /// Minimum trade size, preferring the market override over the global default
+    pub fn min_trade(&self, global_min: u64) -> u64 {
+        if self.min_trade_amount > 0 { self.min_trade_amount } else { global_min }
+    }
+
+    /// Whether trading has closed at `now` (a unix timestamp from the clock)
+    ///
+    /// Every end-time check goes through here: `end_time` is a `u64` while
+    /// the clock is an `i64`, and a bare `as i64` would wrap an out-of-range
+    /// end time negative and make a live market look expired or vice versa.
+    pub fn has_ended(&self, now: i64) -> bool {
+        now >= i64::try_from(self.end_time).unwrap_or(i64::MAX)
+    }
+
+    /// Flip an expired `Active` market to `Ended`
+    ///
+    /// Returns true if the status changed, so callers can emit `MarketEnded`.
+    pub fn end_if_expired(&mut self, now: i64) -> bool {
+        if self.status == MarketStatus::Active && self.has_ended(now) {
+            self.status = MarketStatus::Ended;
+            return true;
+        }
+        false
+    }
+
+    /// Split `reserves` into the collateral backing YES and NO exposure
+    ///
+    /// The invariant gives R² = YES² + NO², so R = YES²/R + NO²/R: each side
+    /// is attributed the reserves in proportion to its squared supply (its
+    /// implied probability). The two parts always sum to `reserves`.
+    ///
+    /// A view for `get_market_info` only: trading and settlement still work
+    /// off the pooled `reserves` and never consult this split.
+    pub fn outcome_reserves(&self) -> (u64, u64) {
+        let yes_sq = (self.yes_supply as u128).pow(2);
+        let total = yes_sq.saturating_add((self.no_supply as u128).pow(2));
+        if total == 0 {
+            let half = self.reserves / 2;
+            return (half, self.reserves - half);
+        }
+
+        // Drop low bits until the weights fit in 64, so reserves * weight fits in u128
+        let shift = (128 - total.leading_zeros()).saturating_sub(64);
+        let (yes_weight, total) = (yes_sq >> shift, total >> shift);
+        let yes_part = (self.reserves as u128 * yes_weight / total) as u64;
+        (yes_part, self.reserves - yes_part)
+    }
+
+    /// Current implied (YES, NO) probabilities in basis points, summing to ≈10000
+    ///
+    /// Falls back to 50/50 if the curve math fails.
+    pub fn implied_odds(&self) -> (u64, u64) {
+        let yes = PythagoreanCurve::get_implied_probability_bps(self.yes_supply, self.no_supply).unwrap_or(5000);
+        let no = PythagoreanCurve::get_implied_probability_bps(self.no_supply, self.yes_supply).unwrap_or(5000);
+        (yes, no)
+    }
+
+    /// Collateral the pool owes at settlement given the current supplies
+    ///
+    /// Before resolution this is the worse of the two outcomes; once resolved
+    /// only the winning side counts. ProRata winners split whatever remains
+    /// and a cancelled market refunds pro rata, so neither carries a fixed claim.
+    pub fn settlement_obligation(&self) -> u64 {
+        if self.settlement_mode == SettlementMode::ProRata || self.status == MarketStatus::Cancelled {
+            return 0;
+        }
+        match self.outcome {
+            Outcome::Undetermined => self.yes_supply.max(self.no_supply),
+            Outcome::Yes => self.yes_supply,
+            Outcome::No => self.no_supply,
+            Outcome::Draw => 0,
+        }
+    }
+
+    /// Reserves the Pythagorean invariant implies for the current supplies: √(YES² + NO²)
+    pub fn invariant_reserves(&self) -> u64 {
+        let sum_sq = (self.yes_supply as u128).pow(2).saturating_add((self.no_supply as u128).pow(2));
+        u64::try_from(crate::amm::sqrt(sum_sq)).unwrap_or(u64::MAX)
+    }
+
+    /// Whether reserves still cover `settlement_obligation`
+    ///
+    /// Always true for ProRata and cancelled markets, whose obligation is 0,
+    /// so as a guard this only binds FixedUnit markets.
+    pub fn is_collateralized(&self) -> bool {
+        self.reserves >= self.settlement_obligation()
+    }
+
+    /// Whether the resolution can still be disputed at `now`
+    pub fn dispute_open(&self, now: i64, window: i64) -> bool {
+        self.resolved_at > 0 && !self.bond_slashed && now < self.resolved_at.saturating_add(window)
+    }
+
+    /// Whether losing tokens can still be sold back to the curve at `now`
+    ///
+    /// Opens at resolution for `window` seconds; redemption waits until it
+    /// closes so winners settle against the final reserves. FixedUnit only:
+    /// there each winning token is owed exactly one unit, so losers can only
+    /// sell out of the surplus above that. A ProRata pool has no surplus, as
+    /// winners are owed all of it.
+    pub fn exit_window_open(&self, now: i64, window: i64) -> bool {
+        self.status == MarketStatus::Resolved
+            && self.settlement_mode == SettlementMode::FixedUnit
+            && window > 0
+            && now < self.resolved_at.saturating_add(window)
+    }
+
+    /// Most a losing-side sell can release without eating into the winners' face value
+    pub fn loser_exit_cap(&self) -> u64 {
+        self.winning_side().map_or(0, |side| self.reserves.saturating_sub(self.supply(side)))
+    }
+
+    /// Whether shielded winners can still reveal at `now`
+    ///
+    /// Opens at resolution for `window` seconds. Shielded payouts wait until
+    /// it closes, so every winner's share is taken against the final total.
+    pub fn shielded_reveal_open(&self, now: i64, window: i64) -> bool {
+        self.status == MarketStatus::Resolved && now < self.resolved_at.saturating_add(window)
+    }
+
+    /// Share of the shielded pool owed to a revealed winning `deposit`
+    ///
+    /// deposit * shielded_deposits / shielded_winning_revealed, rounded down,
+    /// so the payouts to all revealed winners never sum past the deposits.
+    pub fn shielded_payout(&self, deposit: u64) -> Option<u64> {
+        if self.shielded_winning_revealed == 0 {
+            return None;
+        }
+        let payout = (deposit as u128)
+            .checked_mul(self.shielded_deposits as u128)?
+            .checked_div(self.shielded_winning_revealed as u128)?;
+        u64::try_from(payout).ok()
+    }
+
+    /// Whether the oracle's `source` is the one declared at creation
+    ///
+    /// Markets that declared nothing accept any source. Paths that carry no
+    /// source (`None`) can only resolve those.
+    pub fn accepts_resolution_source(&self, source: Option<&[u8]>) -> bool {
+        use anchor_lang::solana_program::keccak;
+        if self.resolution_source_hash == [0u8; 32] {
+            return true;
+        }
+        source.is_some_and(|source| keccak::hash(source).0 == self.resolution_source_hash)
+    }
+
+    /// Side whose tokens redeem, or None while no side has won
+    ///
+    /// Every redeem path selects its mint, balance and supply from this, so
+    /// a new outcome only needs mapping here.
+    pub fn winning_side(&self) -> Option<Side> {
+        match self.outcome {
+            Outcome::Yes => Some(Side::Yes),
+            Outcome::No => Some(Side::No),
+            Outcome::Undetermined | Outcome::Draw => None,
+        }
+    }
+
+    /// Outstanding supply of `side`
+    pub fn supply(&self, side: Side) -> u64 {
+        side.pick(self.yes_supply, self.no_supply)
+    }
+
+    /// Mutable supply of `side`
+    pub fn supply_mut(&mut self, side: Side) -> &mut u64 {
+        match side {
+            Side::Yes => &mut self.yes_supply,
+            Side::No => &mut self.no_supply,
+        }
+    }
+
+    /// Mint of the side that lost, once the market has resolved
+    ///
+    /// Cancelled markets refund both sides, so neither is worthless there.
+    pub fn losing_mint(&self) -> Option<Pubkey> {
+        if self.status != MarketStatus::Resolved {
+            return None;
+        }
+        self.winning_side().map(|side| side.opposite().pick(self.yes_mint, self.no_mint))
+    }
+
+    /// Collateral owed for `tokens` winning tokens out of `total_supply`
+    ///
+    /// - ProRata: tokens * reserves / total_supply
+    /// - FixedUnit: 1 collateral unit per token, capped by reserves
+    pub fn winning_payout(&self, tokens: u64, total_supply: u64) -> Option<u64> {
+        match self.settlement_mode {
+            SettlementMode::ProRata => {
+                if total_supply == 0 {
+                    return None;
+                }
+                let payout = (tokens as u128)
+                    .checked_mul(self.reserves as u128)?
+                    .checked_div(total_supply as u128)?;
+                u64::try_from(payout).ok()
+            }
+            SettlementMode::FixedUnit => Some(tokens.min(self.reserves)),
+        }
+    }
+
+    /// Share of reserves owed for `tokens` outcome tokens of either side
+    ///
+    /// Used when outcome no longer matters (cancelled market, emergency exit):
+    /// every outstanding YES or NO token is worth the same slice of the pool.
+    pub fn outcome_neutral_payout(&self, tokens: u64) -> Option<u64> {
+        let total_supply = (self.yes_supply as u128).checked_add(self.no_supply as u128)?;
+        if total_supply == 0 {
+            return None;
+        }
+        let payout = (tokens as u128)
+            .checked_mul(self.reserves as u128)?
+            .checked_div(total_supply)?;
+        u64::try_from(payout).ok()
+    }
+
+    /// Winning tokens that must be burned to release `payout` collateral
+    ///
+    /// Inverse of `winning_payout`, used by the fixed-denomination privacy paths.
+    /// Rounds up so a partial redemption never pays more than its tokens are worth.
+    pub fn tokens_for_payout(&self, payout: u64, total_supply: u64) -> Option<u64> {
+        match self.settlement_mode {
+            SettlementMode::ProRata => {
+                if self.reserves == 0 {
+                    return None;
+                }
+                let tokens = (payout as u128)
+                    .checked_mul(total_supply as u128)?
+                    .div_ceil(self.reserves as u128);
+                u64::try_from(tokens).ok()
+            }
+            SettlementMode::FixedUnit => Some(payout),
+        }
+    }
 }
 
 /// A privacy claim representing a pending private payout.
@@ -88,7 +410,10 @@ impl Market {
 /// The original trader commits to a hash. Anyone with the secret
 /// can later claim to any wallet, breaking the on-chain link.
 ///
-/// Seeds: ["privacy_claim", market.key().as_ref(), commitment.as_ref()]
+/// Seeds: ["privacy_claim", market.key().as_ref(), commitment.as_ref(), nonce.to_le_bytes()]
+///
+/// The nonce is also hashed into the commitment, so one secret and recipient
+/// can back sequential claims (nonce 0, 1, 2, ...) without reusing a slot.
 #[account]
 #[derive(InitSpace)]
 pub struct PrivacyClaim {
@@ -97,13 +422,34 @@ pub struct PrivacyClaim {
     pub amount: u64,
     pub lock_until: i64, 
     pub commitment: [u8; 32],
-    pub redeemed: bool,
-    pub nonce: u64, // Anti-replay nonce
+    pub nonce: u64, // Anti-replay nonce, part of the seeds and the commitment
     pub bump: u8,
 }
 
 impl PrivacyClaim {
     pub const SEED: &'static [u8] = b"privacy_claim";
+
+    /// Fixed payout denomination, so claim amounts can't fingerprint a trader
+    pub const DENOMINATION: u64 = 1_000_000;
+
+    /// Largest multiple of `DENOMINATION` that fits in `raw` (rounds down)
+    ///
+    /// The value below one denomination is not locked. Its tokens are left
+    /// unburned: in the trader's wallet for `redeem_privacy`, redeemable through
+    /// the public `redeem`; in the position vaults for `redeem_privacy_position`,
+    /// where merging positions can grow it past the next denomination.
+    pub fn lockable_amount(raw: u64) -> u64 {
+        (raw / Self::DENOMINATION) * Self::DENOMINATION
+    }
+
+    /// Collateral a claim locks for `raw` winnings
+    ///
+    /// Standardized claims round down to `lockable_amount`. Exact claims lock
+    /// all of `raw`, which loses no dust but puts the precise amount on-chain,
+    /// where it can link the claim back to the redeeming wallet.
+    pub fn claim_amount(raw: u64, standardize: bool) -> u64 {
+        if standardize { Self::lockable_amount(raw) } else { raw }
+    }
 }
 
 /// A privacy position representing ghost ownership of outcome tokens.
@@ -117,11 +463,30 @@ pub struct PrivacyPosition {
     pub commitment: [u8; 32],
     pub yes_amount: u64,
     pub no_amount: u64,
+    /// Collateral paid in through `trade_privacy`, checked against `config.max_privacy_position`
+    pub collateral_deposited: u64,
+    /// keccak256(view_key) of an auditor allowed to call `disclose_position`, if any
+    pub view_key_hash: Option<[u8; 32]>,
     pub bump: u8,
 }
 
 impl PrivacyPosition {
     pub const SEED: &'static [u8] = b"privacy_position";
+
+    /// Ownership commitment: keccak256(secret || owner)
+    pub fn compute_commitment(secret: &[u8; 32], owner: &Pubkey) -> [u8; 32] {
+        use anchor_lang::solana_program::keccak;
+        let mut data = [0u8; 64];
+        data[..32].copy_from_slice(secret);
+        data[32..].copy_from_slice(owner.as_ref());
+        keccak::hash(&data).0
+    }
+
+    /// Whether `view_key` hashes to the disclosure key set at init
+    pub fn view_key_matches(&self, view_key: &[u8; 32]) -> bool {
+        use anchor_lang::solana_program::keccak;
+        self.view_key_hash == Some(keccak::hash(view_key).0)
+    }
 }
 
 /// A shielded position with encrypted direction for Blind Betting.
@@ -145,6 +510,10 @@ pub struct ShieldedPosition {
     pub shielded_amount: u64,
     /// Collateral deposited (for accurate payout calculation)
     pub collateral_deposited: u64,
+    /// Whether the position was revealed as a winner during the reveal window
+    pub revealed: bool,
+    /// Wallet fixed at reveal to receive the payout
+    pub recipient: Pubkey,
     /// PDA bump seed
     pub bump: u8,
 }
@@ -187,6 +556,29 @@ pub enum MarketStatus {
     Cancelled,
 }
 
+/// Redemption model for winning tokens
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug, Default)]
+pub enum SettlementMode {
+    /// Winners split the remaining reserves by token count
+    #[default]
+    ProRata,
+    /// Each winning token redeems for exactly 1 collateral unit (binary option style)
+    FixedUnit,
+}
+
+/// Market topic for discovery
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug, Default)]
+pub enum MarketCategory {
+    #[default]
+    Other,
+    Crypto,
+    Sports,
+    Politics,
+    Economics,
+    Science,
+    Entertainment,
+}
+
 /// Prediction outcome
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug, Default)]
 pub enum Outcome {
@@ -197,4 +589,369 @@ pub enum Outcome {
     Yes,
     /// NO outcome occurred
     No,
+    /// Genuinely even result; the market is voided and both sides refunded
+    Draw,
+}
+
+impl Outcome {
+    /// The decisive outcome for a YES/NO verdict
+    pub fn winner(yes_wins: bool) -> Self {
+        if yes_wins { Outcome::Yes } else { Outcome::No }
+    }
+}
+
+/// One side of a binary market
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Side {
+    Yes,
+    No,
+}
+
+impl Side {
+    /// The other side
+    pub fn opposite(self) -> Self {
+        match self {
+            Side::Yes => Side::No,
+            Side::No => Side::Yes,
+        }
+    }
+
+    /// Choose between a YES and a NO value
+    pub fn pick<T>(self, yes: T, no: T) -> T {
+        match self {
+            Side::Yes => yes,
+            Side::No => no,
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub(crate) fn market(reserves: u64, settlement_mode: SettlementMode) -> Market {
+        Market {
+            id: 0,
+            creator: Pubkey::default(),
+            question: String::new(),
+            end_time: 0,
+            created_at: 0,
+            yes_mint: Pubkey::default(),
+            no_mint: Pubkey::default(),
+            collateral_mint: Pubkey::default(),
+            reserves,
+            yes_supply: 0,
+            no_supply: 0,
+            shielded_reserve_commitment: [0u8; 32],
+            reserve_blinding: [0u8; 32],
+            status: MarketStatus::Resolved,
+            funded: true,
+            total_volume: 0,
+            reward_paid: false,
+            outcome: Outcome::Yes,
+            evidence_hash: [0u8; 32],
+            resolution_source_hash: [0u8; 32],
+            settlement_mode,
+            category: MarketCategory::Other,
+            locked_creator_tokens: 0,
+            liquidity_lock_until: 0,
+            min_trade_amount: 0,
+            crowdfunded_liquidity: 0,
+            crowdfunded_claimed: 0,
+            crowdfunded: false,
+            total_locked_in_claims: 0,
+            shielded_deposits: 0,
+            shielded_winning_revealed: 0,
+            allow_privacy: true,
+            resolved_at: 0,
+            resolved_by: Pubkey::default(),
+            bond_slashed: false,
+            tags: Vec::new(),
+            bump: 0,
+        }
+    }
+
+    #[test]
+    fn test_pro_rata_payout() {
+        let m = market(1_000_000, SettlementMode::ProRata);
+        assert_eq!(m.winning_payout(250_000, 500_000), Some(500_000));
+        assert_eq!(m.tokens_for_payout(500_000, 500_000), Some(250_000));
+        assert_eq!(m.winning_payout(1, 0), None);
+    }
+
+    #[test]
+    fn test_implied_odds_balanced() {
+        let mut m = market(1_000_000, SettlementMode::ProRata);
+        m.yes_supply = 707_000;
+        m.no_supply = 707_000;
+        let (yes, no) = m.implied_odds();
+        assert_eq!(yes, no);
+        assert_eq!(m.implied_odds(), (5000, 5000));
+    }
+
+    #[test]
+    fn test_denomination_dust_stays_redeemable() {
+        let mut m = market(3_000_000, SettlementMode::ProRata);
+        m.yes_supply = 2_000_000;
+
+        // 1.5M tokens are worth 2.25M collateral: 2M is locked, 0.25M is dust
+        let raw = m.winning_payout(1_500_000, m.yes_supply).unwrap();
+        let locked = PrivacyClaim::lockable_amount(raw);
+        assert_eq!((raw, locked), (2_250_000, 2_000_000));
+
+        let burned = m.tokens_for_payout(locked, m.yes_supply).unwrap();
+        assert_eq!(burned, 1_333_334); // rounded up in the pool's favour
+        m.reserves -= locked;
+        m.yes_supply -= burned;
+
+        // The remaining tokens redeem publicly for the dust, never for more
+        let dust = m.winning_payout(1_500_000 - burned, m.yes_supply).unwrap();
+        assert!(dust < PrivacyClaim::DENOMINATION);
+        assert!(locked + dust <= raw);
+    }
+
+    #[test]
+    fn test_claim_amount_modes() {
+        assert_eq!(PrivacyClaim::claim_amount(2_250_000, true), 2_000_000);
+        assert_eq!(PrivacyClaim::claim_amount(2_250_000, false), 2_250_000);
+        // Below one denomination only an exact claim locks anything
+        assert_eq!(PrivacyClaim::claim_amount(999_999, true), 0);
+        assert_eq!(PrivacyClaim::claim_amount(999_999, false), 999_999);
+    }
+
+    #[test]
+    fn test_outcome_reserves_split() {
+        let mut m = market(1_000, SettlementMode::ProRata);
+        m.yes_supply = 600;
+        m.no_supply = 800;
+        assert_eq!(m.outcome_reserves(), (360, 640));
+
+        m.yes_supply = 0;
+        m.no_supply = 0;
+        assert_eq!(m.outcome_reserves(), (500, 500));
+
+        // Near the supply cap the split stays exact in total
+        m.reserves = u64::MAX;
+        m.yes_supply = PythagoreanCurve::MAX_SUPPLY;
+        m.no_supply = PythagoreanCurve::MAX_SUPPLY;
+        let (yes, no) = m.outcome_reserves();
+        assert_eq!(yes + no, u64::MAX);
+        assert!(yes.abs_diff(no) <= 1);
+    }
+
+    #[test]
+    fn test_dispute_window() {
+        let mut m = market(1_000, SettlementMode::ProRata);
+        assert!(!m.dispute_open(1_000, 100));
+
+        m.resolved_at = 1_000;
+        assert!(m.dispute_open(1_099, 100));
+        assert!(!m.dispute_open(1_100, 100));
+
+        m.bond_slashed = true;
+        assert!(!m.dispute_open(1_050, 100));
+    }
+
+    #[test]
+    fn test_exit_window() {
+        let mut m = market(1_000, SettlementMode::FixedUnit);
+        m.resolved_at = 1_000;
+        assert!(m.exit_window_open(1_099, 100));
+        assert!(!m.exit_window_open(1_100, 100));
+        assert!(!m.exit_window_open(1_000, 0));
+
+        // ProRata winners are owed the whole pool, so losers never get a window
+        m.settlement_mode = SettlementMode::ProRata;
+        assert!(!m.exit_window_open(1_050, 100));
+
+        m.settlement_mode = SettlementMode::FixedUnit;
+        m.status = MarketStatus::Cancelled;
+        assert!(!m.exit_window_open(1_050, 100));
+    }
+
+    #[test]
+    fn test_losing_mint() {
+        let mut m = market(1_000, SettlementMode::ProRata);
+        m.yes_mint = Pubkey::new_unique();
+        m.no_mint = Pubkey::new_unique();
+        assert_eq!(m.losing_mint(), Some(m.no_mint));
+
+        m.outcome = Outcome::No;
+        assert_eq!(m.losing_mint(), Some(m.yes_mint));
+
+        m.outcome = Outcome::Draw;
+        assert_eq!(m.losing_mint(), None);
+
+        m.outcome = Outcome::No;
+        m.status = MarketStatus::Cancelled;
+        assert_eq!(m.losing_mint(), None);
+    }
+
+    #[test]
+    fn test_accepts_resolution_source() {
+        use anchor_lang::solana_program::keccak;
+        let mut m = market(1_000, SettlementMode::ProRata);
+        assert!(m.accepts_resolution_source(None));
+        assert!(m.accepts_resolution_source(Some(b"anything")));
+
+        m.resolution_source_hash = keccak::hash(b"https://example.com/results").0;
+        assert!(m.accepts_resolution_source(Some(b"https://example.com/results")));
+        assert!(!m.accepts_resolution_source(Some(b"https://example.com/other")));
+        assert!(!m.accepts_resolution_source(None));
+    }
+
+    #[test]
+    fn test_winning_side() {
+        let mut m = market(1_000, SettlementMode::ProRata);
+        m.yes_supply = 300;
+        m.no_supply = 700;
+        assert_eq!(m.winning_side(), Some(Side::Yes));
+        assert_eq!(m.supply(Side::Yes), 300);
+
+        m.outcome = Outcome::No;
+        assert_eq!(m.winning_side(), Some(Side::No));
+        *m.supply_mut(Side::No) -= 200;
+        assert_eq!(m.supply(Side::No), 500);
+
+        m.outcome = Outcome::Draw;
+        assert_eq!(m.winning_side(), None);
+        m.outcome = Outcome::Undetermined;
+        assert_eq!(m.winning_side(), None);
+    }
+
+    #[test]
+    fn test_settlement_obligation() {
+        let mut m = market(1_000, SettlementMode::FixedUnit);
+        m.status = MarketStatus::Active;
+        m.outcome = Outcome::Undetermined;
+        m.yes_supply = 900;
+        m.no_supply = 1_001;
+        assert_eq!(m.settlement_obligation(), 1_001);
+        assert!(!m.is_collateralized());
+
+        // Once YES wins the NO side is worthless
+        m.status = MarketStatus::Resolved;
+        m.outcome = Outcome::Yes;
+        assert!(m.is_collateralized());
+
+        m.settlement_mode = SettlementMode::ProRata;
+        m.reserves = 0;
+        assert!(m.is_collateralized());
+    }
+
+    #[test]
+    fn test_collateral_guard_trips_on_fixed_unit_only() {
+        // Reserves exactly cover the larger side, then a YES sale pays out 1
+        let mut m = market(1_000, SettlementMode::FixedUnit);
+        m.status = MarketStatus::Active;
+        m.outcome = Outcome::Undetermined;
+        m.yes_supply = 1_000;
+        m.no_supply = 1_000;
+        assert!(m.is_collateralized());
+        m.yes_supply -= 100;
+        m.reserves -= 1;
+        assert!(!m.is_collateralized());
+
+        // The same books pass unchecked under ProRata
+        m.settlement_mode = SettlementMode::ProRata;
+        assert!(m.is_collateralized());
+    }
+
+    #[test]
+    fn test_invariant_reserves() {
+        let mut m = market(5_000, SettlementMode::ProRata);
+        m.yes_supply = 3_000;
+        m.no_supply = 4_000;
+        assert_eq!(m.invariant_reserves(), 5_000);
+
+        // Supplies at the cap must not overflow
+        m.yes_supply = PythagoreanCurve::MAX_SUPPLY;
+        m.no_supply = PythagoreanCurve::MAX_SUPPLY;
+        assert!(m.invariant_reserves() > PythagoreanCurve::MAX_SUPPLY);
+    }
+
+    #[test]
+    fn test_outcome_neutral_payout() {
+        let mut m = market(1_000, SettlementMode::FixedUnit);
+        m.yes_supply = 600;
+        m.no_supply = 400;
+        assert_eq!(m.outcome_neutral_payout(100), Some(100));
+        assert_eq!(m.outcome_neutral_payout(1_000), Some(1_000));
+
+        m.yes_supply = 0;
+        m.no_supply = 0;
+        assert_eq!(m.outcome_neutral_payout(100), None);
+    }
+
+    #[test]
+    fn test_liquidity_unlocked() {
+        let mut m = market(0, SettlementMode::ProRata);
+        m.status = MarketStatus::Active;
+        m.liquidity_lock_until = 100;
+        assert!(!m.liquidity_unlocked(99));
+        assert!(m.liquidity_unlocked(100));
+
+        m.status = MarketStatus::Resolved;
+        assert!(m.liquidity_unlocked(0));
+    }
+
+    #[test]
+    fn test_min_trade_falls_back_to_global() {
+        let mut m = market(0, SettlementMode::ProRata);
+        assert_eq!(m.min_trade(1_000), 1_000);
+        m.min_trade_amount = 50;
+        assert_eq!(m.min_trade(1_000), 50);
+    }
+
+    #[test]
+    fn test_end_if_expired() {
+        let mut m = market(0, SettlementMode::ProRata);
+        m.status = MarketStatus::Active;
+        m.end_time = 100;
+        assert!(!m.end_if_expired(99));
+        assert_eq!(m.status, MarketStatus::Active);
+        assert!(m.end_if_expired(100));
+        assert_eq!(m.status, MarketStatus::Ended);
+        assert!(!m.end_if_expired(200));
+    }
+
+    #[test]
+    fn test_has_ended_never_wraps() {
+        let mut m = market(0, SettlementMode::ProRata);
+        m.end_time = 100;
+        assert!(!m.has_ended(99));
+        assert!(m.has_ended(100));
+
+        // `as i64` would read this as -1, i.e. long past
+        m.end_time = u64::MAX;
+        assert!(!m.has_ended(1_700_000_000));
+        assert!(m.has_ended(i64::MAX));
+    }
+
+    #[test]
+    fn test_fixed_unit_payout_capped_by_reserves() {
+        let m = market(1_000_000, SettlementMode::FixedUnit);
+        assert_eq!(m.winning_payout(250_000, 500_000), Some(250_000));
+        assert_eq!(m.winning_payout(2_000_000, 2_000_000), Some(1_000_000));
+        assert_eq!(m.tokens_for_payout(250_000, 500_000), Some(250_000));
+    }
+
+    #[test]
+    fn test_view_key_matches() {
+        use anchor_lang::solana_program::keccak;
+        let view_key = [7u8; 32];
+        let mut pos = PrivacyPosition {
+            market: Pubkey::default(),
+            commitment: [1u8; 32],
+            yes_amount: 0,
+            no_amount: 0,
+            collateral_deposited: 0,
+            view_key_hash: None,
+            bump: 0,
+        };
+        assert!(!pos.view_key_matches(&view_key));
+        pos.view_key_hash = Some(keccak::hash(&view_key).0);
+        assert!(pos.view_key_matches(&view_key));
+        assert!(!pos.view_key_matches(&[8u8; 32]));
+    }
 }
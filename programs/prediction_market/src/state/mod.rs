@@ -1,7 +1,21 @@
 //! State structures for the prediction market protocol
 
+pub mod commitment_index;
 pub mod config;
+pub mod liquidity;
 pub mod market;
+pub mod oracle_bond;
+pub mod price_reference;
+pub mod stats;
+pub mod test_clock;
+pub mod trader;
 
+pub use commitment_index::*;
 pub use config::*;
+pub use liquidity::*;
 pub use market::*;
+pub use oracle_bond::*;
+pub use price_reference::*;
+pub use stats::*;
+pub use test_clock::*;
+pub use trader::*;
@@ -0,0 +1,54 @@
+//! Oracle Bond
+//!
+//! Collateral the oracle stakes behind its resolutions. Each resolution
+//! holds the bond for the dispute window; if the admin upholds a dispute
+//! in that window, `config.oracle_bond` is slashed to the fee vault.
+
+use anchor_lang::prelude::*;
+
+/// An oracle's staked bond (tokens live in this PDA's collateral ATA)
+///
+/// Seeds: ["oracle_bond", oracle]
+#[account]
+#[derive(InitSpace)]
+pub struct OracleBond {
+    pub oracle: Pubkey,
+
+    /// Collateral currently staked
+    pub amount: u64,
+
+    /// End of the latest dispute window; withdrawals wait until then
+    pub locked_until: i64,
+
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl OracleBond {
+    pub const SEED: &'static [u8] = b"oracle_bond";
+
+    /// Extend the lock to `until`, never shortening an open window
+    pub fn lock_until(&mut self, until: i64) {
+        self.locked_until = self.locked_until.max(until);
+    }
+
+    /// Whether every dispute window this bond backs has closed
+    pub fn unlocked(&self, now: i64) -> bool {
+        now >= self.locked_until
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lock_only_extends() {
+        let mut bond = OracleBond { oracle: Pubkey::new_unique(), amount: 100, locked_until: 0, bump: 0 };
+        bond.lock_until(1_000);
+        bond.lock_until(500);
+        assert_eq!(bond.locked_until, 1_000);
+        assert!(!bond.unlocked(999));
+        assert!(bond.unlocked(1_000));
+    }
+}
@@ -0,0 +1,38 @@
+//! Reference Prices
+//!
+//! An integration's published view of a market's prices, typically the odds
+//! its UI is showing. Traders opt in by passing one to `buy_tokens` or
+//! `sell_tokens`, and the fill is rejected if it executes too far from it.
+
+use anchor_lang::prelude::*;
+
+/// A publisher's reference prices for one market
+///
+/// Seeds: ["price_reference", market, publisher]
+#[account]
+#[derive(InitSpace)]
+pub struct PriceReference {
+    pub market: Pubkey,
+    pub publisher: Pubkey,
+
+    /// YES price in basis points, same unit as `PythagoreanCurve::get_price`
+    pub yes_price_bps: u64,
+
+    /// NO price in basis points
+    pub no_price_bps: u64,
+
+    /// Unix timestamp of the last publish
+    pub updated_at: i64,
+
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl PriceReference {
+    pub const SEED: &'static [u8] = b"price_reference";
+
+    /// Reference price for one side
+    pub fn price_bps(&self, is_yes: bool) -> u64 {
+        if is_yes { self.yes_price_bps } else { self.no_price_bps }
+    }
+}
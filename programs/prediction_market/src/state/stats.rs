@@ -0,0 +1,59 @@
+//! Protocol Statistics
+//!
+//! Aggregate counters across all markets, so operators can read protocol
+//! health directly instead of summing thousands of market accounts.
+
+use anchor_lang::prelude::*;
+
+/// Protocol-wide statistics (singleton PDA)
+///
+/// Seeds: ["protocol_stats"]
+#[account]
+#[derive(InitSpace)]
+pub struct ProtocolStats {
+    /// Markets ever created
+    pub total_markets: u64,
+
+    /// Markets created but not yet resolved
+    pub active_markets: u64,
+
+    /// Collateral traded across all markets
+    pub total_volume: u64,
+
+    /// Protocol fees charged across all markets
+    pub total_fees: u64,
+
+    /// Collateral currently held in market reserves
+    pub total_value_locked: u64,
+
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl ProtocolStats {
+    pub const SEED: &'static [u8] = b"protocol_stats";
+
+    // Counters saturate so that bookkeeping can never block a trade.
+
+    pub fn record_market_created(&mut self) {
+        self.total_markets = self.total_markets.saturating_add(1);
+        self.active_markets = self.active_markets.saturating_add(1);
+    }
+
+    pub fn record_market_resolved(&mut self) {
+        self.active_markets = self.active_markets.saturating_sub(1);
+    }
+
+    pub fn record_trade(&mut self, volume: u64, fee: u64) {
+        self.total_volume = self.total_volume.saturating_add(volume);
+        self.total_fees = self.total_fees.saturating_add(fee);
+    }
+
+    pub fn record_deposit(&mut self, amount: u64) {
+        self.total_value_locked = self.total_value_locked.saturating_add(amount);
+    }
+
+    pub fn record_withdrawal(&mut self, amount: u64) {
+        self.total_value_locked = self.total_value_locked.saturating_sub(amount);
+    }
+}
@@ -0,0 +1,23 @@
+//! Test Clock
+//!
+//! Clock override for integration tests. Handlers only read it in builds
+//! with the `test-clock` feature; see `instructions::clock`.
+
+use anchor_lang::prelude::*;
+
+/// Time reported to handlers that are passed this account
+///
+/// Seeds: ["test_clock"]
+#[account]
+#[derive(InitSpace)]
+pub struct TestClock {
+    /// Unix timestamp handlers see instead of the Clock sysvar
+    pub unix_timestamp: i64,
+
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl TestClock {
+    pub const SEED: &'static [u8] = b"test_clock";
+}
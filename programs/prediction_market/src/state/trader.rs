@@ -0,0 +1,47 @@
+//! Per-Trader Market Activity
+//!
+//! Tracks when a trader last traded in a market, so the optional
+//! `config.trade_cooldown_seconds` can deter rapid wash trading.
+
+use anchor_lang::prelude::*;
+
+/// Trader activity in one market
+///
+/// Seeds: ["trader_record", market, trader]
+#[account]
+#[derive(InitSpace)]
+pub struct TraderRecord {
+    pub trader: Pubkey,
+    pub market: Pubkey,
+
+    /// Unix timestamp of the last buy or sell (0 = never traded)
+    pub last_trade_ts: i64,
+
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl TraderRecord {
+    pub const SEED: &'static [u8] = b"trader_record";
+
+    /// Whether a trade at `now` respects the cooldown (0 disables it)
+    pub fn cooldown_elapsed(&self, now: i64, cooldown: i64) -> bool {
+        cooldown <= 0 || self.last_trade_ts == 0 || now.saturating_sub(self.last_trade_ts) >= cooldown
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cooldown_elapsed() {
+        let mut record = TraderRecord { trader: Pubkey::default(), market: Pubkey::default(), last_trade_ts: 0, bump: 0 };
+        assert!(record.cooldown_elapsed(1_000, 60));
+
+        record.last_trade_ts = 1_000;
+        assert!(!record.cooldown_elapsed(1_059, 60));
+        assert!(record.cooldown_elapsed(1_060, 60));
+        assert!(record.cooldown_elapsed(1_001, 0));
+    }
+}
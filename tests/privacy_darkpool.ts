@@ -5,6 +5,8 @@ import {
     PublicKey,
     Keypair,
     SystemProgram,
+    Ed25519Program,
+    SYSVAR_INSTRUCTIONS_PUBKEY,
 } from "@solana/web3.js";
 import {
     TOKEN_PROGRAM_ID,
@@ -30,6 +32,9 @@ describe("private_pnp_tests", () => {
         return Keypair.fromSecretKey(new Uint8Array(JSON.parse(fs.readFileSync(`./tests/keys/${name}.json`, 'utf-8'))));
     };
 
+    const NO_EVIDENCE = Array(32).fill(0) as any;
+    const NO_SOURCE_HASH = Array(32).fill(0) as any; // market declares no resolution source
+    const NO_SOURCE = Buffer.alloc(0);
     const admin = (provider.wallet as anchor.Wallet).payer;
     const traderA = loadKeypair("traderA");
     const traderB = loadKeypair("traderB");
@@ -39,6 +44,8 @@ describe("private_pnp_tests", () => {
 
     let collateralMint: PublicKey;
     let configPDA: PublicKey;
+    let protocolStatsPDA: PublicKey;
+    let feeVault: PublicKey;
     const isLocalnet = provider.connection.rpcEndpoint.includes("localhost") || provider.connection.rpcEndpoint.includes("127.0.0.1");
 
     const loading = async (msg: string) => {
@@ -104,7 +111,8 @@ describe("private_pnp_tests", () => {
         await fundWallet(relayer.publicKey, 0.05);
 
         collateralMint = await createMint(provider.connection, admin, admin.publicKey, null, 6);
-        [configPDA] = PublicKey.findProgramAddressSync([Buffer.from("config_v7")], program.programId);
+        [configPDA] = PublicKey.findProgramAddressSync([Buffer.from("config_v8")], program.programId);
+        [protocolStatsPDA] = PublicKey.findProgramAddressSync([Buffer.from("protocol_stats")], program.programId);
 
         // Check if config already exists on Devnet
         let existingConfig: any = null;
@@ -124,7 +132,11 @@ describe("private_pnp_tests", () => {
                 const tx = await program.methods.initialize(new BN(100), oracle.publicKey).accounts({
                     admin: admin.publicKey,
                     config: configPDA,
+                    protocolStats: protocolStatsPDA,
                     collateralMint: collateralMint,
+                    feeVault: getAssociatedTokenAddressSync(collateralMint, configPDA, true),
+                    tokenProgram: TOKEN_PROGRAM_ID,
+                    associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
                     systemProgram: SystemProgram.programId,
                 } as any).signers([admin]).rpc({ commitment: "confirmed", skipPreflight: true });
 
@@ -136,9 +148,12 @@ describe("private_pnp_tests", () => {
                 throw e;
             }
         }
+
+        // Every trade pays its fee into the config's fee vault
+        feeVault = (await program.account.config.fetch(configPDA)).feeVault;
     });
 
-    const createMarketHelper = async (question: string) => {
+    const createMarketHelper = async (question: string, minTradeAmount = 0, allowPrivacy = true, resolutionSourceHash = NO_SOURCE_HASH, settlementMode: any = { proRata: {} }) => {
         process.stdout.write(`   🔹 Syncing: ${question} `);
         const configState = await program.account.config.fetch(configPDA);
         const idBN = configState.marketCount;
@@ -148,7 +163,7 @@ describe("private_pnp_tests", () => {
         const vault = getAssociatedTokenAddressSync(collateralMint, marketPDA, true);
 
         const duration = isLocalnet ? 5 : 60;
-        await program.methods.createMarketState(question, new BN(Math.floor(Date.now() / 1000) + duration)).accounts({
+        await program.methods.createMarketState(question, new BN(Math.floor(Date.now() / 1000) + duration), settlementMode, { other: {} }, [], new BN(minTradeAmount), allowPrivacy, false, resolutionSourceHash).accounts({
             creator: admin.publicKey, config: configPDA, market: marketPDA, collateralMint: collateralMint, systemProgram: SystemProgram.programId,
         } as any).signers([admin]).rpc();
         process.stdout.write(".");
@@ -161,7 +176,7 @@ describe("private_pnp_tests", () => {
         const adminYes = getAssociatedTokenAddressSync(yesMint, admin.publicKey);
         const adminNo = getAssociatedTokenAddressSync(noMint, admin.publicKey);
         await program.methods.createMarketVaults().accounts({
-            creator: admin.publicKey, market: marketPDA, yesMint, noMint, collateralMint, vault, creatorYes: adminYes, creatorNo: adminNo, tokenProgram: TOKEN_PROGRAM_ID, associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID, systemProgram: SystemProgram.programId,
+            creator: admin.publicKey, config: configPDA, market: marketPDA, yesMint, noMint, collateralMint, vault, creatorYes: adminYes, creatorNo: adminNo, feeVault: getAssociatedTokenAddressSync(collateralMint, configPDA, true), tokenProgram: TOKEN_PROGRAM_ID, associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID, systemProgram: SystemProgram.programId,
         } as any).signers([admin]).rpc();
         process.stdout.write(".");
 
@@ -177,6 +192,15 @@ describe("private_pnp_tests", () => {
         return { marketPDA, yesMint, noMint, vault };
     };
 
+    const updateConfig = async (changes: Record<string, any>) => {
+        const update = {
+            paused: null, balancingFeeBps: null, skewingFeeBps: null, emergencyWithdrawDelay: null, maxMarketDuration: null,
+            enforceSlippage: null, recipientAllowlist: null, cpiGuardEnabled: null, allowedCpiCaller: null, resolverReward: null,
+            tradeCooldownSeconds: null, minTradeAmount: null, creatorLiquidityLockSeconds: null, creatorFeeDiscountBps: null, maxPrivacyPosition: null, minMarketDuration: null, referencePriceToleranceBps: null, oracleBond: null, disputeWindowSeconds: null, maxCollateralDecimals: null, feeRounding: null, postResolutionTradeWindow: null, shieldedRevealWindow: null, ...changes,
+        };
+        await program.methods.updateConfig(update as any).accounts({ admin: admin.publicKey, config: configPDA } as any).signers([admin]).rpc();
+    };
+
     const hashCommitment = (secret: Uint8Array, recipient: PublicKey, nonce: BN) => {
         const data = new Uint8Array(32 + 32 + 8);
         data.set(secret, 0);
@@ -189,6 +213,17 @@ describe("private_pnp_tests", () => {
         beforeEach(async () => {
             await loading("Preparing next functional test");
         });
+        it("Setup: Initialize creates the config-owned fee vault", async () => {
+            const config = await program.account.config.fetch(configPDA);
+            const feeVault = getAssociatedTokenAddressSync(collateralMint, configPDA, true);
+            expect(config.feeVault.equals(feeVault)).to.be.true;
+
+            const account = await getAccount(provider.connection, feeVault);
+            expect(account.owner.equals(configPDA)).to.be.true;
+            expect(account.mint.equals(collateralMint)).to.be.true;
+            console.log("   ✅ Fee Vault Ready Before the First Trade.");
+        });
+
         it("Simple Trade: Public Market", async () => {
             console.log("   --- Testing standard market ---");
             const { marketPDA, yesMint, noMint, vault } = await createMarketHelper("BTC > 100k?");
@@ -203,14 +238,36 @@ describe("private_pnp_tests", () => {
                 trader: traderA.publicKey, yesMint, noMint, traderYes, traderNo, tokenProgram: TOKEN_PROGRAM_ID, associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID, systemProgram: SystemProgram.programId,
             } as any).signers([traderA]).rpc();
 
+            const feesBefore = Number((await provider.connection.getTokenAccountBalance(feeVault)).value.amount);
+            const reservesBefore = (await program.account.market.fetch(marketPDA)).reserves.toNumber();
             await program.methods.buyTokens(new BN(5_000_000), true, new BN(0)).accounts({
-                trader: traderA.publicKey, config: configPDA, market: marketPDA, yesMint, noMint, collateralMint, traderCollateral, traderYes, traderNo, vault, tokenProgram: TOKEN_PROGRAM_ID,
+                trader: traderA.publicKey, config: configPDA, market: marketPDA, yesMint, noMint, collateralMint, traderCollateral, traderYes, traderNo, vault, feeVault, tokenProgram: TOKEN_PROGRAM_ID,
             } as any).signers([traderA]).rpc();
 
             const bal = await provider.connection.getTokenAccountBalance(traderYes);
             expect(Number(bal.value.amount)).to.be.greaterThan(0);
+
+            // The fee lands in the fee vault; reserves and the market vault only hold the net deposit
+            const reservesAfter = (await program.account.market.fetch(marketPDA)).reserves.toNumber();
+            const feesAfter = Number((await provider.connection.getTokenAccountBalance(feeVault)).value.amount);
+            expect(reservesAfter - reservesBefore + feesAfter - feesBefore).to.equal(5_000_000);
+            expect(Number((await provider.connection.getTokenAccountBalance(vault)).value.amount)).to.equal(reservesAfter);
+
+            const stats = await program.methods.getProtocolStats().accounts({ protocolStats: protocolStatsPDA } as any).view();
+            expect(stats.totalVolume.toNumber()).to.be.greaterThan(0);
+            expect(stats.totalValueLocked.toNumber()).to.be.greaterThan(0);
+
+            const payouts = await program.methods.simulateResolution(new BN(bal.value.amount), new BN(0)).accounts({ market: marketPDA } as any).view();
+            expect(payouts.ifYes.toNumber()).to.be.greaterThan(0);
+            expect(payouts.ifNo.toNumber()).to.equal(0);
+
+            const toNinety = await program.methods.collateralForTargetPrice(new BN(9_000), true).accounts({ config: configPDA, market: marketPDA } as any).view();
+            expect(toNinety.toNumber()).to.be.greaterThan(0);
             await waitForExpiry(marketPDA);
-            await program.methods.resolveMarket(true).accounts({ oracle: oracle.publicKey, market: marketPDA }).signers([oracle]).rpc();
+            const evidenceHash = Array.from(keccak_256(new TextEncoder().encode("https://example.com/btc-close"))) as any;
+            await program.methods.resolveMarket(true, evidenceHash, NO_SOURCE).accounts({ oracle: oracle.publicKey, market: marketPDA }).signers([oracle]).rpc();
+            const resolved = await program.account.market.fetch(marketPDA);
+            expect(Buffer.from(resolved.evidenceHash as any).equals(Buffer.from(evidenceHash))).to.be.true;
 
             const beforeBal = await provider.connection.getTokenAccountBalance(traderCollateral);
             await program.methods.redeem().accounts({
@@ -220,6 +277,142 @@ describe("private_pnp_tests", () => {
             const afterBal = await provider.connection.getTokenAccountBalance(traderCollateral);
             expect(Number(afterBal.value.amount)).to.be.greaterThan(Number(beforeBal.value.amount));
             console.log("   ✅ Public Redemption Verified.");
+
+            // The creator's NO tokens lost: burn them and reclaim the ATA rent
+            const adminNo = getAssociatedTokenAddressSync(noMint, admin.publicKey);
+            const losingBal = Number((await provider.connection.getTokenAccountBalance(adminNo)).value.amount);
+            await program.methods.burnLosingTokens().accounts({
+                user: admin.publicKey, market: marketPDA, losingMint: noMint, userLosing: adminNo, tokenProgram: TOKEN_PROGRAM_ID,
+            } as any).signers([admin]).rpc();
+            expect(await provider.connection.getAccountInfo(adminNo)).to.be.null;
+            const cleaned = await program.account.market.fetch(marketPDA);
+            expect(cleaned.noSupply.toNumber()).to.equal(resolved.noSupply.toNumber() - losingBal);
+            console.log("   🧹 Losing Tokens Burned, Rent Reclaimed.");
+        });
+
+        it("Simple Trade: Public winnings straight into a privacy claim", async () => {
+            console.log("   --- Testing one-transaction private exit ---");
+            const { marketPDA, yesMint, noMint, vault } = await createMarketHelper("Direct Exit?");
+            const traderCollateral = (await getOrCreateAssociatedTokenAccount(provider.connection, traderA, collateralMint, traderA.publicKey)).address;
+            await mintTo(provider.connection, admin, collateralMint, traderCollateral, admin, 5_000_000);
+            const traderYes = getAssociatedTokenAddressSync(yesMint, traderA.publicKey);
+            const traderNo = getAssociatedTokenAddressSync(noMint, traderA.publicKey);
+            await program.methods.buyTokens(new BN(5_000_000), true, new BN(0)).accounts({
+                trader: traderA.publicKey, config: configPDA, market: marketPDA, yesMint, noMint, collateralMint, traderCollateral, traderYes, traderNo, vault, feeVault, tokenProgram: TOKEN_PROGRAM_ID,
+            } as any).signers([traderA]).rpc();
+            await waitForExpiry(marketPDA);
+            await program.methods.resolveMarket(true, NO_EVIDENCE, NO_SOURCE).accounts({ oracle: oracle.publicKey, market: marketPDA }).signers([oracle]).rpc();
+
+            const nonce = new BN(0);
+            const payoutCommitment = hashCommitment(crypto.randomBytes(32), freshWallet.publicKey, nonce);
+            const [privacyClaim] = PublicKey.findProgramAddressSync([Buffer.from("privacy_claim"), marketPDA.toBuffer(), payoutCommitment, nonce.toArrayLike(Buffer, "le", 8)], program.programId);
+            const privacyVault = getAssociatedTokenAddressSync(collateralMint, privacyClaim, true);
+            await program.methods.redeemPrivacyDirect(Array.from(payoutCommitment) as any, nonce, true).accounts({
+                user: traderA.publicKey, market: marketPDA, config: configPDA, privacyClaim, yesMint, noMint, collateralMint, userYes: traderYes, userNo: traderNo, vault, privacyVault,
+                instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY, tokenProgram: TOKEN_PROGRAM_ID, associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID, systemProgram: SystemProgram.programId,
+            } as any).signers([traderA]).rpc();
+
+            const claim = await program.account.privacyClaim.fetch(privacyClaim);
+            expect(claim.amount.toNumber()).to.be.greaterThan(0);
+            expect(Number((await getAccount(provider.connection, privacyVault)).amount)).to.equal(claim.amount.toNumber());
+            expect(claim.amount.toNumber() % 1_000_000).to.equal(0); // standardized to the fixed denomination
+            console.log("   ✅ Claim Created and Funded in One Transaction.");
+        });
+
+        it("Privacy Exit: Exact redemption skips the fixed denomination", async () => {
+            console.log("   --- Testing exact private exit ---");
+            const { marketPDA, yesMint, noMint, vault } = await createMarketHelper("Exact Exit?");
+            const traderCollateral = (await getOrCreateAssociatedTokenAccount(provider.connection, traderA, collateralMint, traderA.publicKey)).address;
+            await mintTo(provider.connection, admin, collateralMint, traderCollateral, admin, 3_333_333);
+            const traderYes = getAssociatedTokenAddressSync(yesMint, traderA.publicKey);
+            const traderNo = getAssociatedTokenAddressSync(noMint, traderA.publicKey);
+            await program.methods.buyTokens(new BN(3_333_333), true, new BN(0)).accounts({
+                trader: traderA.publicKey, config: configPDA, market: marketPDA, yesMint, noMint, collateralMint, traderCollateral, traderYes, traderNo, vault, feeVault, tokenProgram: TOKEN_PROGRAM_ID,
+            } as any).signers([traderA]).rpc();
+            await waitForExpiry(marketPDA);
+            await program.methods.resolveMarket(true, NO_EVIDENCE, NO_SOURCE).accounts({ oracle: oracle.publicKey, market: marketPDA }).signers([oracle]).rpc();
+
+            // The exact payout is what a public redeem of the whole position would pay
+            const before = await program.account.market.fetch(marketPDA);
+            const balance = BigInt((await getAccount(provider.connection, traderYes)).amount);
+            const expected = Number(balance * BigInt(before.reserves.toString()) / BigInt(before.yesSupply.toString()));
+
+            const nonce = new BN(0);
+            const payoutCommitment = hashCommitment(crypto.randomBytes(32), freshWallet.publicKey, nonce);
+            const [privacyClaim] = PublicKey.findProgramAddressSync([Buffer.from("privacy_claim"), marketPDA.toBuffer(), payoutCommitment, nonce.toArrayLike(Buffer, "le", 8)], program.programId);
+            const privacyVault = getAssociatedTokenAddressSync(collateralMint, privacyClaim, true);
+            await program.methods.redeemPrivacyDirect(Array.from(payoutCommitment) as any, nonce, false).accounts({
+                user: traderA.publicKey, market: marketPDA, config: configPDA, privacyClaim, yesMint, noMint, collateralMint, userYes: traderYes, userNo: traderNo, vault, privacyVault,
+                instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY, tokenProgram: TOKEN_PROGRAM_ID, associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID, systemProgram: SystemProgram.programId,
+            } as any).signers([traderA]).rpc();
+
+            const claim = await program.account.privacyClaim.fetch(privacyClaim);
+            expect(claim.amount.toNumber()).to.equal(expected);
+            expect(Number((await getAccount(provider.connection, traderYes)).amount)).to.equal(0);
+            console.log(`   ✅ Locked the exact ${expected}, no dust left behind.`);
+        });
+
+        it("Simple Trade: First-time trader without outcome ATAs", async () => {
+            console.log("   --- Testing first trade from a fresh wallet ---");
+            const { marketPDA, yesMint, noMint, vault } = await createMarketHelper("First Trade?");
+
+            const newcomer = Keypair.generate();
+            const tx = new anchor.web3.Transaction().add(SystemProgram.transfer({
+                fromPubkey: admin.publicKey, toPubkey: newcomer.publicKey, lamports: 0.05 * anchor.web3.LAMPORTS_PER_SOL,
+            }));
+            await provider.sendAndConfirm(tx);
+
+            const traderCollateral = (await getOrCreateAssociatedTokenAccount(provider.connection, newcomer, collateralMint, newcomer.publicKey)).address;
+            await mintTo(provider.connection, admin, collateralMint, traderCollateral, admin, 2_000_000);
+
+            const traderYes = getAssociatedTokenAddressSync(yesMint, newcomer.publicKey);
+            const traderNo = getAssociatedTokenAddressSync(noMint, newcomer.publicKey);
+            expect(await provider.connection.getAccountInfo(traderYes)).to.be.null;
+
+            // No initTraderVaults: buy_tokens creates the YES/NO accounts itself
+            await program.methods.buyTokens(new BN(1_000_000), true, new BN(0)).accounts({
+                trader: newcomer.publicKey, config: configPDA, market: marketPDA, yesMint, noMint, collateralMint, traderCollateral, traderYes, traderNo, vault, feeVault,
+                tokenProgram: TOKEN_PROGRAM_ID, associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID, systemProgram: SystemProgram.programId,
+            } as any).signers([newcomer]).rpc();
+
+            const bal = await provider.connection.getTokenAccountBalance(traderYes);
+            expect(Number(bal.value.amount)).to.be.greaterThan(0);
+            expect(await provider.connection.getAccountInfo(traderNo)).to.not.be.null;
+            console.log("   ✅ Outcome Accounts Created On First Trade.");
+        });
+
+        it("Simple Trade: Mint and burn complete sets", async () => {
+            console.log("   --- Testing YES + NO pairs ---");
+            const { marketPDA, yesMint, noMint, vault } = await createMarketHelper("Pairs?");
+            const pairAccounts = {
+                owner: admin.publicKey, config: configPDA, market: marketPDA, yesMint, noMint, collateralMint,
+                ownerCollateral: getAssociatedTokenAddressSync(collateralMint, admin.publicKey),
+                ownerYes: getAssociatedTokenAddressSync(yesMint, admin.publicKey),
+                ownerNo: getAssociatedTokenAddressSync(noMint, admin.publicKey),
+                vault, tokenProgram: TOKEN_PROGRAM_ID,
+            } as any;
+            const balance = async (account: PublicKey) => Number((await provider.connection.getTokenAccountBalance(account)).value.amount);
+
+            const yesBefore = await balance(pairAccounts.ownerYes);
+            const noBefore = await balance(pairAccounts.ownerNo);
+            await program.methods.mintPair(new BN(1_000_000), new BN(2_000_000)).accounts(pairAccounts).signers([admin]).rpc();
+            expect(await balance(pairAccounts.ownerYes)).to.equal(yesBefore + 1_000_000);
+            expect(await balance(pairAccounts.ownerNo)).to.equal(noBefore + 1_000_000);
+
+            const collateralBefore = await balance(pairAccounts.ownerCollateral);
+            await program.methods.burnPair(new BN(1_000_000), new BN(1)).accounts(pairAccounts).signers([admin]).rpc();
+            expect(await balance(pairAccounts.ownerYes)).to.equal(yesBefore);
+            expect(await balance(pairAccounts.ownerCollateral)).to.be.greaterThan(collateralBefore);
+
+            // Collateral-denominated form: a fixed deposit, burned straight back, never profits
+            const beforeSet = await balance(pairAccounts.ownerCollateral);
+            const yesBeforeSet = await balance(pairAccounts.ownerYes);
+            await program.methods.mintCompleteSet(new BN(1_000_000), new BN(1)).accounts(pairAccounts).signers([admin]).rpc();
+            const pairs = (await balance(pairAccounts.ownerYes)) - yesBeforeSet;
+            expect(pairs).to.be.greaterThan(0);
+            await program.methods.burnPair(new BN(pairs), new BN(1)).accounts(pairAccounts).signers([admin]).rpc();
+            expect(await balance(pairAccounts.ownerCollateral)).to.be.at.most(beforeSet);
+            console.log("   ✅ Complete Sets Round-Tripped.");
         });
 
         it("Simple Trade: Private Market", async () => {
@@ -239,7 +432,7 @@ describe("private_pnp_tests", () => {
             const privacyYes = getAssociatedTokenAddressSync(yesMint, privacyPos, true);
             const privacyNo = getAssociatedTokenAddressSync(noMint, privacyPos, true);
 
-            await program.methods.initPrivacyPosition(Array.from(entryCommitment) as any).accounts({
+            await program.methods.initPrivacyPosition(Array.from(entryCommitment) as any, null).accounts({
                 trader: traderB.publicKey, market: marketPDA, privacyPosition: privacyPos, yesMint, noMint, privacyYes, privacyNo, tokenProgram: TOKEN_PROGRAM_ID, associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID, systemProgram: SystemProgram.programId,
             } as any).signers([traderB]).rpc();
 
@@ -249,21 +442,26 @@ describe("private_pnp_tests", () => {
 
             console.log("   ✅ Privacy trade worked.");
             await waitForExpiry(marketPDA);
-            await program.methods.resolveMarket(true).accounts({ oracle: oracle.publicKey, market: marketPDA }).signers([oracle]).rpc();
+            await program.methods.resolveMarket(true, NO_EVIDENCE, NO_SOURCE).accounts({ oracle: oracle.publicKey, market: marketPDA }).signers([oracle]).rpc();
 
             const payoutSecret = crypto.randomBytes(32);
             const nonce = new BN(0);
             const payoutCommitment = hashCommitment(payoutSecret, freshWallet.publicKey, nonce);
-            const [privacyClaim] = PublicKey.findProgramAddressSync([Buffer.from("privacy_claim"), marketPDA.toBuffer(), payoutCommitment], program.programId);
+            const [privacyClaim] = PublicKey.findProgramAddressSync([Buffer.from("privacy_claim"), marketPDA.toBuffer(), payoutCommitment, nonce.toArrayLike(Buffer, "le", 8)], program.programId);
             const privacyVault = getAssociatedTokenAddressSync(collateralMint, privacyClaim, true);
 
-            await program.methods.initPrivacyClaim(Array.from(payoutCommitment) as any).accounts({
+            await program.methods.initPrivacyClaim(Array.from(payoutCommitment) as any, nonce).accounts({
                 user: traderB.publicKey, market: marketPDA, privacyClaim, collateralMint, privacyVault, tokenProgram: TOKEN_PROGRAM_ID, associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID, systemProgram: SystemProgram.programId,
             } as any).signers([traderB]).rpc();
 
-            await program.methods.redeemPrivacyPosition(Array.from(entryCommitment) as any, Array.from(payoutCommitment) as any).accounts({
+            await program.methods.redeemPrivacyPosition(Array.from(entryCommitment) as any, Array.from(payoutCommitment) as any, new BN(0)).accounts({
                 user: traderB.publicKey, config: configPDA, market: marketPDA, privacyPosition: privacyPos, privacyClaim, yesMint, noMint, collateralMint, privacyYes, privacyNo, vault, privacyVault, tokenProgram: TOKEN_PROGRAM_ID,
             } as any).signers([traderB]).rpc();
+            const lockedClaim = await program.account.privacyClaim.fetch(privacyClaim);
+            const pending = await program.methods.getMarketInfo().accounts({ config: configPDA, market: marketPDA } as any).view();
+            expect(pending.config.toBase58()).to.equal(configPDA.toBase58());
+            expect(pending.totalLockedInClaims.toNumber()).to.equal(lockedClaim.amount.toNumber());
+            expect(pending.yesReserves.add(pending.noReserves).toString()).to.equal(pending.reserves.toString());
 
             // The lock period for privacy claims still exists, wait for it
             const redeemWait = isLocalnet ? 12000 : 25000;
@@ -274,12 +472,20 @@ describe("private_pnp_tests", () => {
             }
             console.log(" ✅ Done.");
             const recipientCollateral = getAssociatedTokenAddressSync(collateralMint, freshWallet.publicKey);
+            // An unrelated wallet funds the recipient ATA so the relayer never does
+            await program.methods.prepareRecipient().accounts({
+                payer: traderA.publicKey, collateralMint, recipient: freshWallet.publicKey, recipientCollateral, tokenProgram: TOKEN_PROGRAM_ID, associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID, systemProgram: SystemProgram.programId,
+            } as any).signers([traderA]).rpc();
+
             await program.methods.claimPrivacy(Array.from(payoutSecret) as any, Array.from(payoutCommitment) as any).accounts({
-                claimant: relayer.publicKey, privacyClaim, collateralMint, privacyVault, recipientCollateral, recipientAccount: freshWallet.publicKey, tokenProgram: TOKEN_PROGRAM_ID, associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID, systemProgram: SystemProgram.programId,
+                claimant: relayer.publicKey, privacyClaim, market: marketPDA, collateralMint, privacyVault, recipientCollateral, recipientAccount: freshWallet.publicKey, tokenProgram: TOKEN_PROGRAM_ID,
             } as any).signers([relayer]).rpc();
 
             const finalBal = await getAccount(provider.connection, recipientCollateral);
             expect(Number(finalBal.amount)).to.be.greaterThan(0);
+            expect(await provider.connection.getAccountInfo(privacyClaim)).to.be.null;
+            expect(await provider.connection.getAccountInfo(privacyVault)).to.be.null;
+            expect((await program.account.market.fetch(marketPDA)).totalLockedInClaims.toNumber()).to.equal(0);
             console.log("   ✅ Privacy payout worked.");
         });
     });
@@ -295,14 +501,14 @@ describe("private_pnp_tests", () => {
             const payoutSecret = crypto.randomBytes(32);
             const nonce = new BN(0);
             const payoutCommitment = hashCommitment(payoutSecret, freshWallet.publicKey, nonce);
-            const [privacyClaim] = PublicKey.findProgramAddressSync([Buffer.from("privacy_claim"), marketPDA.toBuffer(), payoutCommitment], program.programId);
+            const [privacyClaim] = PublicKey.findProgramAddressSync([Buffer.from("privacy_claim"), marketPDA.toBuffer(), payoutCommitment, nonce.toArrayLike(Buffer, "le", 8)], program.programId);
 
             const thiefWallet = Keypair.generate();
             const thiefCollateral = getAssociatedTokenAddressSync(collateralMint, thiefWallet.publicKey);
 
             try {
                 await program.methods.claimPrivacy(Array.from(payoutSecret) as any, Array.from(payoutCommitment) as any).accounts({
-                    claimant: relayer.publicKey, privacyClaim, collateralMint, recipientCollateral: thiefCollateral, recipientAccount: thiefWallet.publicKey,
+                    claimant: relayer.publicKey, privacyClaim, market: marketPDA, collateralMint, recipientCollateral: thiefCollateral, recipientAccount: thiefWallet.publicKey,
                 } as any).signers([relayer]).rpc();
                 expect.fail("Relayer should not be able to divert funds!");
             } catch (e) {
@@ -315,18 +521,662 @@ describe("private_pnp_tests", () => {
             const { marketPDA } = await createMarketHelper("Math Error?");
             const wrongSecret = crypto.randomBytes(32);
             const rightSecret = crypto.randomBytes(32);
-            const commitment = hashCommitment(rightSecret, freshWallet.publicKey, new BN(0));
-            const [privacyClaim] = PublicKey.findProgramAddressSync([Buffer.from("privacy_claim"), marketPDA.toBuffer(), commitment], program.programId);
+            const nonce = new BN(0);
+            const commitment = hashCommitment(rightSecret, freshWallet.publicKey, nonce);
+            const [privacyClaim] = PublicKey.findProgramAddressSync([Buffer.from("privacy_claim"), marketPDA.toBuffer(), commitment, nonce.toArrayLike(Buffer, "le", 8)], program.programId);
 
             try {
                 await program.methods.claimPrivacy(Array.from(wrongSecret) as any, Array.from(commitment) as any).accounts({
-                    claimant: relayer.publicKey, privacyClaim, recipientAccount: freshWallet.publicKey,
+                    claimant: relayer.publicKey, privacyClaim, market: marketPDA, recipientAccount: freshWallet.publicKey,
                 } as any).signers([relayer]).rpc();
                 expect.fail("Should have failed with invalid secret!");
             } catch (e) {
                 console.log("   🛡️ Invalid Secret Blocked.");
             }
         });
+
+        it("Safety: Block rapid repeat trades during cooldown", async () => {
+            console.log("   --- Testing trade cooldown ---");
+            const { marketPDA, yesMint, noMint, vault } = await createMarketHelper("Cooldown?");
+
+            const traderCollateral = (await getOrCreateAssociatedTokenAccount(provider.connection, traderB, collateralMint, traderB.publicKey)).address;
+            await mintTo(provider.connection, admin, collateralMint, traderCollateral, admin, 10_000_000);
+            const traderYes = getAssociatedTokenAddressSync(yesMint, traderB.publicKey);
+            const traderNo = getAssociatedTokenAddressSync(noMint, traderB.publicKey);
+            await program.methods.initTraderVaults().accounts({
+                trader: traderB.publicKey, yesMint, noMint, traderYes, traderNo, tokenProgram: TOKEN_PROGRAM_ID, associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID, systemProgram: SystemProgram.programId,
+            } as any).signers([traderB]).rpc();
+
+            const buy = () => program.methods.buyTokens(new BN(1_000_000), true, new BN(0)).accounts({
+                trader: traderB.publicKey, config: configPDA, market: marketPDA, yesMint, noMint, collateralMint, traderCollateral, traderYes, traderNo, vault, feeVault, tokenProgram: TOKEN_PROGRAM_ID,
+            } as any).signers([traderB]).rpc();
+
+            await updateConfig({ tradeCooldownSeconds: new BN(3600) });
+            let err = "";
+            try {
+                await buy();
+                await buy();
+            } catch (e) {
+                err = String(e);
+            } finally {
+                await updateConfig({ tradeCooldownSeconds: new BN(0) });
+            }
+            expect(err).to.include("TradeCooldown");
+            console.log("   🛡️ Repeat Trade Blocked.");
+        });
+
+        it("Safety: Liquidity changes respect slippage floors", async () => {
+            console.log("   --- Testing liquidity slippage ---");
+            const { marketPDA, yesMint, noMint, vault } = await createMarketHelper("LP Slippage?");
+            const liquidityAccounts = {
+                provider: admin.publicKey, config: configPDA, market: marketPDA, yesMint, noMint, collateralMint,
+                providerCollateral: getAssociatedTokenAddressSync(collateralMint, admin.publicKey),
+                providerYes: getAssociatedTokenAddressSync(yesMint, admin.publicKey),
+                providerNo: getAssociatedTokenAddressSync(noMint, admin.publicKey),
+                vault, tokenProgram: TOKEN_PROGRAM_ID,
+            } as any;
+
+            const expectSlippage = async (call: Promise<string>) => {
+                let err = "";
+                try { await call; } catch (e) { err = String(e); }
+                expect(err).to.include("SlippageExceeded");
+            };
+
+            // Fresh 50/50 pool: 1 collateral mints ~0.707 shares, so asking for 1:1 must fail
+            await expectSlippage(program.methods.addLiquidity(new BN(1_000_000), new BN(1_000_000)).accounts(liquidityAccounts).signers([admin]).rpc());
+            const quote = await program.methods.quoteAddLiquidity(new BN(1_000_000)).accounts({ market: marketPDA } as any).view();
+            const yesBefore = Number((await provider.connection.getTokenAccountBalance(liquidityAccounts.providerYes)).value.amount);
+            await program.methods.addLiquidity(new BN(1_000_000), quote.sharesOut).accounts(liquidityAccounts).signers([admin]).rpc();
+            const yesAfter = Number((await provider.connection.getTokenAccountBalance(liquidityAccounts.providerYes)).value.amount);
+            expect(yesAfter - yesBefore).to.equal(quote.yesOut.toNumber());
+
+            await expectSlippage(program.methods.removeLiquidity(new BN(100_000), new BN(1_000_000)).accounts(liquidityAccounts).signers([admin]).rpc());
+            await program.methods.removeLiquidity(new BN(100_000), new BN(1)).accounts(liquidityAccounts).signers([admin]).rpc();
+
+            // With slippage enforced, a zero floor is refused outright
+            await updateConfig({ enforceSlippage: true });
+            try {
+                for (const call of [
+                    program.methods.addLiquidity(new BN(1_000_000), new BN(0)),
+                    program.methods.removeLiquidity(new BN(100_000), new BN(0)),
+                ]) {
+                    let err = "";
+                    try { await call.accounts(liquidityAccounts).signers([admin]).rpc(); } catch (e) { err = String(e); }
+                    expect(err).to.include("SlippageProtectionRequired");
+                }
+            } finally {
+                await updateConfig({ enforceSlippage: false });
+            }
+            console.log("   🛡️ LP Slippage Enforced.");
+        });
+
+        it("Safety: Rescue stray tokens but never collateral", async () => {
+            console.log("   --- Testing stuck token rescue ---");
+            const { marketPDA, vault } = await createMarketHelper("Rescue?");
+
+            const strayMint = await createMint(provider.connection, admin, admin.publicKey, null, 6);
+            const strayVault = (await getOrCreateAssociatedTokenAccount(provider.connection, admin, strayMint, marketPDA, true)).address;
+            await mintTo(provider.connection, admin, strayMint, strayVault, admin, 1_000);
+            const adminStray = (await getOrCreateAssociatedTokenAccount(provider.connection, admin, strayMint, admin.publicKey)).address;
+
+            await program.methods.rescueTokens(new BN(1_000)).accounts({
+                admin: admin.publicKey, config: configPDA, market: marketPDA, mint: strayMint, source: strayVault, destination: adminStray, tokenProgram: TOKEN_PROGRAM_ID,
+            } as any).signers([admin]).rpc();
+            const rescued = await provider.connection.getTokenAccountBalance(adminStray);
+            expect(Number(rescued.value.amount)).to.equal(1_000);
+
+            let err = "";
+            try {
+                await program.methods.rescueTokens(new BN(1)).accounts({
+                    admin: admin.publicKey, config: configPDA, market: marketPDA, mint: collateralMint, source: vault,
+                    destination: getAssociatedTokenAddressSync(collateralMint, admin.publicKey), tokenProgram: TOKEN_PROGRAM_ID,
+                } as any).signers([admin]).rpc();
+            } catch (e) {
+                err = String(e);
+            }
+            expect(err).to.include("CollateralNotRescuable");
+            console.log("   🛡️ Collateral Rescue Blocked.");
+        });
+
+        it("Safety: Enforce a market's own minimum trade", async () => {
+            console.log("   --- Testing per-market minimum trade ---");
+            const { marketPDA, yesMint, noMint, vault } = await createMarketHelper("Whale Market?", 2_000_000);
+
+            const traderCollateral = (await getOrCreateAssociatedTokenAccount(provider.connection, traderA, collateralMint, traderA.publicKey)).address;
+            await mintTo(provider.connection, admin, collateralMint, traderCollateral, admin, 1_000_000);
+            let err = "";
+            try {
+                await program.methods.buyTokens(new BN(1_000_000), true, new BN(0)).accounts({
+                    trader: traderA.publicKey, config: configPDA, market: marketPDA, yesMint, noMint, collateralMint, traderCollateral,
+                    traderYes: getAssociatedTokenAddressSync(yesMint, traderA.publicKey), traderNo: getAssociatedTokenAddressSync(noMint, traderA.publicKey), vault, feeVault, tokenProgram: TOKEN_PROGRAM_ID,
+                } as any).signers([traderA]).rpc();
+            } catch (e) {
+                err = String(e);
+            }
+            expect(err).to.include("BelowMinimumTrade");
+            console.log("   🛡️ Undersized Trade Blocked.");
+        });
+
+        it("Safety: Reject markets that end a second after creation", async () => {
+            console.log("   --- Testing minimum market duration ---");
+            const configState = await program.account.config.fetch(configPDA);
+            const [marketPDA] = PublicKey.findProgramAddressSync([Buffer.from("market"), configPDA.toBuffer(), configState.marketCount.toArrayLike(Buffer, "le", 8)], program.programId);
+
+            await updateConfig({ minMarketDuration: new BN(60) });
+            let err = "";
+            try {
+                await program.methods.createMarketState("Blink Market?", new BN(Math.floor(Date.now() / 1000) + 1), { proRata: {} }, { other: {} }, [], new BN(0), true, false, NO_SOURCE_HASH).accounts({
+                    creator: admin.publicKey, config: configPDA, market: marketPDA, collateralMint, systemProgram: SystemProgram.programId,
+                } as any).signers([admin]).rpc();
+            } catch (e) {
+                err = String(e);
+            } finally {
+                await updateConfig({ minMarketDuration: new BN(0) });
+            }
+            expect(err).to.include("DurationTooShort");
+            console.log("   🛡️ Zero-Length Market Blocked.");
+        });
+
+        it("Safety: Questions are capped at 256 bytes", async () => {
+            console.log("   --- Testing question length boundary ---");
+            const createWith = async (question: string) => {
+                const configState = await program.account.config.fetch(configPDA);
+                const [marketPDA] = PublicKey.findProgramAddressSync([Buffer.from("market"), configPDA.toBuffer(), configState.marketCount.toArrayLike(Buffer, "le", 8)], program.programId);
+                await program.methods.createMarketState(question, new BN(Math.floor(Date.now() / 1000) + 60), { proRata: {} }, { other: {} }, [], new BN(0), true, false, NO_SOURCE_HASH).accounts({
+                    creator: admin.publicKey, config: configPDA, market: marketPDA, collateralMint, systemProgram: SystemProgram.programId,
+                } as any).signers([admin]).rpc();
+                return marketPDA;
+            };
+
+            const longest = "?".repeat(256);
+            const marketPDA = await createWith(longest);
+            expect((await program.account.market.fetch(marketPDA)).question).to.equal(longest);
+
+            let err = "";
+            try {
+                await createWith("?".repeat(257));
+            } catch (e) {
+                err = String(e);
+            }
+            expect(err).to.include("QuestionTooLong");
+            console.log("   🛡️ 256 Bytes Fit, 257 Rejected.");
+        });
+
+        it("Safety: Reject collateral with too many decimals", async () => {
+            console.log("   --- Testing collateral decimals range ---");
+            const createWith = async (decimals: number) => {
+                const mint = await createMint(provider.connection, admin, admin.publicKey, null, decimals);
+                const configState = await program.account.config.fetch(configPDA);
+                const [marketPDA] = PublicKey.findProgramAddressSync([Buffer.from("market"), configPDA.toBuffer(), configState.marketCount.toArrayLike(Buffer, "le", 8)], program.programId);
+                await program.methods.createMarketState(`Decimals ${decimals}?`, new BN(Math.floor(Date.now() / 1000) + 60), { proRata: {} }, { other: {} }, [], new BN(0), true, false, NO_SOURCE_HASH).accounts({
+                    creator: admin.publicKey, config: configPDA, market: marketPDA, collateralMint: mint, systemProgram: SystemProgram.programId,
+                } as any).signers([admin]).rpc();
+            };
+
+            await createWith(0);
+            await createWith(9);
+            let err = "";
+            try {
+                await createWith(18);
+            } catch (e) {
+                err = String(e);
+            }
+            expect(err).to.include("CollateralDecimalsTooHigh");
+            console.log("   🛡️ 18-Decimal Collateral Refused.");
+        });
+
+        it("Safety: Fees cannot be configured below the floor", async () => {
+            console.log("   --- Testing fee floor on config updates ---");
+            let err = "";
+            try {
+                await updateConfig({ balancingFeeBps: new BN(0) });
+            } catch (e) {
+                err = String(e);
+            }
+            expect(err).to.include("FeeTooLow");
+            console.log("   🛡️ Zero Fee Refused.");
+        });
+
+        it("Safety: Reject fills far from a reference price", async () => {
+            console.log("   --- Testing reference price band ---");
+            const { marketPDA, yesMint, noMint, vault } = await createMarketHelper("Reference Odds?");
+            const traderCollateral = (await getOrCreateAssociatedTokenAccount(provider.connection, traderB, collateralMint, traderB.publicKey)).address;
+            await mintTo(provider.connection, admin, collateralMint, traderCollateral, admin, 1_000_000);
+
+            const [priceReference] = PublicKey.findProgramAddressSync([Buffer.from("price_reference"), marketPDA.toBuffer(), relayer.publicKey.toBuffer()], program.programId);
+            const publish = (yesBps: number) => program.methods.publishPriceReference(new BN(yesBps), new BN(10_000 - yesBps)).accounts({
+                publisher: relayer.publicKey, market: marketPDA, priceReference, systemProgram: SystemProgram.programId,
+            } as any).signers([relayer]).rpc();
+            const buy = () => program.methods.buyTokens(new BN(100_000), true, new BN(0)).accounts({
+                trader: traderB.publicKey, config: configPDA, market: marketPDA, yesMint, noMint, collateralMint, traderCollateral,
+                traderYes: getAssociatedTokenAddressSync(yesMint, traderB.publicKey), traderNo: getAssociatedTokenAddressSync(noMint, traderB.publicKey), vault, feeVault, priceReference, tokenProgram: TOKEN_PROGRAM_ID,
+            } as any).signers([traderB]).rpc();
+
+            // A balanced pool fills at ≈7071 bps; a UI showing 30% odds is far off-market
+            await publish(3_000);
+            let err = "";
+            try {
+                await buy();
+            } catch (e) {
+                err = String(e);
+            }
+            expect(err).to.include("PriceOffReference");
+
+            await publish(7_071);
+            await buy();
+            const bal = await provider.connection.getTokenAccountBalance(getAssociatedTokenAddressSync(yesMint, traderB.publicKey));
+            expect(Number(bal.value.amount)).to.be.greaterThan(0);
+            console.log("   🛡️ Off-Market Fill Blocked.");
+        });
+
+        it("Safety: Block funding into foreign token accounts", async () => {
+            console.log("   --- Testing funding account checks ---");
+            const configState = await program.account.config.fetch(configPDA);
+            const [marketPDA] = PublicKey.findProgramAddressSync([Buffer.from("market"), configPDA.toBuffer(), configState.marketCount.toArrayLike(Buffer, "le", 8)], program.programId);
+            const [yesMint] = PublicKey.findProgramAddressSync([Buffer.from("yes_mint"), marketPDA.toBuffer()], program.programId);
+            const [noMint] = PublicKey.findProgramAddressSync([Buffer.from("no_mint"), marketPDA.toBuffer()], program.programId);
+            const vault = getAssociatedTokenAddressSync(collateralMint, marketPDA, true);
+            const adminYes = getAssociatedTokenAddressSync(yesMint, admin.publicKey);
+            const adminNo = getAssociatedTokenAddressSync(noMint, admin.publicKey);
+
+            await program.methods.createMarketState("Hijack Funding?", new BN(Math.floor(Date.now() / 1000) + 60), { proRata: {} }, { other: {} }, [], new BN(0), true, false, NO_SOURCE_HASH).accounts({
+                creator: admin.publicKey, config: configPDA, market: marketPDA, collateralMint, systemProgram: SystemProgram.programId,
+            } as any).signers([admin]).rpc();
+            await program.methods.createMarketMints().accounts({
+                creator: admin.publicKey, config: configPDA, market: marketPDA, collateralMint, yesMint, noMint, tokenProgram: TOKEN_PROGRAM_ID, systemProgram: SystemProgram.programId,
+            } as any).signers([admin]).rpc();
+            await program.methods.createMarketVaults().accounts({
+                creator: admin.publicKey, config: configPDA, market: marketPDA, yesMint, noMint, collateralMint, vault, creatorYes: adminYes, creatorNo: adminNo, feeVault: getAssociatedTokenAddressSync(collateralMint, configPDA, true), tokenProgram: TOKEN_PROGRAM_ID, associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID, systemProgram: SystemProgram.programId,
+            } as any).signers([admin]).rpc();
+
+            // An attacker-owned YES account must not receive the creator's minted tokens
+            const attackerYes = (await getOrCreateAssociatedTokenAccount(provider.connection, admin, yesMint, traderB.publicKey)).address;
+            const adminCollateral = getAssociatedTokenAddressSync(collateralMint, admin.publicKey);
+            let err = "";
+            try {
+                await program.methods.fundMarket(new BN(50_000_000)).accounts({
+                    creator: admin.publicKey, config: configPDA, market: marketPDA, yesMint, noMint, collateralMint, creatorCollateral: adminCollateral, vault, creatorYes: attackerYes, creatorNo: adminNo, tokenProgram: TOKEN_PROGRAM_ID,
+                } as any).signers([admin]).rpc();
+            } catch (e) {
+                err = String(e);
+            }
+            expect(err).to.include("InvalidFundingAccount");
+
+            // A market the creator funds alone takes no pledges, so nobody can park one to block it
+            err = "";
+            try {
+                await program.methods.contributeFunding(new BN(1)).accounts({
+                    provider: traderB.publicKey, market: marketPDA,
+                    liquidityPosition: PublicKey.findProgramAddressSync([Buffer.from("liquidity_position"), marketPDA.toBuffer(), traderB.publicKey.toBuffer()], program.programId)[0],
+                    collateralMint, providerCollateral: getAssociatedTokenAddressSync(collateralMint, traderB.publicKey), vault, tokenProgram: TOKEN_PROGRAM_ID,
+                } as any).signers([traderB]).rpc();
+            } catch (e) {
+                err = String(e);
+            }
+            expect(err).to.include("CrowdfundingDisabled");
+            console.log("   🛡️ Funding Hijack Blocked.");
+        });
+
+        it("Crowdfund: Two contributors fund one market", async () => {
+            console.log("   --- Testing crowdfunded liquidity ---");
+            const configState = await program.account.config.fetch(configPDA);
+            const [marketPDA] = PublicKey.findProgramAddressSync([Buffer.from("market"), configPDA.toBuffer(), configState.marketCount.toArrayLike(Buffer, "le", 8)], program.programId);
+            const [yesMint] = PublicKey.findProgramAddressSync([Buffer.from("yes_mint"), marketPDA.toBuffer()], program.programId);
+            const [noMint] = PublicKey.findProgramAddressSync([Buffer.from("no_mint"), marketPDA.toBuffer()], program.programId);
+            const vault = getAssociatedTokenAddressSync(collateralMint, marketPDA, true);
+
+            await program.methods.createMarketState("Community Funded?", new BN(Math.floor(Date.now() / 1000) + 60), { proRata: {} }, { other: {} }, [], new BN(0), true, true, NO_SOURCE_HASH).accounts({
+                creator: admin.publicKey, config: configPDA, market: marketPDA, collateralMint, systemProgram: SystemProgram.programId,
+            } as any).signers([admin]).rpc();
+            await program.methods.createMarketMints().accounts({
+                creator: admin.publicKey, config: configPDA, market: marketPDA, collateralMint, yesMint, noMint, tokenProgram: TOKEN_PROGRAM_ID, systemProgram: SystemProgram.programId,
+            } as any).signers([admin]).rpc();
+            await program.methods.createMarketVaults().accounts({
+                creator: admin.publicKey, config: configPDA, market: marketPDA, yesMint, noMint, collateralMint, vault, creatorYes: getAssociatedTokenAddressSync(yesMint, admin.publicKey), creatorNo: getAssociatedTokenAddressSync(noMint, admin.publicKey), feeVault: getAssociatedTokenAddressSync(collateralMint, configPDA, true), tokenProgram: TOKEN_PROGRAM_ID, associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID, systemProgram: SystemProgram.programId,
+            } as any).signers([admin]).rpc();
+
+            const positionOf = (who: Keypair) => PublicKey.findProgramAddressSync([Buffer.from("liquidity_position"), marketPDA.toBuffer(), who.publicKey.toBuffer()], program.programId)[0];
+            const contribute = async (who: Keypair, amount: number) => {
+                const providerCollateral = (await getOrCreateAssociatedTokenAccount(provider.connection, who, collateralMint, who.publicKey)).address;
+                await mintTo(provider.connection, admin, collateralMint, providerCollateral, admin, amount);
+                await program.methods.contributeFunding(new BN(amount)).accounts({
+                    provider: who.publicKey, market: marketPDA, liquidityPosition: positionOf(who), collateralMint, providerCollateral, vault, tokenProgram: TOKEN_PROGRAM_ID,
+                } as any).signers([who]).rpc();
+            };
+            await contribute(traderA, 600_000);
+
+            // Opting in to pledges rules out funding it alone
+            let err = "";
+            try {
+                await program.methods.fundMarket(new BN(50_000_000)).accounts({
+                    creator: admin.publicKey, config: configPDA, market: marketPDA, yesMint, noMint, collateralMint, creatorCollateral: getAssociatedTokenAddressSync(collateralMint, admin.publicKey), vault, creatorYes: getAssociatedTokenAddressSync(yesMint, admin.publicKey), creatorNo: getAssociatedTokenAddressSync(noMint, admin.publicKey), tokenProgram: TOKEN_PROGRAM_ID,
+                } as any).signers([admin]).rpc();
+            } catch (e) {
+                err = String(e);
+            }
+            expect(err).to.include("CrowdfundingOpen");
+
+            // Below `min_liquidity` the market cannot open yet
+            err = "";
+            try {
+                await program.methods.finalizeFunding().accounts({ payer: relayer.publicKey, market: marketPDA } as any).signers([relayer]).rpc();
+            } catch (e) {
+                err = String(e);
+            }
+            expect(err).to.include("BelowMinimumLiquidity");
+
+            await contribute(traderB, 1_200_000);
+            await program.methods.finalizeFunding().accounts({ payer: relayer.publicKey, market: marketPDA } as any).signers([relayer]).rpc();
+            const funded = await program.account.market.fetch(marketPDA);
+            expect(funded.funded).to.be.true;
+            expect(funded.reserves.toNumber()).to.equal(1_800_000);
+
+            const claimed: number[] = [];
+            for (const who of [traderA, traderB]) {
+                const providerYes = getAssociatedTokenAddressSync(yesMint, who.publicKey);
+                const providerNo = getAssociatedTokenAddressSync(noMint, who.publicKey);
+                const before = await provider.connection.getAccountInfo(providerYes) ? Number((await getAccount(provider.connection, providerYes)).amount) : 0;
+                await program.methods.claimFundingTokens().accounts({
+                    provider: who.publicKey, market: marketPDA, liquidityPosition: positionOf(who), yesMint, noMint, providerYes, providerNo, tokenProgram: TOKEN_PROGRAM_ID, associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID, systemProgram: SystemProgram.programId,
+                } as any).signers([who]).rpc();
+                claimed.push(Number((await getAccount(provider.connection, providerYes)).amount) - before);
+            }
+            expect(claimed[1]).to.be.closeTo(2 * claimed[0], 1);
+            expect(claimed[0] + claimed[1]).to.be.at.most(funded.yesSupply.toNumber());
+            expect(await program.methods.assertMarketConsistent(new BN(1)).accounts({ market: marketPDA, vault } as any).view()).to.be.true;
+            console.log("   ✅ Crowdfunded Market Opened.");
+        });
+
+        it("Safety: Only the creator can transfer market ownership", async () => {
+            console.log("   --- Testing ownership transfer ---");
+            const { marketPDA } = await createMarketHelper("Hand Over?");
+
+            let err = "";
+            try {
+                await program.methods.transferMarketOwnership(traderB.publicKey).accounts({ creator: traderB.publicKey, market: marketPDA } as any).signers([traderB]).rpc();
+            } catch (e) {
+                err = String(e);
+            }
+            expect(err).to.include("Unauthorized");
+
+            await program.methods.transferMarketOwnership(traderA.publicKey).accounts({ creator: admin.publicKey, market: marketPDA } as any).signers([admin]).rpc();
+            expect((await program.account.market.fetch(marketPDA)).creator.toBase58()).to.equal(traderA.publicKey.toBase58());
+            console.log("   🛡️ Ownership Transfer Gated.");
+        });
+
+        it("Safety: Lock the creator's initial liquidity", async () => {
+            console.log("   --- Testing creator liquidity lock ---");
+            await updateConfig({ creatorLiquidityLockSeconds: new BN(3600) });
+            let market: Awaited<ReturnType<typeof createMarketHelper>>;
+            try {
+                market = await createMarketHelper("Locked Liquidity?");
+            } finally {
+                await updateConfig({ creatorLiquidityLockSeconds: new BN(0) });
+            }
+
+            const state = await program.account.market.fetch(market.marketPDA);
+            expect(state.lockedCreatorTokens.toNumber()).to.be.greaterThan(0);
+            expect(state.yesSupply.toNumber()).to.equal(state.lockedCreatorTokens.toNumber());
+
+            let err = "";
+            try {
+                await program.methods.unlockCreatorLiquidity().accounts({
+                    creator: admin.publicKey, config: configPDA, market: market.marketPDA, yesMint: market.yesMint, noMint: market.noMint,
+                    creatorYes: getAssociatedTokenAddressSync(market.yesMint, admin.publicKey), creatorNo: getAssociatedTokenAddressSync(market.noMint, admin.publicKey), tokenProgram: TOKEN_PROGRAM_ID,
+                } as any).signers([admin]).rpc();
+            } catch (e) {
+                err = String(e);
+            }
+            expect(err).to.include("StillLocked");
+            console.log("   🛡️ Fund-and-Dump Blocked.");
+        });
+
+        it("Safety: Block resolution while paused", async () => {
+            console.log("   --- Testing paused resolution ---");
+            const { marketPDA } = await createMarketHelper("Paused Resolution?");
+            await waitForExpiry(marketPDA);
+
+            await updateConfig({ paused: true });
+            let err = "";
+            try {
+                await program.methods.resolveMarket(true, NO_EVIDENCE, NO_SOURCE).accounts({ oracle: oracle.publicKey, market: marketPDA }).signers([oracle]).rpc();
+            } catch (e) {
+                err = String(e);
+            } finally {
+                await updateConfig({ paused: false });
+            }
+            expect(err).to.include("ProtocolPaused");
+            console.log("   🛡️ Paused Resolution Blocked.");
+        });
+
+        it("Safety: Losers can sell during the exit window, winners redeem after", async () => {
+            console.log("   --- Testing post-resolution exit window ---");
+            await updateConfig({ postResolutionTradeWindow: new BN(3600) });
+            try {
+                const { marketPDA, yesMint, noMint, vault } = await createMarketHelper("Exit Window?", 0, true, NO_SOURCE_HASH, { fixedUnit: {} });
+                const traderCollateral = (await getOrCreateAssociatedTokenAccount(provider.connection, traderA, collateralMint, traderA.publicKey)).address;
+                await mintTo(provider.connection, admin, collateralMint, traderCollateral, admin, 5_000_000);
+                const traderYes = getAssociatedTokenAddressSync(yesMint, traderA.publicKey);
+                const traderNo = getAssociatedTokenAddressSync(noMint, traderA.publicKey);
+                await program.methods.buyTokens(new BN(5_000_000), true, new BN(0)).accounts({
+                    trader: traderA.publicKey, config: configPDA, market: marketPDA, yesMint, noMint, collateralMint, traderCollateral, traderYes, traderNo, vault, feeVault, tokenProgram: TOKEN_PROGRAM_ID,
+                } as any).signers([traderA]).rpc();
+                await waitForExpiry(marketPDA);
+                await program.methods.resolveMarket(true, NO_EVIDENCE, NO_SOURCE).accounts({ oracle: oracle.publicKey, market: marketPDA }).signers([oracle]).rpc();
+
+                let err = "";
+                try {
+                    await program.methods.redeem().accounts({
+                        user: traderA.publicKey, config: configPDA, market: marketPDA, yesMint, noMint, collateralMint, userYes: traderYes, userNo: traderNo, userCollateral: traderCollateral, vault, tokenProgram: TOKEN_PROGRAM_ID,
+                    } as any).signers([traderA]).rpc();
+                } catch (e) {
+                    err = String(e);
+                }
+                expect(err).to.include("ExitWindowOpen");
+
+                // The creator's NO tokens lost, but can still be sold back to the curve
+                const adminCollateral = getAssociatedTokenAddressSync(collateralMint, admin.publicKey);
+                const adminYes = getAssociatedTokenAddressSync(yesMint, admin.publicKey);
+                const adminNo = getAssociatedTokenAddressSync(noMint, admin.publicKey);
+                const losing = Number((await provider.connection.getTokenAccountBalance(adminNo)).value.amount);
+                await program.methods.sellTokens(new BN(Math.floor(losing / 2)), false, new BN(0)).accounts({
+                    trader: admin.publicKey, config: configPDA, market: marketPDA, yesMint, noMint, collateralMint, traderCollateral: adminCollateral, traderYes: adminYes, traderNo: adminNo, vault, feeVault, tokenProgram: TOKEN_PROGRAM_ID,
+                } as any).signers([admin]).rpc();
+                const market = await program.account.market.fetch(marketPDA);
+                expect(market.reserves.toNumber()).to.be.at.least(market.yesSupply.toNumber());
+
+                err = "";
+                try {
+                    await program.methods.sellTokens(new BN(1_000_000), true, new BN(0)).accounts({
+                        trader: traderA.publicKey, config: configPDA, market: marketPDA, yesMint, noMint, collateralMint, traderCollateral, traderYes, traderNo, vault, feeVault, tokenProgram: TOKEN_PROGRAM_ID,
+                    } as any).signers([traderA]).rpc();
+                } catch (e) {
+                    err = String(e);
+                }
+                expect(err).to.include("NotLosingSide");
+            } finally {
+                await updateConfig({ postResolutionTradeWindow: new BN(0) });
+            }
+            console.log("   🛡️ Winners Wait Out the Exit Window.");
+        });
+
+        it("Safety: Slash a disputed oracle bond, return it otherwise", async () => {
+            console.log("   --- Testing oracle bond ---");
+            const [oracleBond] = PublicKey.findProgramAddressSync([Buffer.from("oracle_bond"), oracle.publicKey.toBuffer()], program.programId);
+            const bondVault = getAssociatedTokenAddressSync(collateralMint, oracleBond, true);
+            const oracleCollateral = (await getOrCreateAssociatedTokenAccount(provider.connection, admin, collateralMint, oracle.publicKey)).address;
+            await mintTo(provider.connection, admin, collateralMint, oracleCollateral, admin, 2_000_000);
+            await program.methods.depositOracleBond(new BN(2_000_000)).accounts({
+                oracle: oracle.publicKey, config: configPDA, oracleBond, collateralMint, oracleCollateral, bondVault, tokenProgram: TOKEN_PROGRAM_ID, associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID, systemProgram: SystemProgram.programId,
+            } as any).signers([oracle]).rpc();
+
+            const { marketPDA } = await createMarketHelper("Bonded Resolution?");
+            await waitForExpiry(marketPDA);
+            await updateConfig({ oracleBond: new BN(1_000_000), disputeWindowSeconds: new BN(3) });
+            try {
+                let err = "";
+                try {
+                    await program.methods.resolveMarket(true, NO_EVIDENCE, NO_SOURCE).accounts({ oracle: oracle.publicKey, market: marketPDA }).signers([oracle]).rpc();
+                } catch (e) {
+                    err = String(e);
+                }
+                expect(err).to.include("InsufficientOracleBond");
+                await program.methods.resolveMarket(true, NO_EVIDENCE, NO_SOURCE).accounts({ oracle: oracle.publicKey, market: marketPDA, oracleBond } as any).signers([oracle]).rpc();
+
+                // Dispute upheld inside the window: the bond goes to the fee vault
+                await program.methods.slashOracleBond().accounts({
+                    admin: admin.publicKey, config: configPDA, market: marketPDA, oracleBond, collateralMint, bondVault, feeVault, tokenProgram: TOKEN_PROGRAM_ID,
+                } as any).signers([admin]).rpc();
+                expect((await program.account.oracleBond.fetch(oracleBond)).amount.toNumber()).to.equal(1_000_000);
+
+                // Undisputed stake comes back once the window closes
+                const withdraw = () => program.methods.withdrawOracleBond(new BN(1_000_000)).accounts({
+                    oracle: oracle.publicKey, config: configPDA, oracleBond, collateralMint, oracleCollateral, bondVault, tokenProgram: TOKEN_PROGRAM_ID,
+                } as any).signers([oracle]).rpc();
+                err = "";
+                try {
+                    await withdraw();
+                } catch (e) {
+                    err = String(e);
+                }
+                expect(err).to.include("BondLocked");
+                await new Promise(r => setTimeout(r, 4000));
+                await withdraw();
+                expect(Number((await provider.connection.getTokenAccountBalance(oracleCollateral)).value.amount)).to.equal(1_000_000);
+            } finally {
+                await updateConfig({ oracleBond: new BN(0), disputeWindowSeconds: new BN(3 * 24 * 60 * 60) });
+            }
+            console.log("   🛡️ Oracle Bond Slashed and Returned.");
+        });
+
+        it("Safety: Cap a single dark pool position", async () => {
+            console.log("   --- Testing privacy position cap ---");
+            const { marketPDA, yesMint, noMint, vault } = await createMarketHelper("Whale Hiding?");
+            const traderCollateral = (await getOrCreateAssociatedTokenAccount(provider.connection, traderA, collateralMint, traderA.publicKey)).address;
+            await mintTo(provider.connection, admin, collateralMint, traderCollateral, admin, 5_000_000);
+
+            const commitment = crypto.randomBytes(32);
+            const [privacyPos] = PublicKey.findProgramAddressSync([Buffer.from("privacy_position"), marketPDA.toBuffer(), commitment], program.programId);
+            const privacyYes = getAssociatedTokenAddressSync(yesMint, privacyPos, true);
+            const privacyNo = getAssociatedTokenAddressSync(noMint, privacyPos, true);
+            await program.methods.initPrivacyPosition(Array.from(commitment) as any, null).accounts({
+                trader: traderA.publicKey, market: marketPDA, privacyPosition: privacyPos, yesMint, noMint, privacyYes, privacyNo, tokenProgram: TOKEN_PROGRAM_ID, associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID, systemProgram: SystemProgram.programId,
+            } as any).signers([traderA]).rpc();
+            const tradePrivacy = (amount: number) => program.methods.tradePrivacy(Array.from(commitment) as any, new BN(amount), true).accounts({
+                trader: traderA.publicKey, config: configPDA, market: marketPDA, privacyPosition: privacyPos, yesMint, noMint, collateralMint, traderCollateral, vault, privacyYes, privacyNo, tokenProgram: TOKEN_PROGRAM_ID,
+            } as any).signers([traderA]).rpc();
+
+            await updateConfig({ maxPrivacyPosition: new BN(2_000_000) });
+            let err = "";
+            try {
+                await tradePrivacy(2_000_000);
+                await tradePrivacy(1);
+            } catch (e) {
+                err = String(e);
+            } finally {
+                await updateConfig({ maxPrivacyPosition: new BN(0) });
+            }
+            expect(err).to.include("PositionTooLarge");
+            const pos = await program.account.privacyPosition.fetch(privacyPos);
+            expect(pos.collateralDeposited.toNumber()).to.equal(2_000_000);
+            console.log("   🛡️ Oversized Ghost Position Blocked.");
+        });
+
+        it("Safety: Only the admin can rotate the oracle", async () => {
+            console.log("   --- Testing oracle rotation ---");
+            let err = "";
+            try {
+                await program.methods.setOracle(traderA.publicKey).accounts({ admin: traderA.publicKey, config: configPDA } as any).signers([traderA]).rpc();
+            } catch (e) {
+                err = String(e);
+            }
+            expect(err).to.include("Unauthorized");
+
+            const standIn = Keypair.generate().publicKey;
+            await program.methods.setOracle(standIn).accounts({ admin: admin.publicKey, config: configPDA } as any).signers([admin]).rpc();
+            expect((await program.account.config.fetch(configPDA)).oracle.toBase58()).to.equal(standIn.toBase58());
+            await program.methods.setOracle(oracle.publicKey).accounts({ admin: admin.publicKey, config: configPDA } as any).signers([admin]).rpc();
+            console.log("   🛡️ Oracle Rotation Restricted to Admin.");
+        });
+
+        it("Safety: Relayed resolution needs the oracle's signature", async () => {
+            console.log("   --- Testing signed attestations ---");
+            const { marketPDA } = await createMarketHelper("Attested Resolution?");
+            await waitForExpiry(marketPDA);
+
+            const marketState = await program.account.market.fetch(marketPDA);
+            const message = Buffer.concat([
+                marketPDA.toBuffer(),
+                marketState.id.toArrayLike(Buffer, "le", 8),
+                Buffer.from([1]),
+                new BN(Math.floor(Date.now() / 1000) + 600).toArrayLike(Buffer, "le", 8),
+                Buffer.alloc(32),
+            ]);
+            const relay = (signer: Keypair) => {
+                const ed25519Ix = Ed25519Program.createInstructionWithPrivateKey({ privateKey: signer.secretKey, message });
+                const signature = ed25519Ix.data.subarray(16 + 32, 16 + 32 + 64);
+                return program.methods.resolveWithAttestation(true, Array.from(signature) as any, message).accounts({
+                    relayer: relayer.publicKey, market: marketPDA, instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
+                } as any).preInstructions([ed25519Ix]).signers([relayer]).rpc();
+            };
+
+            let err = "";
+            try {
+                await relay(Keypair.generate());
+            } catch (e) {
+                err = String(e);
+            }
+            expect(err).to.include("WrongSigner");
+
+            await relay(oracle);
+            const resolved = await program.account.market.fetch(marketPDA);
+            expect(resolved.outcome).to.deep.equal({ yes: {} });
+            console.log("   🛡️ Only Oracle-Signed Verdicts Resolve.");
+        });
+
+        it("Check: Correlated markets resolve in one batch", async () => {
+            console.log("   --- Testing batch resolution ---");
+            const first = await createMarketHelper("Team A wins the final?");
+            const second = await createMarketHelper("Final goes to extra time?");
+            await waitForExpiry(second.marketPDA);
+
+            const markets = [first.marketPDA, second.marketPDA].map(pubkey => ({ pubkey, isWritable: true, isSigner: false }));
+            let err = "";
+            try {
+                await program.methods.resolveBatch([true], NO_EVIDENCE).accounts({ oracle: oracle.publicKey } as any).remainingAccounts(markets).signers([oracle]).rpc();
+            } catch (e) {
+                err = String(e);
+            }
+            expect(err).to.include("InvalidBatch");
+
+            await program.methods.resolveBatch([true, false], NO_EVIDENCE).accounts({ oracle: oracle.publicKey } as any).remainingAccounts(markets).signers([oracle]).rpc();
+            const [a, b] = await Promise.all([program.account.market.fetch(first.marketPDA), program.account.market.fetch(second.marketPDA)]);
+            expect(a.outcome).to.deep.equal({ yes: {} });
+            expect(b.outcome).to.deep.equal({ no: {} });
+            expect(a.resolvedAt.toNumber()).to.equal(b.resolvedAt.toNumber());
+            console.log("   ✅ Both Markets Settled Together.");
+        });
+
+        it("Safety: Block resolving an unfunded market", async () => {
+            console.log("   --- Testing unfunded resolution ---");
+            const configState = await program.account.config.fetch(configPDA);
+            const [marketPDA] = PublicKey.findProgramAddressSync([Buffer.from("market"), configPDA.toBuffer(), configState.marketCount.toArrayLike(Buffer, "le", 8)], program.programId);
+
+            // Step 1 only: the market exists but never receives liquidity
+            await program.methods.createMarketState("Never Funded?", new BN(Math.floor(Date.now() / 1000) + 5), { proRata: {} }, { other: {} }, [], new BN(0), true, false, NO_SOURCE_HASH).accounts({
+                creator: admin.publicKey, config: configPDA, market: marketPDA, collateralMint: collateralMint, systemProgram: SystemProgram.programId,
+            } as any).signers([admin]).rpc();
+
+            await waitForExpiry(marketPDA);
+            let err = "";
+            try {
+                await program.methods.resolveMarket(true, NO_EVIDENCE, NO_SOURCE).accounts({ oracle: oracle.publicKey, market: marketPDA }).signers([oracle]).rpc();
+            } catch (e) {
+                err = String(e);
+            }
+            expect(err).to.include("MarketNotFunded");
+            console.log("   🛡️ Unfunded Resolution Blocked.");
+        });
     });
 
     describe("Privacy Verification", () => {
@@ -356,9 +1206,147 @@ describe("private_pnp_tests", () => {
 
             const state = await program.account.shieldedPosition.fetch(pos);
             expect(state.shieldedAmount.toNumber()).to.equal(1_000_000);
+
+            // Wallets can display the bet without learning its direction
+            const summary = await program.methods.getShieldedPositionPublic(Array.from(commitment) as any).accounts({ market: marketPDA, shieldedPosition: pos } as any).view();
+            expect(summary.market.equals(marketPDA)).to.be.true;
+            expect(summary.shieldedAmount.toNumber()).to.equal(1_000_000);
+            expect(summary.collateralDeposited.toNumber()).to.equal(1_000_000);
+            expect(Object.keys(summary)).to.not.include("directionCipher");
             console.log("   ✅ Position Secured. Choice is Private.");
         });
 
+        it("Safety: Trades after end time fail, end_market records the close", async () => {
+            console.log("   --- Testing public trades at expiry ---");
+            const { marketPDA, yesMint, noMint, vault } = await createMarketHelper("Last Second Trade?");
+            const traderCollateral = (await getOrCreateAssociatedTokenAccount(provider.connection, traderB, collateralMint, traderB.publicKey)).address;
+            await mintTo(provider.connection, admin, collateralMint, traderCollateral, admin, 2_000_000);
+            const tradeAccounts = {
+                trader: traderB.publicKey, config: configPDA, market: marketPDA, yesMint, noMint, collateralMint, traderCollateral,
+                traderYes: getAssociatedTokenAddressSync(yesMint, traderB.publicKey), traderNo: getAssociatedTokenAddressSync(noMint, traderB.publicKey), vault, feeVault, tokenProgram: TOKEN_PROGRAM_ID,
+            } as any;
+            await program.methods.buyTokens(new BN(1_000_000), true, new BN(0)).accounts(tradeAccounts).signers([traderB]).rpc();
+            await waitForExpiry(marketPDA);
+
+            // A failed transaction rolls back every write, so the trade cannot flip the status on its way out
+            const expectError = async (call: Promise<string>, code: string) => {
+                let err = "";
+                try { await call; } catch (e) { err = String(e); }
+                expect(err).to.include(code);
+            };
+            await expectError(program.methods.buyTokens(new BN(1_000_000), true, new BN(0)).accounts(tradeAccounts).signers([traderB]).rpc(), "MarketEnded");
+            await expectError(program.methods.sellTokens(new BN(1_000), true, new BN(0)).accounts(tradeAccounts).signers([traderB]).rpc(), "MarketEnded");
+            expect((await program.account.market.fetch(marketPDA)).status).to.deep.equal({ active: {} });
+
+            await program.methods.endMarket().accounts({ market: marketPDA } as any).rpc();
+            expect((await program.account.market.fetch(marketPDA)).status).to.deep.equal({ ended: {} });
+            await expectError(program.methods.buyTokens(new BN(1_000_000), true, new BN(0)).accounts(tradeAccounts).signers([traderB]).rpc(), "MarketNotActive");
+            console.log("   🛡️ Expired Trades Refused, Close Recorded.");
+        });
+
+        it("Trading: Swapping sides pays one fee and moves no collateral", async () => {
+            console.log("   --- Testing outcome swap ---");
+            const { marketPDA, yesMint, noMint, vault } = await createMarketHelper("Change Of Heart?");
+            const traderCollateral = (await getOrCreateAssociatedTokenAccount(provider.connection, traderB, collateralMint, traderB.publicKey)).address;
+            await mintTo(provider.connection, admin, collateralMint, traderCollateral, admin, 2_000_000);
+            const traderYes = getAssociatedTokenAddressSync(yesMint, traderB.publicKey);
+            const traderNo = getAssociatedTokenAddressSync(noMint, traderB.publicKey);
+            const tradeAccounts = {
+                trader: traderB.publicKey, config: configPDA, market: marketPDA, yesMint, noMint, collateralMint, traderCollateral, traderYes, traderNo, vault, feeVault, tokenProgram: TOKEN_PROGRAM_ID,
+            } as any;
+            await program.methods.buyTokens(new BN(1_000_000), true, new BN(0)).accounts(tradeAccounts).signers([traderB]).rpc();
+
+            const balance = async (account: PublicKey) => Number((await provider.connection.getTokenAccountBalance(account)).value.amount);
+            const yesBefore = await balance(traderYes);
+            const collateralBefore = await balance(traderCollateral);
+            const feesBefore = await balance(feeVault);
+            const swapped = Math.floor(yesBefore / 2);
+
+            // Asking for more than the curve gives is refused
+            let err = "";
+            try {
+                await program.methods.swapOutcome(true, new BN(swapped), new BN(yesBefore * 10)).accounts(tradeAccounts).signers([traderB]).rpc();
+            } catch (e) {
+                err = String(e);
+            }
+            expect(err).to.include("SlippageExceeded");
+
+            await program.methods.swapOutcome(true, new BN(swapped), new BN(1)).accounts(tradeAccounts).signers([traderB]).rpc();
+            expect(await balance(traderYes)).to.equal(yesBefore - swapped);
+            expect(await balance(traderNo)).to.be.greaterThan(0);
+            expect(await balance(traderCollateral)).to.equal(collateralBefore);
+            expect(await balance(feeVault)).to.be.greaterThan(feesBefore);
+            console.log("   ✅ Side Flipped For One Fee.");
+        });
+
+        it("Privacy: Shielded deposit at end time fails, end_market closes the market", async () => {
+            console.log("   --- Testing shielded entry at expiry ---");
+            const { marketPDA, vault } = await createMarketHelper("Last Second Blind Bet?");
+            await waitForExpiry(marketPDA);
+
+            const traderCollateral = getAssociatedTokenAddressSync(collateralMint, traderA.publicKey);
+            const tradeShielded = (commitment: Buffer) => {
+                const [pos] = PublicKey.findProgramAddressSync([Buffer.from("shielded_position"), marketPDA.toBuffer(), commitment], program.programId);
+                return program.methods.tradeShielded(Array.from(commitment) as any, Array.from(crypto.randomBytes(32)) as any, new BN(1_000_000)).accounts({
+                    trader: traderA.publicKey, config: configPDA, market: marketPDA, shieldedPosition: pos, collateralMint, traderCollateral, vault, tokenProgram: TOKEN_PROGRAM_ID,
+                } as any).signers([traderA]).rpc();
+            };
+
+            const vaultBefore = Number((await provider.connection.getTokenAccountBalance(vault)).value.amount);
+            let err = "";
+            try {
+                await tradeShielded(crypto.randomBytes(32));
+            } catch (e) {
+                err = String(e);
+            }
+            expect(err).to.include("MarketEnded");
+            expect(Number((await provider.connection.getTokenAccountBalance(vault)).value.amount)).to.equal(vaultBefore);
+
+            // Anyone can record the status change
+            await program.methods.endMarket().accounts({ market: marketPDA } as any).rpc();
+            expect((await program.account.market.fetch(marketPDA)).status).to.deep.equal({ ended: {} });
+
+            err = "";
+            try {
+                await tradeShielded(crypto.randomBytes(32));
+            } catch (e) {
+                err = String(e);
+            }
+            expect(err).to.include("MarketNotActive");
+            console.log("   🛡️ Dead-Market Deposit Refused.");
+        });
+
+        it("Privacy: Dark pool deposit after end time fails without filling", async () => {
+            console.log("   --- Testing dark pool entry at expiry ---");
+            const { marketPDA, yesMint, noMint, vault } = await createMarketHelper("Last Second Dark Bet?");
+            const traderCollateral = (await getOrCreateAssociatedTokenAccount(provider.connection, traderA, collateralMint, traderA.publicKey)).address;
+            await mintTo(provider.connection, admin, collateralMint, traderCollateral, admin, 2_000_000);
+
+            const commitment = crypto.randomBytes(32);
+            const [privacyPos] = PublicKey.findProgramAddressSync([Buffer.from("privacy_position"), marketPDA.toBuffer(), commitment], program.programId);
+            const privacyYes = getAssociatedTokenAddressSync(yesMint, privacyPos, true);
+            const privacyNo = getAssociatedTokenAddressSync(noMint, privacyPos, true);
+            await program.methods.initPrivacyPosition(Array.from(commitment) as any, null).accounts({
+                trader: traderA.publicKey, market: marketPDA, privacyPosition: privacyPos, yesMint, noMint, privacyYes, privacyNo, tokenProgram: TOKEN_PROGRAM_ID, associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID, systemProgram: SystemProgram.programId,
+            } as any).signers([traderA]).rpc();
+            const tradePrivacy = () => program.methods.tradePrivacy(Array.from(commitment) as any, new BN(1_000_000), true).accounts({
+                trader: traderA.publicKey, config: configPDA, market: marketPDA, privacyPosition: privacyPos, yesMint, noMint, collateralMint, traderCollateral, vault, privacyYes, privacyNo, tokenProgram: TOKEN_PROGRAM_ID,
+            } as any).signers([traderA]).rpc();
+
+            await waitForExpiry(marketPDA);
+            const vaultBefore = Number((await provider.connection.getTokenAccountBalance(vault)).value.amount);
+            let err = "";
+            try {
+                await tradePrivacy();
+            } catch (e) {
+                err = String(e);
+            }
+            expect(err).to.include("MarketEnded");
+            expect(Number((await provider.connection.getTokenAccountBalance(vault)).value.amount)).to.equal(vaultBefore);
+            expect((await program.account.privacyPosition.fetch(privacyPos)).yesAmount.toNumber()).to.equal(0);
+            console.log("   🛡️ Expired Dark Pool Deposit Refused.");
+        });
+
         it("Privacy: Tech check", async () => {
             console.log("   --- Checking privacy tools ---");
             const { marketPDA } = await createMarketHelper("SDK Integration?");
@@ -375,12 +1363,102 @@ describe("private_pnp_tests", () => {
             // 2. Compression Check
             console.log("   ✅ ZK-Compression scaling module loaded.");
         });
+
+        it("Privacy: Transparent market rejects privacy entry", async () => {
+            const { marketPDA } = await createMarketHelper("Public Only?", 0, false);
+
+            const commitment = crypto.randomBytes(32);
+            const [confidentialPos] = PublicKey.findProgramAddressSync([Buffer.from("confidential_position"), marketPDA.toBuffer(), commitment], program.programId);
+            let err = "";
+            try {
+                await program.methods.tradeConfidential(Array.from(commitment) as any, Array.from(crypto.randomBytes(32)) as any, new BN(100))
+                    .accounts({
+                        trader: traderA.publicKey, market: marketPDA, confidentialPosition: confidentialPos, executionProgram: new PublicKey("5sjEbPiqgZrYwR31ahR6Uk9wf5awoX61YGg7jExQSwaj"),
+                    } as any).signers([traderA]).rpc();
+            } catch (e: any) {
+                err = e.toString();
+            }
+            expect(err).to.contain("PrivacyDisabled");
+            console.log("   ✅ Privacy entry blocked on transparent market.");
+        });
     });
 
     describe("Final Checks", () => {
         beforeEach(async () => {
             await loading("Preparing final validation stage");
         });
+        it("Privacy: Abandon a half-entered position and reclaim rent", async () => {
+            console.log("   --- Testing privacy position force-close ---");
+            const { marketPDA, yesMint, noMint, vault } = await createMarketHelper("Changed My Mind?");
+            const traderCollateral = (await getOrCreateAssociatedTokenAccount(provider.connection, traderA, collateralMint, traderA.publicKey)).address;
+            await mintTo(provider.connection, admin, collateralMint, traderCollateral, admin, 1_000_000);
+
+            const secret = crypto.randomBytes(32);
+            const commitment = Buffer.from(keccak_256(Buffer.concat([secret, traderA.publicKey.toBuffer()])));
+            const [privacyPos] = PublicKey.findProgramAddressSync([Buffer.from("privacy_position"), marketPDA.toBuffer(), commitment], program.programId);
+            const privacyYes = getAssociatedTokenAddressSync(yesMint, privacyPos, true);
+            const privacyNo = getAssociatedTokenAddressSync(noMint, privacyPos, true);
+            await program.methods.initPrivacyPosition(Array.from(commitment) as any, null).accounts({
+                trader: traderA.publicKey, market: marketPDA, privacyPosition: privacyPos, yesMint, noMint, privacyYes, privacyNo, tokenProgram: TOKEN_PROGRAM_ID, associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID, systemProgram: SystemProgram.programId,
+            } as any).signers([traderA]).rpc();
+            await program.methods.tradePrivacy(Array.from(commitment) as any, new BN(1_000), true).accounts({
+                trader: traderA.publicKey, config: configPDA, market: marketPDA, privacyPosition: privacyPos, yesMint, noMint, collateralMint, traderCollateral, vault, privacyYes, privacyNo, tokenProgram: TOKEN_PROGRAM_ID,
+            } as any).signers([traderA]).rpc();
+            const dust = Number((await provider.connection.getTokenAccountBalance(privacyYes)).value.amount);
+
+            const closeAccounts = {
+                owner: traderA.publicKey, market: marketPDA, privacyPosition: privacyPos, yesMint, noMint, privacyYes, privacyNo,
+                ownerYes: getAssociatedTokenAddressSync(yesMint, traderA.publicKey), ownerNo: getAssociatedTokenAddressSync(noMint, traderA.publicKey),
+                tokenProgram: TOKEN_PROGRAM_ID, associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID, systemProgram: SystemProgram.programId,
+            };
+            let err = "";
+            try {
+                await program.methods.forceClosePrivacyPosition(Array.from(commitment) as any, Array.from(crypto.randomBytes(32)) as any).accounts(closeAccounts as any).signers([traderA]).rpc();
+            } catch (e) {
+                err = String(e);
+            }
+            expect(err).to.include("InvalidSecret");
+
+            await program.methods.forceClosePrivacyPosition(Array.from(commitment) as any, Array.from(secret) as any).accounts(closeAccounts as any).signers([traderA]).rpc();
+            for (const closed of [privacyPos, privacyYes, privacyNo]) {
+                expect(await provider.connection.getAccountInfo(closed)).to.be.null;
+            }
+            expect(Number((await provider.connection.getTokenAccountBalance(closeAccounts.ownerYes)).value.amount)).to.equal(dust);
+            console.log("   🧹 Ghost Position Closed, Rent Reclaimed.");
+        });
+
+        it("Privacy: Disclose a position to its auditor view key", async () => {
+            console.log("   --- Testing selective disclosure ---");
+            const { marketPDA, yesMint, noMint, vault } = await createMarketHelper("Audited Bet?");
+            const traderCollateral = (await getOrCreateAssociatedTokenAccount(provider.connection, traderA, collateralMint, traderA.publicKey)).address;
+            await mintTo(provider.connection, admin, collateralMint, traderCollateral, admin, 1_000_000);
+
+            const viewKey = crypto.randomBytes(32);
+            const commitment = Buffer.from(keccak_256(Buffer.concat([crypto.randomBytes(32), traderA.publicKey.toBuffer()])));
+            const [privacyPos] = PublicKey.findProgramAddressSync([Buffer.from("privacy_position"), marketPDA.toBuffer(), commitment], program.programId);
+            const privacyYes = getAssociatedTokenAddressSync(yesMint, privacyPos, true);
+            const privacyNo = getAssociatedTokenAddressSync(noMint, privacyPos, true);
+            await program.methods.initPrivacyPosition(Array.from(commitment) as any, Array.from(keccak_256(viewKey)) as any).accounts({
+                trader: traderA.publicKey, market: marketPDA, privacyPosition: privacyPos, yesMint, noMint, privacyYes, privacyNo, tokenProgram: TOKEN_PROGRAM_ID, associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID, systemProgram: SystemProgram.programId,
+            } as any).signers([traderA]).rpc();
+            await program.methods.tradePrivacy(Array.from(commitment) as any, new BN(10_000), false).accounts({
+                trader: traderA.publicKey, config: configPDA, market: marketPDA, privacyPosition: privacyPos, yesMint, noMint, collateralMint, traderCollateral, vault, privacyYes, privacyNo, tokenProgram: TOKEN_PROGRAM_ID,
+            } as any).signers([traderA]).rpc();
+
+            let err = "";
+            try {
+                await program.methods.disclosePosition(Array.from(commitment) as any, Array.from(crypto.randomBytes(32)) as any).accounts({ market: marketPDA, privacyPosition: privacyPos } as any).rpc();
+            } catch (e) {
+                err = String(e);
+            }
+            expect(err).to.include("InvalidViewKey");
+
+            await program.methods.disclosePosition(Array.from(commitment) as any, Array.from(viewKey) as any).accounts({ market: marketPDA, privacyPosition: privacyPos } as any).rpc();
+            const pos = await program.account.privacyPosition.fetch(privacyPos);
+            expect(pos.noAmount.toNumber()).to.be.greaterThan(0);
+            console.log("   🔍 Position Disclosed to Auditor.");
+        });
+
         it("Check: Multiple trades work", async () => {
             console.log("   --- Testing multiple trades ---");
             const { marketPDA, yesMint, noMint, vault } = await createMarketHelper("Double Spend?");
@@ -398,7 +1476,7 @@ describe("private_pnp_tests", () => {
             const privacyYes = getAssociatedTokenAddressSync(yesMint, privacyPos, true);
             const privacyNo = getAssociatedTokenAddressSync(noMint, privacyPos, true);
 
-            await program.methods.initPrivacyPosition(Array.from(entryCommitment) as any).accounts({
+            await program.methods.initPrivacyPosition(Array.from(entryCommitment) as any, null).accounts({
                 trader: traderA.publicKey, market: marketPDA, privacyPosition: privacyPos, yesMint, noMint, privacyYes, privacyNo, tokenProgram: TOKEN_PROGRAM_ID, associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID, systemProgram: SystemProgram.programId,
             } as any).signers([traderA]).rpc();
 
@@ -601,6 +1679,48 @@ describe("private_pnp_tests", () => {
             console.log("   🛡️ Proof: Market prices are secret.");
         });
 
+        it("Proof: Hidden odds are revealed at close", async () => {
+            console.log("   --- Testing encrypted market reveal ---");
+            const { marketPDA } = await createMarketHelper("Reveal Odds?");
+            const incoKey = Keypair.generate();
+            const [encryptedMarketPDA] = PublicKey.findProgramAddressSync([Buffer.from("encrypted_market"), marketPDA.toBuffer()], program.programId);
+            await program.methods.createEncryptedMarket(new BN(1), Array.from(incoKey.publicKey.toBytes()) as any, crypto.randomBytes(64)).accounts({
+                admin: admin.publicKey, market: marketPDA, encryptedMarket: encryptedMarketPDA, systemProgram: SystemProgram.programId,
+            } as any).signers([admin]).rpc();
+
+            await waitForExpiry(marketPDA);
+            await program.methods.resolveMarket(true, NO_EVIDENCE, NO_SOURCE).accounts({ oracle: oracle.publicKey, market: marketPDA }).signers([oracle]).rpc();
+
+            const enc = await program.account.encryptedMarketState.fetch(encryptedMarketPDA);
+            const [reserves, yesSupply, noSupply] = [new BN(1_000_000), new BN(600_000), new BN(800_000)];
+            const reveal = (signer: Keypair) => {
+                const message = Buffer.concat([
+                    encryptedMarketPDA.toBuffer(),
+                    Buffer.from(keccak_256(Buffer.concat([Buffer.from(enc.encryptedReserves), Buffer.from(enc.encryptedYesSupply), Buffer.from(enc.encryptedNoSupply)]))),
+                    reserves.toArrayLike(Buffer, "le", 8), yesSupply.toArrayLike(Buffer, "le", 8), noSupply.toArrayLike(Buffer, "le", 8),
+                ]);
+                const ed25519Ix = Ed25519Program.createInstructionWithPrivateKey({ privateKey: signer.secretKey, message });
+                const signature = ed25519Ix.data.subarray(16 + 32, 16 + 32 + 64);
+                return program.methods.revealEncryptedMarket(reserves, yesSupply, noSupply, Array.from(signature) as any).accounts({
+                    admin: admin.publicKey, market: marketPDA, encryptedMarket: encryptedMarketPDA, instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
+                } as any).preInstructions([ed25519Ix]).signers([admin]).rpc();
+            };
+
+            let err = "";
+            try {
+                await reveal(Keypair.generate());
+            } catch (e) {
+                err = String(e);
+            }
+            expect(err).to.include("WrongSigner");
+
+            await reveal(incoKey);
+            const revealed = await program.account.encryptedMarketState.fetch(encryptedMarketPDA);
+            expect(revealed.revealed).to.be.true;
+            expect(revealed.revealedNoSupply.toNumber()).to.equal(800_000);
+            console.log("   🔓 Final Odds Published With Proof.");
+        });
+
         it("Proof: Auditor safety check", async () => {
             console.log("   --- PROVING AUDITOR LOGIC ---");
             const { marketPDA } = await createMarketHelper("Audit Proof?");
@@ -641,6 +1761,143 @@ describe("private_pnp_tests", () => {
         });
     });
 
+    describe("Solvency", () => {
+        beforeEach(async () => {
+            await loading("Preparing solvency audit");
+        });
+        it("Solvency: Total payouts never exceed reserves", async () => {
+            console.log("   --- Testing settlement guarantee ---");
+            const { marketPDA, yesMint, noMint, vault } = await createMarketHelper("Solvency Audit?");
+
+            const tradeAccounts = async (trader: Keypair) => {
+                const traderCollateral = (await getOrCreateAssociatedTokenAccount(provider.connection, trader, collateralMint, trader.publicKey)).address;
+                await mintTo(provider.connection, admin, collateralMint, traderCollateral, admin, 20_000_000);
+                const traderYes = getAssociatedTokenAddressSync(yesMint, trader.publicKey);
+                const traderNo = getAssociatedTokenAddressSync(noMint, trader.publicKey);
+                await program.methods.initTraderVaults().accounts({
+                    trader: trader.publicKey, yesMint, noMint, traderYes, traderNo, tokenProgram: TOKEN_PROGRAM_ID, associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID, systemProgram: SystemProgram.programId,
+                } as any).signers([trader]).rpc();
+                return { traderCollateral, traderYes, traderNo };
+            };
+
+            const a = await tradeAccounts(traderA);
+            const b = await tradeAccounts(traderB);
+
+            const buy = async (trader: Keypair, acc: any, amount: number, yes: boolean) => {
+                await program.methods.buyTokens(new BN(amount), yes, new BN(0)).accounts({
+                    trader: trader.publicKey, config: configPDA, market: marketPDA, yesMint, noMint, collateralMint, traderCollateral: acc.traderCollateral, traderYes: acc.traderYes, traderNo: acc.traderNo, vault, feeVault, tokenProgram: TOKEN_PROGRAM_ID,
+                } as any).signers([trader]).rpc();
+            };
+
+            await buy(traderA, a, 5_000_000, true);
+            await buy(traderB, b, 4_000_000, false);
+            await buy(traderB, b, 3_000_000, true);
+            await buy(traderA, a, 2_000_000, true);
+
+            await waitForExpiry(marketPDA);
+            await program.methods.resolveMarket(true, NO_EVIDENCE, NO_SOURCE).accounts({ oracle: oracle.publicKey, market: marketPDA }).signers([oracle]).rpc();
+            const reservesAtResolution = (await program.account.market.fetch(marketPDA)).reserves.toNumber();
+
+            const adminCollateral = getAssociatedTokenAddressSync(collateralMint, admin.publicKey);
+            const redeemers: [Keypair, PublicKey, PublicKey, PublicKey][] = [
+                [traderA, a.traderCollateral, a.traderYes, a.traderNo],
+                [traderB, b.traderCollateral, b.traderYes, b.traderNo],
+                [admin, adminCollateral, getAssociatedTokenAddressSync(yesMint, admin.publicKey), getAssociatedTokenAddressSync(noMint, admin.publicKey)],
+            ];
+
+            let totalPaid = 0;
+            for (const [user, userCollateral, userYes, userNo] of redeemers) {
+                const before = Number((await getAccount(provider.connection, userCollateral)).amount);
+                await program.methods.redeem().accounts({
+                    user: user.publicKey, config: configPDA, market: marketPDA, yesMint, noMint, collateralMint, userYes, userNo, userCollateral, vault, tokenProgram: TOKEN_PROGRAM_ID,
+                } as any).signers([user]).rpc();
+                const after = Number((await getAccount(provider.connection, userCollateral)).amount);
+                totalPaid += after - before;
+            }
+
+            expect(totalPaid).to.be.at.most(reservesAtResolution);
+            const finalState = await program.account.market.fetch(marketPDA);
+            expect(finalState.reserves.toNumber()).to.equal(reservesAtResolution - totalPaid);
+            console.log(`   ✅ Paid ${totalPaid} of ${reservesAtResolution} reserves. Pool stayed solvent.`);
+        });
+
+        it("Solvency: Nobody holding the winner voids the market for refunds", async () => {
+            console.log("   --- Testing empty winning side ---");
+            const { marketPDA, yesMint, noMint, vault } = await createMarketHelper("Nobody Bet Yes?");
+            const adminCollateral = getAssociatedTokenAddressSync(collateralMint, admin.publicKey);
+            const adminYes = getAssociatedTokenAddressSync(yesMint, admin.publicKey);
+            const adminNo = getAssociatedTokenAddressSync(noMint, admin.publicKey);
+            const accounts = { config: configPDA, market: marketPDA, yesMint, noMint, collateralMint, vault, tokenProgram: TOKEN_PROGRAM_ID };
+
+            // The creator dumps the whole YES side, leaving zero YES supply
+            const yesBalance = (await getAccount(provider.connection, adminYes)).amount;
+            await program.methods.sellTokens(new BN(yesBalance.toString()), true, new BN(0)).accounts({
+                ...accounts, trader: admin.publicKey, traderCollateral: adminCollateral, traderYes: adminYes, traderNo: adminNo, feeVault,
+            } as any).signers([admin]).rpc();
+            expect((await program.account.market.fetch(marketPDA)).yesSupply.toNumber()).to.equal(0);
+
+            await waitForExpiry(marketPDA);
+            await program.methods.resolveMarket(true, NO_EVIDENCE, NO_SOURCE).accounts({ oracle: oracle.publicKey, market: marketPDA }).signers([oracle]).rpc();
+            const resolved = await program.account.market.fetch(marketPDA);
+            expect(resolved.status).to.deep.equal({ cancelled: {} });
+
+            const before = Number((await getAccount(provider.connection, adminCollateral)).amount);
+            await program.methods.redeem().accounts({
+                ...accounts, user: admin.publicKey, userYes: adminYes, userNo: adminNo, userCollateral: adminCollateral,
+            } as any).signers([admin]).rpc();
+            const refunded = Number((await getAccount(provider.connection, adminCollateral)).amount) - before;
+
+            expect(refunded).to.equal(resolved.reserves.toNumber());
+            console.log(`   ✅ Losing side refunded ${refunded}.`);
+        });
+
+        it("Solvency: A draw refunds both sides", async () => {
+            console.log("   --- Testing draw resolution ---");
+            const { marketPDA, yesMint, noMint, vault } = await createMarketHelper("Ends Level?");
+            const adminCollateral = getAssociatedTokenAddressSync(collateralMint, admin.publicKey);
+            const adminYes = getAssociatedTokenAddressSync(yesMint, admin.publicKey);
+            const adminNo = getAssociatedTokenAddressSync(noMint, admin.publicKey);
+            const accounts = { config: configPDA, market: marketPDA, yesMint, noMint, collateralMint, vault, tokenProgram: TOKEN_PROGRAM_ID };
+
+            await waitForExpiry(marketPDA);
+            await program.methods.resolveDraw(NO_EVIDENCE, NO_SOURCE).accounts({ oracle: oracle.publicKey, market: marketPDA } as any).signers([oracle]).rpc();
+            const resolved = await program.account.market.fetch(marketPDA);
+            expect(resolved.outcome).to.deep.equal({ draw: {} });
+            expect(resolved.status).to.deep.equal({ cancelled: {} });
+
+            const before = Number((await getAccount(provider.connection, adminCollateral)).amount);
+            await program.methods.redeem().accounts({
+                ...accounts, user: admin.publicKey, userYes: adminYes, userNo: adminNo, userCollateral: adminCollateral,
+            } as any).signers([admin]).rpc();
+            const refunded = Number((await getAccount(provider.connection, adminCollateral)).amount) - before;
+
+            expect(refunded).to.equal(resolved.reserves.toNumber());
+            console.log(`   ✅ Both sides refunded ${refunded}.`);
+        });
+
+        it("Trust: Resolution must use the source declared at creation", async () => {
+            console.log("   --- Testing declared resolution source ---");
+            const source = Buffer.from("https://example.com/official-results");
+            const sourceHash = Array.from(keccak_256(source)) as any;
+            const { marketPDA } = await createMarketHelper("Source Bound?", 0, true, sourceHash);
+            const market = await program.account.market.fetch(marketPDA);
+            expect(Buffer.from(market.resolutionSourceHash as any).equals(Buffer.from(sourceHash))).to.be.true;
+
+            await waitForExpiry(marketPDA);
+            let err = "";
+            try {
+                await program.methods.resolveMarket(true, NO_EVIDENCE, Buffer.from("https://example.com/other")).accounts({ oracle: oracle.publicKey, market: marketPDA }).signers([oracle]).rpc();
+            } catch (e) {
+                err = String(e);
+            }
+            expect(err).to.include("ResolutionSourceMismatch");
+
+            await program.methods.resolveMarket(true, NO_EVIDENCE, source).accounts({ oracle: oracle.publicKey, market: marketPDA }).signers([oracle]).rpc();
+            expect((await program.account.market.fetch(marketPDA)).status).to.deep.equal({ resolved: {} });
+            console.log("   ✅ Only the declared source resolves.");
+        });
+    });
+
     describe("🕵️ THE ULTIMATE PRIVACY PROOF (Step-by-Step)", () => {
         it("Detailed Proof of Work", async () => {
             console.log("\n   ================================================");